@@ -0,0 +1,407 @@
+//! `fraxtal-op-reth bootnodes` subcommands.
+
+use std::{
+    future::Future,
+    net::{Ipv4Addr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use alloy_primitives::FixedBytes;
+use clap::{Args, Subcommand};
+use eyre::{OptionExt, WrapErr};
+use fraxtal_chainspec::{chain_value_parser, fraxtal_bootnodes};
+use futures_util::StreamExt;
+use reth_chainspec::{EthChainSpec, Hardforks};
+use reth_discv4::{DiscoveryUpdate, Discv4, Discv4Config};
+use reth_discv5::{
+    BootNode, Discv5,
+    discv5::{ConfigBuilder, ListenConfig},
+};
+use reth_ecies::stream::ECIESStream;
+use reth_eth_wire::{
+    EthNetworkPrimitives, HelloMessageWithProtocols, UnauthedEthStream, UnauthedP2PStream,
+    UnifiedStatus,
+};
+use reth_ethereum_forks::{ForkId, Head};
+use reth_network::config::{SecretKey, rng_secret_key};
+use reth_network_peers::NodeRecord;
+use reth_optimism_chainspec::OpChainSpec;
+use serde::Serialize;
+use tokio::{net::TcpStream, task::JoinSet};
+
+/// `fraxtal-op-reth bootnodes` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum BootnodesCommand {
+    /// Probe the built-in bootnodes of a chain over discv4, discv5 and RLPx, and report which of
+    /// them are reachable.
+    Check(CheckCommand),
+}
+
+impl BootnodesCommand {
+    /// Runs the command.
+    pub fn run(self) -> eyre::Result<()> {
+        match self {
+            Self::Check(command) => command.run(),
+        }
+    }
+}
+
+/// Probes the bootnodes of a chain.
+#[derive(Debug, Args)]
+pub struct CheckCommand {
+    /// The chain whose bootnodes are probed.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(long, value_name = "CHAIN_OR_PATH", default_value = "fraxtal", value_parser = chain_value_parser)]
+    chain: Arc<OpChainSpec>,
+
+    /// Seconds to wait for each probe of a bootnode.
+    #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+    timeout: u64,
+
+    /// Print the report as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl CheckCommand {
+    /// Runs the command.
+    pub fn run(self) -> eyre::Result<()> {
+        let chain_id = self.chain.chain().id();
+        let bootnodes = fraxtal_bootnodes(&*self.chain)
+            .filter(|nodes| !nodes.is_empty())
+            .ok_or_eyre(format!("chain {chain_id} has no built-in bootnodes"))?;
+        let head = latest_head(&self.chain);
+        let timeout = Duration::from_secs(self.timeout);
+
+        let tokio = tokio::runtime::Runtime::new()?;
+        let bootnodes = tokio.block_on(async {
+            let mut probes = JoinSet::new();
+            for (index, record) in bootnodes.into_iter().enumerate() {
+                let chain = self.chain.clone();
+                probes.spawn(async move {
+                    (index, BootnodeReport::probe(record, &chain, head, timeout).await)
+                });
+            }
+            let mut reports = probes.join_all().await;
+            reports.sort_by_key(|(index, _)| *index);
+            reports.into_iter().map(|(_, report)| report).collect()
+        });
+
+        let report =
+            CheckReport { chain_id, local_fork_id: self.chain.fork_id(&head).into(), bootnodes };
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            report.print();
+        }
+        Ok(())
+    }
+}
+
+/// Returns the head the local fork id is computed at: genesis, at the current time.
+///
+/// The forks of the OP Stack chains after Bedrock activate by timestamp, so this is the fork id
+/// a synced node advertises.
+fn latest_head(spec: &OpChainSpec) -> Head {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+    Head {
+        number: spec.genesis_header().number,
+        hash: spec.genesis_hash(),
+        timestamp: now,
+        ..Default::default()
+    }
+}
+
+/// Result of probing the bootnodes of a chain.
+#[derive(Debug, Serialize)]
+struct CheckReport {
+    chain_id: u64,
+    local_fork_id: ForkIdInfo,
+    bootnodes: Vec<BootnodeReport>,
+}
+
+/// Result of probing a single bootnode.
+#[derive(Debug, Serialize)]
+struct BootnodeReport {
+    enode: String,
+    node_id: String,
+    discv4: Probe,
+    discv5: Probe,
+    rlpx: Probe,
+    /// Client version of the RLPx hello message.
+    client_version: Option<String>,
+    /// Fork id of the eth status message.
+    fork_id: Option<ForkIdInfo>,
+}
+
+/// Result of a single probe.
+#[derive(Debug, Serialize)]
+struct Probe {
+    reachable: bool,
+    latency_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// What a bootnode advertises in the RLPx and eth handshakes.
+#[derive(Debug)]
+struct RlpxInfo {
+    client_version: String,
+    fork_id: Option<ForkIdInfo>,
+}
+
+/// An EIP-2124 fork id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+struct ForkIdInfo {
+    hash: FixedBytes<4>,
+    next: u64,
+}
+
+impl From<ForkId> for ForkIdInfo {
+    fn from(fork_id: ForkId) -> Self {
+        Self { hash: FixedBytes(fork_id.hash.0), next: fork_id.next }
+    }
+}
+
+impl std::fmt::Display for ForkIdInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (next {})", self.hash, self.next)
+    }
+}
+
+impl BootnodeReport {
+    /// Probes `record` over discv4, discv5 and RLPx.
+    async fn probe(record: NodeRecord, spec: &OpChainSpec, head: Head, timeout: Duration) -> Self {
+        let secret_key = rng_secret_key();
+        let ((discv4, _), (discv5, _), (rlpx, info)) = futures_util::join!(
+            Probe::run(timeout, ping_discv4(record, secret_key)),
+            Probe::run(timeout, ping_discv5(record, secret_key)),
+            Probe::run(timeout, handshake_rlpx(record, secret_key, spec, head)),
+        );
+        let (client_version, fork_id) =
+            info.map_or((None, None), |info| (Some(info.client_version), info.fork_id));
+        Self {
+            enode: record.to_string(),
+            node_id: record.id.to_string(),
+            discv4,
+            discv5,
+            rlpx,
+            client_version,
+            fork_id,
+        }
+    }
+}
+
+impl Probe {
+    /// Runs `probe` with a timeout.
+    ///
+    /// The probe returns its latency, measured from when it first contacts the bootnode, so the
+    /// time spent setting up the local discovery services is not counted.
+    async fn run<T>(
+        timeout: Duration,
+        probe: impl Future<Output = eyre::Result<(T, Duration)>>,
+    ) -> (Self, Option<T>) {
+        let result = match tokio::time::timeout(timeout, probe).await {
+            Ok(result) => result,
+            Err(_) => Err(eyre::eyre!("timed out after {}s", timeout.as_secs())),
+        };
+        match result {
+            Ok((output, latency)) => {
+                let latency_ms = latency.as_millis() as u64;
+                (Self { reachable: true, latency_ms: Some(latency_ms), error: None }, Some(output))
+            }
+            Err(err) => {
+                (Self { reachable: false, latency_ms: None, error: Some(format!("{err:#}")) }, None)
+            }
+        }
+    }
+
+    /// Returns the latency, or the error, of the probe.
+    fn summary(&self) -> String {
+        match (self.latency_ms, &self.error) {
+            (Some(latency), _) => format!("{latency}ms"),
+            (None, Some(error)) => format!("unreachable ({error})"),
+            (None, None) => "unreachable".to_string(),
+        }
+    }
+}
+
+/// Pings `record` over discv4, returning the round trip time once it answered with a pong.
+async fn ping_discv4(record: NodeRecord, secret_key: SecretKey) -> eyre::Result<((), Duration)> {
+    let local_addr = SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0));
+    let local_record = NodeRecord::from_secret_key(local_addr, &secret_key);
+    let config = Discv4Config::builder().enable_lookup(false).build();
+    let (discv4, mut service) = Discv4::bind(local_addr, local_record, secret_key, config)
+        .await
+        .wrap_err("failed to bind discv4")?;
+    let mut updates = service.update_stream();
+    let _service = service.spawn();
+
+    // Adding the node pings it, and it is only reported as added once it answered.
+    let start = Instant::now();
+    discv4.add_node(record);
+    while let Some(update) = updates.next().await {
+        if let DiscoveryUpdate::Added(node) | DiscoveryUpdate::DiscoveredAtCapacity(node) = update
+            && node.id == record.id
+        {
+            return Ok(((), start.elapsed()));
+        }
+    }
+    eyre::bail!("discv4 service stopped")
+}
+
+/// Requests the ENR of `record` over discv5, which requires a complete discv5 handshake,
+/// returning the time it took.
+async fn ping_discv5(record: NodeRecord, secret_key: SecretKey) -> eyre::Result<((), Duration)> {
+    let listen = ListenConfig::from_ip(Ipv4Addr::UNSPECIFIED.into(), 0);
+    let config = reth_discv5::Config::builder(SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0)))
+        .discv5_config(ConfigBuilder::new(listen).build())
+        .build();
+    let (discv5, _events) =
+        Discv5::start(&secret_key, config).await.wrap_err("failed to start discv5")?;
+
+    let BootNode::Enode(addr) = BootNode::from_unsigned(record)? else {
+        eyre::bail!("bootnode {} can't be converted to a discv5 enode", record.id)
+    };
+    let start = Instant::now();
+    discv5
+        .with_discv5(|discv5| discv5.request_enr(addr.to_string()))
+        .await
+        .map_err(|err| eyre::eyre!("{err:?}"))?;
+    Ok(((), start.elapsed()))
+}
+
+/// Connects to `record` over RLPx, returning the client version of its hello message and the
+/// fork id of its eth status, with the time the RLPx handshake took.
+///
+/// The fork id is missing if the eth handshake fails, e.g. because the node is on another chain.
+async fn handshake_rlpx(
+    record: NodeRecord,
+    secret_key: SecretKey,
+    spec: &OpChainSpec,
+    head: Head,
+) -> eyre::Result<(RlpxInfo, Duration)> {
+    let start = Instant::now();
+    let stream = TcpStream::connect(record.tcp_addr()).await.wrap_err("failed to connect")?;
+    let ecies = ECIESStream::connect(stream, secret_key, record.id)
+        .await
+        .wrap_err("ECIES handshake failed")?;
+
+    let local_id = NodeRecord::from_secret_key(record.tcp_addr(), &secret_key).id;
+    let hello = HelloMessageWithProtocols::builder(local_id).build();
+    let (p2p, their_hello) =
+        UnauthedP2PStream::new(ecies).handshake(hello).await.wrap_err("p2p handshake failed")?;
+    let client_version = their_hello.client_version;
+    let latency = start.elapsed();
+
+    let Ok(eth_version) = p2p.shared_capabilities().eth_version() else {
+        return Ok((RlpxInfo { client_version, fork_id: None }, latency));
+    };
+    let mut status = UnifiedStatus::spec_builder(spec, &head).build();
+    status.version = eth_version;
+    let fork_id = UnauthedEthStream::new(p2p)
+        .handshake::<EthNetworkPrimitives>(status, spec.fork_filter(head))
+        .await
+        .ok()
+        .map(|(_, their_status)| their_status.forkid.into());
+    Ok((RlpxInfo { client_version, fork_id }, latency))
+}
+
+impl CheckReport {
+    /// Returns the index of the first bootnode with the same node id as the one at `index`, if
+    /// it is listed earlier.
+    ///
+    /// The same node is often listed once per discovery port.
+    fn duplicate_of(&self, index: usize) -> Option<usize> {
+        let node_id = &self.bootnodes[index].node_id;
+        self.bootnodes[..index].iter().position(|node| &node.node_id == node_id)
+    }
+
+    fn print(&self) {
+        println!("Chain id:      {}", self.chain_id);
+        println!("Local fork id: {}", self.local_fork_id);
+
+        for (index, node) in self.bootnodes.iter().enumerate() {
+            println!();
+            println!("{}", node.enode);
+            if let Some(first) = self.duplicate_of(index) {
+                println!("  same node as bootnode #{}", first + 1);
+            }
+            println!("  discv4: {}", node.discv4.summary());
+            println!("  discv5: {}", node.discv5.summary());
+            println!("  rlpx:   {}", node.rlpx.summary());
+            if let Some(client_version) = &node.client_version {
+                println!("  client: {client_version}");
+                match node.fork_id {
+                    Some(fork_id) if fork_id == self.local_fork_id => {
+                        println!("  fork:   {fork_id}");
+                    }
+                    Some(fork_id) => println!("  fork:   {fork_id}, differs from local"),
+                    None => println!("  fork:   eth handshake failed"),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const NODE_A: &str = "enode://3628cd2691d1fded97bc02ac312dea9cf77e0f2a3f3ec682acc8fea6029b56c0d56940f0d6960fb58ae198929499ab7965dd6826b6a7056c5495b4300980265b@44.237.102.237:30301";
+    const NODE_A_ALT_PORT: &str = "enode://3628cd2691d1fded97bc02ac312dea9cf77e0f2a3f3ec682acc8fea6029b56c0d56940f0d6960fb58ae198929499ab7965dd6826b6a7056c5495b4300980265b@44.237.102.237:9200";
+    const NODE_B: &str = "enode://ef3189491c952c132722b8909390a1cb136a2067a72cdb1b8f236f021aad024c3e50841b8e566f14d7b5cea0ba44d9010c07e5a20d497ddccb5ff7a6e35b73bd@44.214.254.33:30301";
+
+    const FORK_ID: ForkIdInfo = ForkIdInfo { hash: FixedBytes([1, 2, 3, 4]), next: 0 };
+
+    fn report(enode: &str) -> BootnodeReport {
+        let record: NodeRecord = enode.parse().unwrap();
+        BootnodeReport {
+            enode: record.to_string(),
+            node_id: record.id.to_string(),
+            discv4: Probe { reachable: true, latency_ms: Some(12), error: None },
+            discv5: Probe { reachable: false, latency_ms: None, error: Some("timed out".into()) },
+            rlpx: Probe { reachable: true, latency_ms: Some(40), error: None },
+            client_version: Some("op-reth/v2.1.0".into()),
+            fork_id: Some(FORK_ID),
+        }
+    }
+
+    #[test]
+    fn serializes_report() {
+        let node = report(NODE_B);
+        let report =
+            CheckReport { chain_id: 252, local_fork_id: FORK_ID, bootnodes: vec![report(NODE_B)] };
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            json!({
+                "chain_id": 252,
+                "local_fork_id": { "hash": "0x01020304", "next": 0 },
+                "bootnodes": [{
+                    "enode": node.enode,
+                    "node_id": node.node_id,
+                    "discv4": { "reachable": true, "latency_ms": 12 },
+                    "discv5": { "reachable": false, "latency_ms": null, "error": "timed out" },
+                    "rlpx": { "reachable": true, "latency_ms": 40 },
+                    "client_version": "op-reth/v2.1.0",
+                    "fork_id": { "hash": "0x01020304", "next": 0 },
+                }],
+            })
+        );
+    }
+
+    #[test]
+    fn detects_duplicate_nodes() {
+        let report = CheckReport {
+            chain_id: 252,
+            local_fork_id: FORK_ID,
+            bootnodes: vec![report(NODE_A), report(NODE_B), report(NODE_A_ALT_PORT)],
+        };
+        assert_eq!(report.duplicate_of(0), None);
+        assert_eq!(report.duplicate_of(1), None);
+        assert_eq!(report.duplicate_of(2), Some(0));
+    }
+}
@@ -0,0 +1,152 @@
+//! `fraxtal-op-reth chain` subcommand.
+
+use std::sync::Arc;
+
+use alloy_primitives::B256;
+use clap::Args;
+use fraxtal_chainspec::{chain_value_parser, fraxtal_bootnodes, fraxtal_dns_networks};
+use fraxtal_op_evm::block::{IrregularStateTransition, MigrationPlan};
+use reth_chainspec::{EthChainSpec, ForkCondition, Hardforks};
+use reth_optimism_chainspec::OpChainSpec;
+use reth_optimism_forks::OpHardfork;
+use serde::Serialize;
+
+/// Prints the chain id, genesis hash, hardfork schedule, fork migrations and bootnodes of a chain.
+#[derive(Debug, Args)]
+pub struct ChainCommand {
+    /// The chain to inspect.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(value_name = "CHAIN_OR_PATH", default_value = "fraxtal", value_parser = chain_value_parser)]
+    chain: Arc<OpChainSpec>,
+
+    /// Print the chain as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl ChainCommand {
+    /// Runs the command.
+    pub fn run(self) -> eyre::Result<()> {
+        let info = ChainInfo::new(&self.chain);
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&info)?);
+        } else {
+            info.print();
+        }
+        Ok(())
+    }
+}
+
+/// Summary of a chain spec.
+#[derive(Debug, Serialize)]
+struct ChainInfo {
+    chain_id: u64,
+    genesis_hash: B256,
+    hardforks: Vec<HardforkInfo>,
+    bootnodes: Vec<String>,
+    dns_networks: Vec<String>,
+}
+
+/// Activation and migrations of a single OP Stack fork.
+#[derive(Debug, Serialize)]
+struct HardforkInfo {
+    name: String,
+    activation: Activation,
+    migrations: Vec<IrregularStateTransition>,
+}
+
+/// Activation condition of a fork.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Activation {
+    Block(u64),
+    Timestamp(u64),
+}
+
+impl ChainInfo {
+    fn new(spec: &OpChainSpec) -> Self {
+        let chain_id = spec.chain().id();
+        let plan = MigrationPlan::builtin(chain_id);
+        let hardforks = OpHardfork::VARIANTS
+            .iter()
+            .copied()
+            .filter_map(|fork| {
+                let activation = match spec.fork(fork) {
+                    ForkCondition::Block(block) => Activation::Block(block),
+                    ForkCondition::Timestamp(timestamp) => Activation::Timestamp(timestamp),
+                    ForkCondition::TTD { .. } | ForkCondition::Never => return None,
+                };
+                let migrations = MigrationPlan::op_stack()
+                    .transitions(fork)
+                    .chain(plan.transitions(fork))
+                    .cloned()
+                    .collect();
+                Some(HardforkInfo { name: fork.to_string(), activation, migrations })
+            })
+            .collect();
+        let bootnodes =
+            fraxtal_bootnodes(spec).unwrap_or_default().iter().map(ToString::to_string).collect();
+
+        let dns_networks = fraxtal_dns_networks(spec).unwrap_or_default();
+
+        Self { chain_id, genesis_hash: spec.genesis_hash(), hardforks, bootnodes, dns_networks }
+    }
+
+    fn print(&self) {
+        println!("Chain id:     {}", self.chain_id);
+        println!("Genesis hash: {}", self.genesis_hash);
+
+        println!();
+        println!("Hardforks:");
+        for fork in &self.hardforks {
+            let activation = match fork.activation {
+                Activation::Block(block) => format!("block {block}"),
+                Activation::Timestamp(timestamp) => format!("timestamp {timestamp}"),
+            };
+            println!("  {:<10} {activation}", fork.name);
+            for transition in &fork.migrations {
+                println!("    - {}", describe(transition));
+            }
+        }
+
+        println!();
+        if self.bootnodes.is_empty() {
+            println!("Bootnodes: none");
+        } else {
+            println!("Bootnodes:");
+            for node in &self.bootnodes {
+                println!("  {node}");
+            }
+        }
+        if !self.dns_networks.is_empty() {
+            println!();
+            println!("DNS discovery trees:");
+            for link in &self.dns_networks {
+                println!("  {link}");
+            }
+        }
+    }
+}
+
+/// Returns a one-line description of a transition.
+fn describe(transition: &IrregularStateTransition) -> String {
+    match transition {
+        IrregularStateTransition::ProxyUpgrade(upgrade) => format!(
+            "proxy upgrade of {} (implementation {})",
+            upgrade.address, upgrade.implementation
+        ),
+        IrregularStateTransition::BytecodePatch(patch) => format!(
+            "bytecode patch of {} ({} bytes at offset {})",
+            patch.address,
+            patch.value.len(),
+            patch.offset
+        ),
+        IrregularStateTransition::StorageWrite(write) => {
+            format!("storage write of {} (slot {})", write.address, write.slot)
+        }
+        IrregularStateTransition::ForceDeploy(deploy) => {
+            format!("force deploy to {} ({} bytes)", deploy.address, deploy.code.len())
+        }
+    }
+}
@@ -0,0 +1,137 @@
+//! `fraxtal-op-reth migrations` subcommands.
+
+use std::{path::PathBuf, sync::Arc};
+
+use alloy_primitives::B256;
+use clap::{Args, Subcommand};
+use eyre::WrapErr;
+use fraxtal_chainspec::chain_value_parser;
+use fraxtal_node::{migrations::load_migration_plan, node::FraxtalNode};
+use fraxtal_op_evm::block::{
+    IrregularStateTransition, MigrationPlan, StateDiff, diff::apply_with_diff,
+};
+use reth_chainspec::EthChainSpec;
+use reth_optimism_chainspec::OpChainSpec;
+use reth_optimism_forks::OpHardfork;
+use reth_provider::{BlockNumReader, StateProviderFactory, providers::ReadOnlyConfig};
+use reth_revm::database::StateProviderDatabase;
+use revm::database::State;
+
+/// `fraxtal-op-reth migrations` subcommands.
+#[derive(Debug, Subcommand)]
+pub enum MigrationsCommand {
+    /// Apply the migrations of a fork to a historical state without committing anything, and
+    /// print the resulting state diff.
+    Preview(PreviewCommand),
+}
+
+impl MigrationsCommand {
+    /// Runs the command.
+    pub fn run(self) -> eyre::Result<()> {
+        match self {
+            Self::Preview(command) => command.run(),
+        }
+    }
+}
+
+/// Dry-runs the migrations of a fork.
+#[derive(Debug, Args)]
+pub struct PreviewCommand {
+    /// The chain this node is running.
+    ///
+    /// Possible values are either a built-in chain or the path to a chain specification file.
+    #[arg(long, value_name = "CHAIN_OR_PATH", default_value = "fraxtal", value_parser = chain_value_parser)]
+    chain: Arc<OpChainSpec>,
+
+    /// The path to the data dir of the node, opened read-only.
+    #[arg(long, value_name = "DATA_DIR")]
+    datadir: PathBuf,
+
+    /// The fork whose migrations are applied, e.g. `granite`.
+    #[arg(long)]
+    fork: OpHardfork,
+
+    /// The block whose post-state the migrations are applied to, i.e. the parent of the fork
+    /// activation block. Defaults to the latest block.
+    #[arg(long)]
+    block: Option<u64>,
+
+    /// Path to a TOML or JSON migration plan replacing the built-in migrations of the chain.
+    #[arg(long = "fraxtal.migration-plan", value_name = "PATH")]
+    migration_plan: Option<PathBuf>,
+
+    /// Print the diff as JSON.
+    #[arg(long)]
+    json: bool,
+}
+
+impl PreviewCommand {
+    /// Runs the command.
+    pub fn run(self) -> eyre::Result<()> {
+        let plan = match &self.migration_plan {
+            Some(path) => load_migration_plan(path)?,
+            None => MigrationPlan::builtin(self.chain.chain().id()).clone(),
+        };
+        let transitions: Vec<&IrregularStateTransition> = MigrationPlan::op_stack()
+            .transitions(self.fork)
+            .chain(plan.transitions(self.fork))
+            .collect();
+
+        let tokio = tokio::runtime::Runtime::new()?;
+        let runtime = reth_tasks::Runtime::with_existing_handle(tokio.handle().clone())?;
+        let factory = FraxtalNode::provider_factory_builder()
+            .open_read_only(
+                self.chain.clone(),
+                ReadOnlyConfig::from_datadir(&self.datadir),
+                runtime,
+            )
+            .wrap_err_with(|| format!("failed to open datadir {}", self.datadir.display()))?;
+
+        let block = match self.block {
+            Some(block) => block,
+            None => factory.best_block_number()?,
+        };
+        let state = factory
+            .history_by_block_number(block)
+            .wrap_err_with(|| format!("state at block {block} is not available"))?;
+        let mut db = State::builder().with_database(StateProviderDatabase::new(state)).build();
+
+        let diff = apply_with_diff(transitions, &mut db)
+            .map_err(|err| eyre::eyre!("failed to apply migrations: {err}"))?;
+
+        if self.json {
+            println!("{}", serde_json::to_string_pretty(&diff)?);
+        } else {
+            print_diff(self.fork, block, &diff);
+        }
+
+        Ok(())
+    }
+}
+
+fn print_diff(fork: OpHardfork, block: u64, diff: &StateDiff) {
+    if diff.is_empty() {
+        println!("No migrations scheduled for {fork}");
+        return;
+    }
+
+    println!("{fork} migrations applied on top of block {block}:");
+    for account in &diff.accounts {
+        println!();
+        println!("{}", account.address);
+        if account.code_changed() {
+            println!(
+                "  code hash: {} -> {}",
+                display_code_hash(account.code_hash_before),
+                display_code_hash(account.code_hash_after)
+            );
+        }
+        for slot in &account.storage {
+            println!("  slot {}: {:#x} -> {:#x}", slot.slot, slot.before, slot.after);
+        }
+    }
+}
+
+fn display_code_hash(code_hash: Option<B256>) -> String {
+    code_hash.map_or_else(|| "<none>".to_string(), |hash| hash.to_string())
+}
@@ -0,0 +1,105 @@
+//! Fraxtal-specific subcommands of `fraxtal-op-reth`.
+//!
+//! These extend the upstream op-reth CLI: the [`FraxtalCommand`] variants are registered as
+//! additional subcommands of its clap command, so they share its global arguments and help.
+
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Subcommand};
+use fraxtal_chainspec::FraxtalChainSpecParser;
+use fraxtal_node::args::FraxtalArgs;
+use reth_optimism_cli::Cli;
+
+pub mod bootnodes;
+pub mod chain;
+pub mod migrations;
+
+/// The upstream op-reth CLI running Fraxtal nodes.
+pub type RethCli = Cli<FraxtalChainSpecParser, FraxtalArgs>;
+
+/// Command line of `fraxtal-op-reth`: the upstream op-reth CLI extended with the Fraxtal-specific
+/// subcommands.
+#[derive(Debug)]
+pub enum FraxtalCli {
+    /// A Fraxtal-specific subcommand.
+    Fraxtal(FraxtalCommand),
+    /// An upstream op-reth subcommand.
+    Reth(Box<RethCli>),
+}
+
+impl FraxtalCli {
+    /// Returns the clap command of the upstream CLI with the Fraxtal subcommands registered.
+    pub fn command() -> clap::Command {
+        FraxtalCommand::augment_subcommands(RethCli::command())
+    }
+
+    /// Parses the process arguments, exiting on error or when help or the version is requested.
+    pub fn parse() -> Self {
+        Self::from_matches(&Self::command().get_matches()).unwrap_or_else(|err| err.exit())
+    }
+
+    /// Parses the given arguments, the first being the binary name.
+    pub fn try_parse_from<I, T>(args: I) -> Result<Self, clap::Error>
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<std::ffi::OsString> + Clone,
+    {
+        Self::from_matches(&Self::command().try_get_matches_from(args)?)
+    }
+
+    fn from_matches(matches: &ArgMatches) -> Result<Self, clap::Error> {
+        if matches.subcommand_name().is_some_and(FraxtalCommand::has_subcommand) {
+            FraxtalCommand::from_arg_matches(matches).map(Self::Fraxtal)
+        } else {
+            RethCli::from_arg_matches(matches).map(|cli| Self::Reth(Box::new(cli)))
+        }
+        .map_err(|err| err.format(&mut Self::command()))
+    }
+}
+
+/// Fraxtal-specific subcommands.
+#[derive(Debug, Subcommand)]
+pub enum FraxtalCommand {
+    /// Check the health of the built-in bootnodes of a chain.
+    #[command(subcommand)]
+    Bootnodes(bootnodes::BootnodesCommand),
+    /// Print the chain id, genesis hash, hardfork schedule, fork migrations and bootnodes of a
+    /// chain.
+    Chain(chain::ChainCommand),
+    /// Inspect the irregular state transitions applied at fork boundaries.
+    #[command(subcommand)]
+    Migrations(migrations::MigrationsCommand),
+}
+
+impl FraxtalCommand {
+    /// Runs the command.
+    pub fn run(self) -> eyre::Result<()> {
+        match self {
+            Self::Bootnodes(command) => command.run(),
+            Self::Chain(command) => command.run(),
+            Self::Migrations(command) => command.run(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dispatches_fraxtal_subcommands() {
+        let cli = FraxtalCli::try_parse_from(["fraxtal-op-reth", "chain", "fraxtal"]).unwrap();
+        assert!(matches!(cli, FraxtalCli::Fraxtal(FraxtalCommand::Chain(_))));
+
+        // Global arguments of the upstream CLI may precede the subcommand.
+        let cli =
+            FraxtalCli::try_parse_from(["fraxtal-op-reth", "-vvvv", "chain", "--json"]).unwrap();
+        assert!(matches!(cli, FraxtalCli::Fraxtal(FraxtalCommand::Chain(_))));
+
+        let cli = FraxtalCli::try_parse_from(["fraxtal-op-reth", "-vvvv", "node"]).unwrap();
+        assert!(matches!(cli, FraxtalCli::Reth(_)));
+    }
+
+    #[test]
+    fn rejects_unknown_subcommand() {
+        assert!(FraxtalCli::try_parse_from(["fraxtal-op-reth", "frobnicate"]).is_err());
+    }
+}
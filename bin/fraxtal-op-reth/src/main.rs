@@ -1,15 +1,14 @@
 #![allow(missing_docs, rustdoc::missing_crate_level_docs)]
 // The `optimism` feature must be enabled to use this crate.
 
-use clap::Parser;
-use fraxtal_chainspec::FraxtalChainSpecParser;
+use commands::FraxtalCli;
 use fraxtal_node::proof_history::launch_node_with_proof_history;
-use reth_optimism_cli::Cli;
-use reth_optimism_node::args::RollupArgs;
 use tracing::info;
 
 use tracing as _;
 
+mod commands;
+
 #[global_allocator]
 static ALLOC: reth_cli_util::allocator::Allocator = reth_cli_util::allocator::new_allocator();
 
@@ -22,12 +21,14 @@ fn main() {
         }
     }
 
-    if let Err(err) =
-        Cli::<FraxtalChainSpecParser, RollupArgs>::parse().run(async move |builder, rollup_args| {
+    let result = match FraxtalCli::parse() {
+        FraxtalCli::Fraxtal(command) => command.run(),
+        FraxtalCli::Reth(cli) => (*cli).run(async move |builder, args| {
             info!(target: "reth::cli", "Launching node");
-            launch_node_with_proof_history(builder, rollup_args).await
-        })
-    {
+            launch_node_with_proof_history(builder, args).await
+        }),
+    };
+    if let Err(err) = result {
         eprintln!("Error: {err:?}");
         std::process::exit(1);
     }
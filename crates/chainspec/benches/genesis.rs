@@ -0,0 +1,48 @@
+//! Cost of the compressed genesis embedding.
+//!
+//! Reports the embedded size of each genesis file next to its decompressed size, and measures
+//! the time spent decompressing it and building the chain spec on first use.
+//!
+//! Both are compared against embedding the JSON uncompressed: the binary grows by the size
+//! difference, and first use skips the decompression but still parses and builds the spec.
+
+#![allow(missing_docs)]
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use fraxtal_chainspec::EMBEDDED_GENESIS;
+use reth_optimism_chainspec::OpChainSpec;
+use std::hint::black_box;
+
+fn genesis(c: &mut Criterion) {
+    for genesis in &EMBEDDED_GENESIS {
+        let chain = genesis.expected.chain;
+        let json = genesis.decompress().unwrap();
+        println!(
+            "{chain}: {} bytes embedded, {} bytes decompressed ({:.1}x), {} bytes saved in the \
+             binary against an uncompressed embedding",
+            genesis.compressed.len(),
+            json.len(),
+            json.len() as f64 / genesis.compressed.len() as f64,
+            json.len() - genesis.compressed.len()
+        );
+
+        let mut group = c.benchmark_group(chain);
+        group.sample_size(10);
+        group.bench_function("decompress", |b| b.iter(|| black_box(genesis.decompress().unwrap())));
+        group.bench_function("parse", |b| {
+            b.iter(|| black_box(serde_json::from_slice::<alloy_genesis::Genesis>(&json).unwrap()))
+        });
+        group.bench_function("load", |b| b.iter(|| black_box(genesis.load().unwrap())));
+        // First use with an uncompressed embedding, the startup delta is `load` minus this.
+        group.bench_function("load_uncompressed", |b| {
+            b.iter(|| {
+                let genesis = serde_json::from_slice::<alloy_genesis::Genesis>(&json).unwrap();
+                black_box(OpChainSpec::from(genesis))
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, genesis);
+criterion_main!(benches);
@@ -0,0 +1,27 @@
+//! Compresses the genesis files embedded in the built-in chain specs.
+
+use std::{env, fs, path::PathBuf};
+
+/// The genesis files under `res/genesis`.
+const GENESIS_FILES: [&str; 2] = ["mainnet.json", "hoodi-testnet.json"];
+
+/// The zstd compression level. Compression only runs when a genesis file changes, so the slow
+/// high ratio levels are fine.
+const COMPRESSION_LEVEL: i32 = 19;
+
+fn main() {
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("OUT_DIR is set")).join("genesis");
+    fs::create_dir_all(&out_dir).expect("failed to create the genesis output dir");
+
+    for name in GENESIS_FILES {
+        let path = PathBuf::from("res/genesis").join(name);
+        println!("cargo:rerun-if-changed={}", path.display());
+
+        let json = fs::read(&path)
+            .unwrap_or_else(|err| panic!("failed to read {}: {err}", path.display()));
+        let compressed = zstd::encode_all(json.as_slice(), COMPRESSION_LEVEL)
+            .unwrap_or_else(|err| panic!("failed to compress {}: {err}", path.display()));
+        fs::write(out_dir.join(format!("{name}.zst")), compressed)
+            .unwrap_or_else(|err| panic!("failed to write the compressed {name}: {err}"));
+    }
+}
@@ -1,6 +1,10 @@
 //! Bootnodes for the Fraxtal networks.
 
-use crate::{fraxtal::FRAXTAL_MAINNET, fraxtal_hoodi_testnet::FRAXTAL_HOODI_TESTNET};
+use crate::{
+    fraxtal::FRAXTAL_MAINNET,
+    fraxtal_hoodi_testnet::FRAXTAL_HOODI_TESTNET,
+    overrides::{overridden_bootnodes, overridden_dns_networks},
+};
 use reth_chainspec::EthChainSpec;
 use reth_network_peers::{NodeRecord, parse_nodes};
 
@@ -22,6 +26,14 @@ pub static FRAXTAL_HOODI_TESTNET_BOOTNODES: &[&str] = &[
     "enode://b467ac6f93a161a1a3808847d16077e30f98810fe9740c54a7eb5f98d917fa555ca957fba7370b6f752b87de4a88ab6e9d4e0d71890dc25bfc17b33eba717217@15.204.110.99:30310",
 ];
 
+/// EIP-1459 DNS discovery trees of Fraxtal mainnet, as `enrtree://<key>@<domain>` links.
+///
+/// Unlike the static bootnodes, the nodes listed in a tree can be updated without a release.
+pub static FRAXTAL_MAINNET_DNS_NETWORKS: &[&str] = &[];
+
+/// EIP-1459 DNS discovery trees of Fraxtal Hoodi testnet.
+pub static FRAXTAL_HOODI_TESTNET_DNS_NETWORKS: &[&str] = &[];
+
 /// Returns parsed Fraxtal mainnet bootnodes.
 pub fn fraxtal_mainnet_nodes() -> Vec<NodeRecord> {
     parse_nodes(FRAXTAL_MAINNET_BOOTNODES)
@@ -32,12 +44,15 @@ pub fn fraxtal_hoodi_testnet_nodes() -> Vec<NodeRecord> {
     parse_nodes(FRAXTAL_HOODI_TESTNET_BOOTNODES)
 }
 
-/// Returns the built-in bootnodes for the given chain id, if any.
+/// Returns the built-in bootnodes of the chain of `spec`, if any.
 ///
 /// The chain ids are read from the genesis JSON files so they stay in sync with the
-/// chainspecs themselves.
-pub fn fraxtal_bootnodes(chain_id: u64) -> Option<Vec<NodeRecord>> {
-    if chain_id == FRAXTAL_MAINNET.chain().id() {
+/// chainspecs themselves. Bootnodes set by a chain spec override file take precedence.
+pub fn fraxtal_bootnodes(spec: &impl EthChainSpec) -> Option<Vec<NodeRecord>> {
+    let chain_id = spec.chain().id();
+    if let Some(nodes) = overridden_bootnodes(spec) {
+        Some(nodes)
+    } else if chain_id == FRAXTAL_MAINNET.chain().id() {
         Some(fraxtal_mainnet_nodes())
     } else if chain_id == FRAXTAL_HOODI_TESTNET.chain().id() {
         Some(fraxtal_hoodi_testnet_nodes())
@@ -46,9 +61,29 @@ pub fn fraxtal_bootnodes(chain_id: u64) -> Option<Vec<NodeRecord>> {
     }
 }
 
+/// Returns the EIP-1459 DNS discovery tree links of the chain of `spec`, if any.
+///
+/// Trees set by a chain spec override file take precedence.
+pub fn fraxtal_dns_networks(spec: &impl EthChainSpec) -> Option<Vec<String>> {
+    if let Some(networks) = overridden_dns_networks(spec) {
+        return Some(networks);
+    }
+    let chain_id = spec.chain().id();
+    let networks = if chain_id == FRAXTAL_MAINNET.chain().id() {
+        FRAXTAL_MAINNET_DNS_NETWORKS
+    } else if chain_id == FRAXTAL_HOODI_TESTNET.chain().id() {
+        FRAXTAL_HOODI_TESTNET_DNS_NETWORKS
+    } else {
+        return None;
+    };
+    Some(networks.iter().map(ToString::to_string).collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::overrides::ChainSpecOverrides;
+    use reth_optimism_chainspec::OP_MAINNET;
 
     #[test]
     fn parse_mainnet_bootnodes() {
@@ -64,8 +99,46 @@ mod tests {
 
     #[test]
     fn lookup_by_chain_id() {
-        assert_eq!(fraxtal_bootnodes(FRAXTAL_MAINNET.chain().id()).unwrap().len(), 6);
-        assert_eq!(fraxtal_bootnodes(FRAXTAL_HOODI_TESTNET.chain().id()).unwrap().len(), 4);
-        assert!(fraxtal_bootnodes(1).is_none());
+        assert_eq!(fraxtal_bootnodes(&*FRAXTAL_MAINNET).unwrap().len(), 6);
+        assert_eq!(fraxtal_bootnodes(&*FRAXTAL_HOODI_TESTNET).unwrap().len(), 4);
+        assert!(fraxtal_bootnodes(&*OP_MAINNET).is_none());
+    }
+
+    #[test]
+    fn lookup_dns_networks() {
+        let links = |links: &[&str]| links.iter().map(ToString::to_string).collect::<Vec<_>>();
+        assert_eq!(
+            fraxtal_dns_networks(&*FRAXTAL_MAINNET),
+            Some(links(FRAXTAL_MAINNET_DNS_NETWORKS))
+        );
+        assert_eq!(
+            fraxtal_dns_networks(&*FRAXTAL_HOODI_TESTNET),
+            Some(links(FRAXTAL_HOODI_TESTNET_DNS_NETWORKS))
+        );
+        assert!(fraxtal_dns_networks(&*OP_MAINNET).is_none());
+
+        // Built-in trees must be valid links, like the ones of override files.
+        let builtin = ChainSpecOverrides {
+            dns_networks: Some(links(
+                &[FRAXTAL_MAINNET_DNS_NETWORKS, FRAXTAL_HOODI_TESTNET_DNS_NETWORKS].concat(),
+            )),
+            ..Default::default()
+        };
+        builtin.dns_networks().unwrap();
+
+        // Trees of an override file replace the built-in ones.
+        let link =
+            "enrtree://AKA3AM6LPBYEUDMVNU3BSVQJ5AD45Y7YPOHJLEF6W26QOE4VTUDPE@nodes.example.org";
+        let overrides =
+            ChainSpecOverrides { dns_networks: Some(links(&[link])), ..Default::default() };
+        let spec = overrides.apply(&FRAXTAL_MAINNET).unwrap();
+        assert_eq!(fraxtal_dns_networks(&spec), Some(links(&[link])));
+    }
+
+    #[test]
+    fn overridden_bootnodes_take_precedence() {
+        let overrides = ChainSpecOverrides { bootnodes: Some(Vec::new()), ..Default::default() };
+        let spec = overrides.apply(&FRAXTAL_MAINNET).unwrap();
+        assert_eq!(fraxtal_bootnodes(&spec), Some(Vec::new()));
     }
 }
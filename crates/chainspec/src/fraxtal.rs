@@ -2,16 +2,22 @@
 
 use std::sync::{Arc, LazyLock};
 
-use alloy_genesis::Genesis;
 use reth_optimism_chainspec::OpChainSpec;
 
+use crate::genesis::{EmbeddedGenesis, FRAXTAL_MAINNET_GENESIS};
+
+/// The embedded genesis file, compressed by the build script.
+pub(crate) const GENESIS: EmbeddedGenesis = EmbeddedGenesis {
+    expected: FRAXTAL_MAINNET_GENESIS,
+    compressed: include_bytes!(concat!(env!("OUT_DIR"), "/genesis/mainnet.json.zst")),
+};
+
 /// The Fraxtal Mainnet spec.
 ///
 /// This uses `OpChainSpec::from(genesis)` to dynamically extract hardforks
 /// from the genesis file instead of hardcoding them, ensuring the computed
 /// genesis hash matches the expected value.
-pub(crate) static FRAXTAL_MAINNET: LazyLock<Arc<OpChainSpec>> = LazyLock::new(|| {
-    let genesis: Genesis = serde_json::from_str(include_str!("../res/genesis/mainnet.json"))
-        .expect("Can't deserialize Fraxtal mainnet genesis json");
-    Arc::new(OpChainSpec::from(genesis))
-});
+///
+/// Construction panics if the spec does not match [`FRAXTAL_MAINNET_GENESIS`].
+pub(crate) static FRAXTAL_MAINNET: LazyLock<Arc<OpChainSpec>> =
+    LazyLock::new(|| Arc::new(GENESIS.load().unwrap_or_else(|err| panic!("{err:#}"))));
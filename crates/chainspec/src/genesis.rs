@@ -0,0 +1,140 @@
+//! Embedded genesis of the built-in chain specs.
+//!
+//! The genesis files are compressed with zstd at build time and only decompressed when a spec is
+//! first used. The built-in specs are derived from them, so a stale or corrupted file would
+//! silently start a different chain; each spec is checked against the values pinned here when it
+//! is constructed.
+
+use alloy_genesis::Genesis;
+use alloy_primitives::{B256, b256};
+use eyre::{WrapErr, ensure};
+use reth_chainspec::EthChainSpec;
+use reth_optimism_chainspec::OpChainSpec;
+
+/// Pinned genesis of a built-in chain.
+///
+/// The values must come from a source independent of the embedded genesis file, e.g. the
+/// superchain registry or the genesis block of a synced node, otherwise a corrupted file would
+/// pin itself. Values that are `None` are not pinned yet and are not checked;
+/// `fraxtal-op-reth chain <name>` prints the genesis hash computed from the embedded genesis file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExpectedGenesis {
+    /// Name of the chain, as accepted by `--chain`.
+    pub chain: &'static str,
+    /// Chain id of the chain.
+    pub chain_id: u64,
+    /// Hash of the genesis block.
+    pub hash: Option<B256>,
+    /// State root of the genesis block.
+    pub state_root: Option<B256>,
+}
+
+/// Genesis of Fraxtal mainnet, as published in the superchain registry.
+pub const FRAXTAL_MAINNET_GENESIS: ExpectedGenesis = ExpectedGenesis {
+    chain: "fraxtal",
+    chain_id: 252,
+    hash: Some(b256!("521982bd54239dc71269eefb58601762cc15cfb2978e0becb46af7962ed6bfaa")),
+    state_root: None,
+};
+
+/// Genesis of the Fraxtal Hoodi testnet.
+pub const FRAXTAL_HOODI_TESTNET_GENESIS: ExpectedGenesis = ExpectedGenesis {
+    chain: "fraxtal-hoodi-testnet",
+    chain_id: 2523,
+    hash: None,
+    state_root: None,
+};
+
+impl ExpectedGenesis {
+    /// Returns the pinned genesis of the built-in chain with the given id, if any.
+    pub fn of_chain(chain_id: u64) -> Option<Self> {
+        EMBEDDED_GENESIS
+            .iter()
+            .map(|genesis| genesis.expected)
+            .find(|expected| expected.chain_id == chain_id)
+    }
+
+    /// Checks the genesis of `spec` against the pinned values.
+    pub fn verify(&self, spec: &OpChainSpec) -> eyre::Result<()> {
+        let chain_id = spec.chain().id();
+        ensure!(
+            chain_id == self.chain_id,
+            "chain id of {} is {chain_id}, expected {}",
+            self.chain,
+            self.chain_id
+        );
+        if let Some(expected) = self.hash {
+            let hash = spec.genesis_hash();
+            ensure!(
+                hash == expected,
+                "genesis hash of {} is {hash}, expected {expected}",
+                self.chain
+            );
+        }
+        if let Some(expected) = self.state_root {
+            let state_root = spec.genesis_header().state_root;
+            ensure!(
+                state_root == expected,
+                "genesis state root of {} is {state_root}, expected {expected}",
+                self.chain
+            );
+        }
+        Ok(())
+    }
+}
+
+/// A zstd-compressed genesis file embedded in the binary.
+#[derive(Debug, Clone, Copy)]
+pub struct EmbeddedGenesis {
+    /// The pinned genesis of the chain.
+    pub expected: ExpectedGenesis,
+    /// The compressed genesis JSON.
+    pub compressed: &'static [u8],
+}
+
+/// The genesis files of the built-in chains.
+pub const EMBEDDED_GENESIS: [EmbeddedGenesis; 2] =
+    [crate::fraxtal::GENESIS, crate::fraxtal_hoodi_testnet::GENESIS];
+
+impl EmbeddedGenesis {
+    /// Returns the decompressed genesis JSON.
+    pub fn decompress(&self) -> eyre::Result<Vec<u8>> {
+        zstd::decode_all(self.compressed)
+            .wrap_err_with(|| format!("Can't decompress {} genesis", self.expected.chain))
+    }
+
+    /// Builds the spec and checks it against the pinned genesis.
+    pub fn load(&self) -> eyre::Result<OpChainSpec> {
+        let genesis: Genesis = serde_json::from_slice(&self.decompress()?)
+            .wrap_err_with(|| format!("Can't deserialize {} genesis json", self.expected.chain))?;
+        let spec = OpChainSpec::from(genesis);
+        self.expected.verify(&spec)?;
+        Ok(spec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_optimism_chainspec::OpChainSpecBuilder;
+
+    #[test]
+    fn rejects_unexpected_genesis() {
+        let spec = OpChainSpecBuilder::default().genesis(Genesis::default()).build();
+        let expected = ExpectedGenesis {
+            chain: "test",
+            chain_id: spec.chain().id(),
+            hash: Some(spec.genesis_hash()),
+            state_root: Some(spec.genesis_header().state_root),
+        };
+        expected.verify(&spec).unwrap();
+        ExpectedGenesis { hash: None, state_root: None, ..expected }.verify(&spec).unwrap();
+
+        let err = ExpectedGenesis { hash: Some(B256::ZERO), ..expected }.verify(&spec).unwrap_err();
+        assert!(err.to_string().contains("genesis hash of test"), "{err}");
+
+        let err =
+            ExpectedGenesis { state_root: Some(B256::ZERO), ..expected }.verify(&spec).unwrap_err();
+        assert!(err.to_string().contains("state root of test"), "{err}");
+    }
+}
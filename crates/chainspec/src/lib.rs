@@ -1,17 +1,26 @@
 use fraxtal::FRAXTAL_MAINNET;
 use fraxtal_hoodi_testnet::FRAXTAL_HOODI_TESTNET;
+use reth_chainspec::EthChainSpec;
 use reth_cli::chainspec::{ChainSpecParser, parse_genesis};
 use reth_optimism_chainspec::OpChainSpec;
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 pub mod bootnodes;
 mod fraxtal;
 mod fraxtal_hoodi_testnet;
+pub mod genesis;
+pub mod overrides;
+pub mod rollup;
 
 pub use bootnodes::{
-    FRAXTAL_HOODI_TESTNET_BOOTNODES, FRAXTAL_MAINNET_BOOTNODES, fraxtal_bootnodes,
+    FRAXTAL_HOODI_TESTNET_BOOTNODES, FRAXTAL_HOODI_TESTNET_DNS_NETWORKS, FRAXTAL_MAINNET_BOOTNODES,
+    FRAXTAL_MAINNET_DNS_NETWORKS, fraxtal_bootnodes, fraxtal_dns_networks,
     fraxtal_hoodi_testnet_nodes, fraxtal_mainnet_nodes,
 };
+pub use genesis::{
+    EMBEDDED_GENESIS, EmbeddedGenesis, ExpectedGenesis, FRAXTAL_HOODI_TESTNET_GENESIS,
+    FRAXTAL_MAINNET_GENESIS,
+};
 
 /// Fraxtal chain specification parser.
 #[derive(Debug, Clone, Default)]
@@ -32,14 +41,45 @@ impl ChainSpecParser for FraxtalChainSpecParser {
 ///
 /// The value parser matches either a known chain, the path
 /// to a json file, or a json formatted string in-memory. The json needs to be a Genesis struct.
+///
+/// The path to an op-node `rollup.json` or a superchain-registry chain TOML derives the spec
+/// from the [rollup config](rollup).
+///
+/// A known chain followed by `+<path>`, e.g. `fraxtal+overrides.toml`, applies the
+/// [overrides](overrides) read from `<path>` to the built-in spec.
 pub fn chain_value_parser(s: &str) -> eyre::Result<Arc<OpChainSpec>, eyre::Error> {
-    Ok(match s {
+    if let Some((chain, path)) = s.split_once('+')
+        && let Some(base) = builtin_chain(chain)
+    {
+        return overrides::load_with_overrides(&base, Path::new(path));
+    }
+
+    if let Some(spec) = builtin_chain(s) {
+        return Ok(spec);
+    }
+    if let Some(spec) = rollup::parse_rollup_file(s)? {
+        return Ok(Arc::new(spec));
+    }
+    Ok(Arc::new(parse_genesis(s)?.into()))
+}
+
+/// Returns the built-in spec with the given name.
+fn builtin_chain(name: &str) -> Option<Arc<OpChainSpec>> {
+    Some(match name {
         "fraxtal" => FRAXTAL_MAINNET.clone(),
         "fraxtal-hoodi-testnet" => FRAXTAL_HOODI_TESTNET.clone(),
-        _ => Arc::new(parse_genesis(s)?.into()),
+        _ => return None,
     })
 }
 
+/// Returns the built-in spec with the given chain id.
+fn builtin_chain_by_id(chain_id: u64) -> Option<Arc<OpChainSpec>> {
+    [&*FRAXTAL_MAINNET, &*FRAXTAL_HOODI_TESTNET]
+        .into_iter()
+        .find(|spec| spec.chain().id() == chain_id)
+        .cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -53,6 +93,21 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn verify_builtin_genesis() {
+        FraxtalChainSpec::verify_all().unwrap();
+        FraxtalChainSpec::verify(&FraxtalChainSpec::mainnet()).unwrap();
+        FraxtalChainSpec::verify(&FraxtalChainSpec::hoodi()).unwrap();
+    }
+
+    #[test]
+    fn verify_skips_other_chains() {
+        let spec = reth_optimism_chainspec::OpChainSpecBuilder::base_mainnet().build();
+        FraxtalChainSpec::verify(&spec).unwrap();
+        assert_eq!(ExpectedGenesis::of_chain(252), Some(FRAXTAL_MAINNET_GENESIS));
+        assert_eq!(ExpectedGenesis::of_chain(spec.chain().id()), None);
+    }
 }
 
 /// Convenience accessors for the built-in Fraxtal chain specs.
@@ -68,4 +123,23 @@ impl FraxtalChainSpec {
     pub fn hoodi() -> Arc<OpChainSpec> {
         FRAXTAL_HOODI_TESTNET.clone()
     }
+
+    /// Checks `spec` against the pinned genesis of the built-in chain with the same chain id, if
+    /// any.
+    ///
+    /// Only the sealed genesis header of `spec` is compared, no embedded genesis file is
+    /// decompressed.
+    pub fn verify(spec: &OpChainSpec) -> eyre::Result<()> {
+        ExpectedGenesis::of_chain(spec.chain().id())
+            .map_or(Ok(()), |expected| expected.verify(spec))
+    }
+
+    /// Rebuilds every built-in spec from its embedded genesis file and checks it against its
+    /// [`ExpectedGenesis`].
+    pub fn verify_all() -> eyre::Result<()> {
+        for genesis in &EMBEDDED_GENESIS {
+            genesis.load()?;
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,326 @@
+//! Overrides layered on top of the built-in chain specs.
+//!
+//! A chain value of the form `<chain>+<path>`, e.g. `fraxtal+overrides.toml`, loads the built-in
+//! `<chain>` spec and applies the overrides read from `<path>`:
+//!
+//! ```toml
+//! bootnodes = ["enode://…@10.0.0.1:30303"]
+//! dns_networks = ["enrtree://…@nodes.example.org"]
+//!
+//! [hardforks]
+//! jovian = 1767225600
+//!
+//! [base_fee_params]
+//! max_change_denominator = 250
+//! elasticity_multiplier = 6
+//! ```
+//!
+//! Hardfork overrides may only move forks that are not active yet. This can only be checked
+//! against the local head once the database is open, so the applied overrides are carried by the
+//! resulting spec, in an extra field of its genesis config, and checked at startup with
+//! [`AppliedOverrides::validate`].
+
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use eyre::{WrapErr, bail, ensure};
+use reth_chainspec::{BaseFeeParams, BaseFeeParamsKind, EthChainSpec, ForkCondition, Hardforks};
+use reth_ethereum_forks::EthereumHardfork;
+use reth_network_peers::NodeRecord;
+use reth_optimism_chainspec::OpChainSpec;
+use reth_optimism_forks::OpHardfork;
+use serde::{Deserialize, Serialize};
+
+/// Genesis config extra field carrying the overrides applied to a built-in spec.
+const OVERRIDES_FIELD: &str = "fraxtalOverrides";
+
+/// Hardfork, base fee and bootnode overrides of a built-in chain spec.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSpecOverrides {
+    /// New activation timestamps, by OP Stack fork name.
+    #[serde(default)]
+    pub hardforks: BTreeMap<String, u64>,
+    /// Base fee parameters replacing the ones of the chain.
+    pub base_fee_params: Option<BaseFeeParamsOverride>,
+    /// Bootnodes replacing the built-in ones of the chain.
+    pub bootnodes: Option<Vec<String>>,
+    /// EIP-1459 DNS discovery tree links replacing the built-in ones of the chain.
+    pub dns_networks: Option<Vec<String>>,
+}
+
+/// Constant base fee parameters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BaseFeeParamsOverride {
+    /// The base fee max change denominator.
+    pub max_change_denominator: u128,
+    /// The elasticity multiplier.
+    pub elasticity_multiplier: u128,
+}
+
+impl ChainSpecOverrides {
+    /// Reads the overrides from a TOML file.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read chain spec overrides {}", path.display()))?;
+        toml::from_str(&contents)
+            .wrap_err_with(|| format!("invalid chain spec overrides {}", path.display()))
+    }
+
+    /// Returns the overrides carried by `spec`, if it is a built-in spec loaded with overrides.
+    pub fn of(spec: &impl EthChainSpec) -> eyre::Result<Option<Self>> {
+        spec.genesis()
+            .config
+            .extra_fields
+            .get_deserialized(OVERRIDES_FIELD)
+            .transpose()
+            .wrap_err("invalid chain spec overrides")
+    }
+
+    /// Returns the overridden forks with their new activation timestamps.
+    pub fn forks(&self) -> eyre::Result<Vec<(OpHardfork, u64)>> {
+        self.hardforks
+            .iter()
+            .map(|(name, &timestamp)| {
+                let fork = name.parse().map_err(|_| eyre::eyre!("unknown hardfork {name}"))?;
+                Ok((fork, timestamp))
+            })
+            .collect()
+    }
+
+    /// Returns the parsed bootnodes, if overridden.
+    pub fn bootnodes(&self) -> eyre::Result<Option<Vec<NodeRecord>>> {
+        self.bootnodes
+            .as_ref()
+            .map(|nodes| {
+                nodes
+                    .iter()
+                    .map(|node| node.parse().wrap_err_with(|| format!("invalid bootnode {node}")))
+                    .collect()
+            })
+            .transpose()
+    }
+
+    /// Returns the DNS discovery tree links, if overridden.
+    pub fn dns_networks(&self) -> eyre::Result<Option<&[String]>> {
+        let Some(networks) = &self.dns_networks else { return Ok(None) };
+        for link in networks {
+            ensure!(link.starts_with("enrtree://"), "invalid DNS discovery tree {link}");
+        }
+        Ok(Some(networks))
+    }
+
+    /// Returns a copy of `base` with the overrides applied, carrying the overrides.
+    ///
+    /// Fails if a fork would activate at or before genesis, or if the overridden schedule is not
+    /// ordered.
+    pub fn apply(&self, base: &OpChainSpec) -> eyre::Result<OpChainSpec> {
+        let mut spec = base.clone();
+        let genesis_timestamp = base.genesis().timestamp;
+
+        for (fork, timestamp) in self.forks()? {
+            ensure!(
+                timestamp > genesis_timestamp,
+                "{fork} can't be moved to {timestamp}, at or before genesis"
+            );
+            spec.inner.hardforks.insert(fork, ForkCondition::Timestamp(timestamp));
+            if let Some(l1_fork) = paired_ethereum_fork(fork) {
+                spec.inner.hardforks.insert(l1_fork, ForkCondition::Timestamp(timestamp));
+            }
+        }
+
+        let mut previous: Option<(OpHardfork, u64)> = None;
+        for &fork in OpHardfork::VARIANTS {
+            let ForkCondition::Timestamp(timestamp) = spec.fork(fork) else { continue };
+            if let Some((previous, previous_timestamp)) = previous {
+                ensure!(
+                    previous_timestamp <= timestamp,
+                    "{fork} at {timestamp} would activate before {previous} at {previous_timestamp}"
+                );
+            }
+            previous = Some((fork, timestamp));
+        }
+
+        if let Some(params) = self.base_fee_params {
+            spec.inner.base_fee_params = BaseFeeParamsKind::Constant(BaseFeeParams::new(
+                params.max_change_denominator,
+                params.elasticity_multiplier,
+            ));
+        }
+
+        // The genesis header is already sealed, the extra field doesn't change the genesis hash.
+        spec.inner.genesis.config.extra_fields.insert(
+            OVERRIDES_FIELD.to_string(),
+            serde_json::to_value(self).wrap_err("failed to serialize chain spec overrides")?,
+        );
+
+        Ok(spec)
+    }
+}
+
+/// The Ethereum fork activated together with an OP Stack fork.
+const fn paired_ethereum_fork(fork: OpHardfork) -> Option<EthereumHardfork> {
+    match fork {
+        OpHardfork::Canyon => Some(EthereumHardfork::Shanghai),
+        OpHardfork::Ecotone => Some(EthereumHardfork::Cancun),
+        OpHardfork::Isthmus => Some(EthereumHardfork::Prague),
+        _ => None,
+    }
+}
+
+/// Overrides applied to a built-in chain spec.
+#[derive(Debug, Clone)]
+pub struct AppliedOverrides {
+    /// The built-in spec the overrides were applied to.
+    pub base: Arc<OpChainSpec>,
+    /// The applied overrides.
+    pub overrides: ChainSpecOverrides,
+}
+
+impl AppliedOverrides {
+    /// Checks that the overrides only move forks that are not active at `head_timestamp`, and
+    /// don't activate any fork at or before it.
+    pub fn validate(&self, head_timestamp: u64) -> eyre::Result<()> {
+        for (fork, timestamp) in self.overrides.forks()? {
+            let activation = self.base.fork(fork);
+            if activation.active_at_timestamp(head_timestamp) {
+                bail!("{fork} is already active at the local head, it can't be moved");
+            }
+            ensure!(
+                timestamp > head_timestamp,
+                "{fork} can't be moved to {timestamp}, before the local head at {head_timestamp}"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Loads the built-in `base` spec with the overrides of the file at `path` applied.
+pub(crate) fn load_with_overrides(
+    base: &Arc<OpChainSpec>,
+    path: &Path,
+) -> eyre::Result<Arc<OpChainSpec>> {
+    let overrides = ChainSpecOverrides::load(path)?;
+    // Bootnodes are validated here, so a malformed entry fails parsing rather than startup.
+    overrides.bootnodes()?;
+    overrides.dns_networks()?;
+    Ok(Arc::new(overrides.apply(base)?))
+}
+
+/// Returns the overrides applied to `spec` together with the built-in spec they were applied to,
+/// if `spec` was loaded with overrides.
+pub fn applied_overrides(spec: &impl EthChainSpec) -> eyre::Result<Option<AppliedOverrides>> {
+    let Some(overrides) = ChainSpecOverrides::of(spec)? else { return Ok(None) };
+    let chain_id = spec.chain().id();
+    let base = crate::builtin_chain_by_id(chain_id)
+        .ok_or_else(|| eyre::eyre!("chain {chain_id} has overrides but no built-in spec"))?;
+    Ok(Some(AppliedOverrides { base, overrides }))
+}
+
+/// Returns the bootnodes overridden in `spec`, if any.
+pub(crate) fn overridden_bootnodes(spec: &impl EthChainSpec) -> Option<Vec<NodeRecord>> {
+    ChainSpecOverrides::of(spec).ok().flatten()?.bootnodes().ok().flatten()
+}
+
+/// Returns the DNS discovery trees overridden in `spec`, if any.
+pub(crate) fn overridden_dns_networks(spec: &impl EthChainSpec) -> Option<Vec<String>> {
+    ChainSpecOverrides::of(spec).ok().flatten()?.dns_networks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_genesis::Genesis;
+    use reth_chainspec::Chain;
+    use reth_optimism_chainspec::OpChainSpecBuilder;
+
+    /// Chain with Granite at 100 and Holocene at 200.
+    fn base() -> OpChainSpec {
+        OpChainSpecBuilder::default()
+            .chain(Chain::from_id(252))
+            .genesis(Genesis::default())
+            .fjord_activated()
+            .with_fork(OpHardfork::Granite, ForkCondition::Timestamp(100))
+            .with_fork(OpHardfork::Holocene, ForkCondition::Timestamp(200))
+            .build()
+    }
+
+    fn overrides(toml: &str) -> ChainSpecOverrides {
+        toml::from_str(toml).unwrap()
+    }
+
+    #[test]
+    fn moves_fork() {
+        let base = base();
+        let spec = overrides("[hardforks]\nholocene = 300").apply(&base).unwrap();
+        assert_eq!(spec.fork(OpHardfork::Holocene), ForkCondition::Timestamp(300));
+        assert_eq!(spec.fork(OpHardfork::Granite), ForkCondition::Timestamp(100));
+        assert_eq!(spec.genesis_hash(), base.genesis_hash());
+    }
+
+    #[test]
+    fn overrides_base_fee_params() {
+        let spec =
+            overrides("[base_fee_params]\nmax_change_denominator = 8\nelasticity_multiplier = 2")
+                .apply(&base())
+                .unwrap();
+        assert_eq!(
+            spec.inner.base_fee_params,
+            BaseFeeParamsKind::Constant(BaseFeeParams::new(8, 2))
+        );
+    }
+
+    #[test]
+    fn rejects_unordered_schedule() {
+        let err = overrides("[hardforks]\ngranite = 250").apply(&base()).unwrap_err();
+        assert!(err.to_string().contains("before Granite"), "{err}");
+    }
+
+    #[test]
+    fn rejects_fork_at_genesis() {
+        let err = overrides("[hardforks]\nholocene = 0").apply(&base()).unwrap_err();
+        assert!(err.to_string().contains("genesis"), "{err}");
+    }
+
+    #[test]
+    fn rejects_unknown_fork() {
+        let err = overrides("[hardforks]\nfrontier = 10").apply(&base()).unwrap_err();
+        assert!(err.to_string().contains("unknown hardfork"), "{err}");
+    }
+
+    #[test]
+    fn spec_carries_overrides() {
+        let base = base();
+        assert_eq!(ChainSpecOverrides::of(&base).unwrap(), None);
+
+        let overrides = overrides("bootnodes = []\n[hardforks]\nholocene = 300");
+        let spec = overrides.apply(&base).unwrap();
+        assert_eq!(ChainSpecOverrides::of(&spec).unwrap(), Some(overrides));
+        assert_eq!(overridden_bootnodes(&spec), Some(Vec::new()));
+        assert_eq!(overridden_dns_networks(&spec), None);
+    }
+
+    #[test]
+    fn rejects_invalid_bootnode() {
+        assert!(overrides("bootnodes = [\"enode://nope\"]").bootnodes().is_err());
+        assert!(overrides("dns_networks = [\"nodes.example.org\"]").dns_networks().is_err());
+    }
+
+    #[test]
+    fn validates_against_head() {
+        let applied = AppliedOverrides {
+            base: Arc::new(base()),
+            overrides: overrides("[hardforks]\nholocene = 300"),
+        };
+        applied.validate(150).unwrap();
+        // Holocene is already active at the head.
+        assert!(applied.validate(250).is_err());
+
+        let applied = AppliedOverrides {
+            base: Arc::new(base()),
+            overrides: overrides("[hardforks]\nholocene = 120"),
+        };
+        // Holocene would activate before the head.
+        assert!(applied.validate(150).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+//! Chain specs derived from OP Stack rollup configs.
+//!
+//! OP Stack tooling describes a chain with a rollup config, either as the `rollup.json` read by
+//! op-node or as a superchain-registry chain TOML. Neither holds the genesis state, which is taken
+//! from the built-in Fraxtal spec with the same chain id, or else from a `genesis.json` next to the
+//! config file. The hardfork schedule and base fee parameters of the config replace the ones of the
+//! genesis, and the resulting genesis hash is checked against the L2 genesis of the config.
+
+use std::path::Path;
+
+use alloy_genesis::Genesis;
+use alloy_primitives::{Address, B256};
+use eyre::{WrapErr, ensure};
+use reth_chainspec::EthChainSpec;
+use reth_optimism_chainspec::OpChainSpec;
+use serde::Deserialize;
+use serde_json::json;
+
+/// Block reference of a rollup genesis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct BlockRef {
+    /// The block hash.
+    pub hash: B256,
+    /// The block number.
+    pub number: u64,
+}
+
+/// L1 system config at genesis.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct SystemConfig {
+    /// The batch submitter address.
+    #[serde(alias = "batcherAddress")]
+    #[serde(rename = "batcherAddr")]
+    pub batcher_address: Address,
+    /// The L1 fee overhead, unused since Ecotone.
+    #[serde(default)]
+    pub overhead: B256,
+    /// The L1 fee scalar.
+    #[serde(default)]
+    pub scalar: B256,
+    /// The L2 block gas limit.
+    #[serde(rename = "gasLimit")]
+    pub gas_limit: u64,
+}
+
+/// Genesis of a rollup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct RollupGenesis {
+    /// The L1 block the rollup starts from.
+    pub l1: BlockRef,
+    /// The L2 genesis block.
+    pub l2: BlockRef,
+    /// The timestamp of the L2 genesis block.
+    pub l2_time: u64,
+    /// The L1 system config at genesis.
+    pub system_config: Option<SystemConfig>,
+}
+
+/// Activation timestamps of the OP Stack forks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub struct RollupHardforks {
+    /// Regolith activation timestamp.
+    pub regolith_time: Option<u64>,
+    /// Canyon activation timestamp.
+    pub canyon_time: Option<u64>,
+    /// Ecotone activation timestamp.
+    pub ecotone_time: Option<u64>,
+    /// Fjord activation timestamp.
+    pub fjord_time: Option<u64>,
+    /// Granite activation timestamp.
+    pub granite_time: Option<u64>,
+    /// Holocene activation timestamp.
+    pub holocene_time: Option<u64>,
+    /// Isthmus activation timestamp.
+    pub isthmus_time: Option<u64>,
+    /// Jovian activation timestamp.
+    pub jovian_time: Option<u64>,
+}
+
+/// EIP-1559 parameters of a rollup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Eip1559Params {
+    /// The elasticity multiplier.
+    #[serde(alias = "eip1559Elasticity")]
+    pub eip1559_elasticity: u64,
+    /// The base fee max change denominator.
+    #[serde(alias = "eip1559Denominator")]
+    pub eip1559_denominator: u64,
+    /// The base fee max change denominator since Canyon.
+    #[serde(alias = "eip1559DenominatorCanyon")]
+    pub eip1559_denominator_canyon: Option<u64>,
+}
+
+/// The parts of a rollup config that make up the L2 chain spec.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RollupConfig {
+    /// The L2 chain id.
+    pub chain_id: u64,
+    /// The L2 block time in seconds.
+    pub block_time: u64,
+    /// The rollup genesis.
+    pub genesis: RollupGenesis,
+    /// The OP Stack hardfork schedule.
+    pub hardforks: RollupHardforks,
+    /// The EIP-1559 parameters, if they differ from the genesis.
+    pub eip1559: Option<Eip1559Params>,
+}
+
+/// `rollup.json` as read by op-node.
+#[derive(Debug, Deserialize)]
+struct RollupJson {
+    l2_chain_id: u64,
+    block_time: u64,
+    genesis: RollupGenesis,
+    #[serde(flatten)]
+    hardforks: RollupHardforks,
+    chain_op_config: Option<Eip1559Params>,
+}
+
+/// Chain TOML of the superchain registry.
+#[derive(Debug, Deserialize)]
+struct SuperchainToml {
+    chain_id: u64,
+    block_time: u64,
+    genesis: RollupGenesis,
+    #[serde(default)]
+    hardforks: RollupHardforks,
+    optimism: Option<Eip1559Params>,
+}
+
+impl RollupConfig {
+    /// Parses an op-node `rollup.json`.
+    pub fn from_rollup_json(contents: &str) -> eyre::Result<Self> {
+        let config: RollupJson = serde_json::from_str(contents)?;
+        Ok(Self {
+            chain_id: config.l2_chain_id,
+            block_time: config.block_time,
+            genesis: config.genesis,
+            hardforks: config.hardforks,
+            eip1559: config.chain_op_config,
+        })
+    }
+
+    /// Parses a superchain-registry chain TOML.
+    pub fn from_superchain_toml(contents: &str) -> eyre::Result<Self> {
+        let config: SuperchainToml = toml::from_str(contents)?;
+        Ok(Self {
+            chain_id: config.chain_id,
+            block_time: config.block_time,
+            genesis: config.genesis,
+            hardforks: config.hardforks,
+            eip1559: config.optimism,
+        })
+    }
+
+    /// Returns `genesis` with the chain id, hardfork schedule and EIP-1559 parameters of the
+    /// config.
+    pub fn apply_to_genesis(&self, mut genesis: Genesis) -> eyre::Result<Genesis> {
+        ensure!(
+            genesis.timestamp == self.genesis.l2_time,
+            "genesis timestamp {} does not match the rollup genesis time {}",
+            genesis.timestamp,
+            self.genesis.l2_time
+        );
+        if let Some(system_config) = self.genesis.system_config {
+            ensure!(
+                genesis.gas_limit == system_config.gas_limit,
+                "genesis gas limit {} does not match the system config gas limit {}",
+                genesis.gas_limit,
+                system_config.gas_limit
+            );
+        }
+
+        let hardforks = &self.hardforks;
+        let config = &mut genesis.config;
+        config.chain_id = self.chain_id;
+        config.shanghai_time = hardforks.canyon_time;
+        config.cancun_time = hardforks.ecotone_time;
+        config.prague_time = hardforks.isthmus_time;
+
+        let fields = &mut config.extra_fields;
+        fields.insert("bedrockBlock".to_string(), json!(self.genesis.l2.number));
+        for (key, time) in [
+            ("regolithTime", hardforks.regolith_time),
+            ("canyonTime", hardforks.canyon_time),
+            ("ecotoneTime", hardforks.ecotone_time),
+            ("fjordTime", hardforks.fjord_time),
+            ("graniteTime", hardforks.granite_time),
+            ("holoceneTime", hardforks.holocene_time),
+            ("isthmusTime", hardforks.isthmus_time),
+            ("jovianTime", hardforks.jovian_time),
+        ] {
+            match time {
+                Some(time) => fields.insert(key.to_string(), json!(time)),
+                None => fields.remove(key),
+            };
+        }
+        if let Some(params) = self.eip1559 {
+            fields.insert(
+                "optimism".to_string(),
+                json!({
+                    "eip1559Elasticity": params.eip1559_elasticity,
+                    "eip1559Denominator": params.eip1559_denominator,
+                    "eip1559DenominatorCanyon": params.eip1559_denominator_canyon,
+                }),
+            );
+        }
+
+        Ok(genesis)
+    }
+
+    /// Derives the chain spec from the config and the genesis state, checking the resulting
+    /// genesis hash against the L2 genesis of the config.
+    pub fn into_chain_spec(self, genesis: Genesis) -> eyre::Result<OpChainSpec> {
+        let spec = OpChainSpec::from(self.apply_to_genesis(genesis)?);
+        ensure!(
+            spec.genesis_hash() == self.genesis.l2.hash,
+            "genesis hash {} does not match the rollup L2 genesis {}",
+            spec.genesis_hash(),
+            self.genesis.l2.hash
+        );
+        Ok(spec)
+    }
+}
+
+/// Parses `s` as the path to a `rollup.json` or superchain-registry TOML.
+///
+/// Returns `None` if `s` is not the path to a rollup config.
+pub(crate) fn parse_rollup_file(s: &str) -> eyre::Result<Option<OpChainSpec>> {
+    let path = Path::new(s);
+    let Ok(contents) = std::fs::read_to_string(path) else { return Ok(None) };
+
+    let config = if path.extension().is_some_and(|ext| ext == "toml") {
+        RollupConfig::from_superchain_toml(&contents)
+            .wrap_err_with(|| format!("invalid superchain config {}", path.display()))?
+    } else {
+        let is_rollup_json = serde_json::from_str::<serde_json::Value>(&contents)
+            .is_ok_and(|value| value.get("l2_chain_id").is_some());
+        if !is_rollup_json {
+            return Ok(None);
+        }
+        RollupConfig::from_rollup_json(&contents)
+            .wrap_err_with(|| format!("invalid rollup config {}", path.display()))?
+    };
+
+    let genesis = match crate::builtin_chain_by_id(config.chain_id) {
+        Some(spec) => spec.genesis().clone(),
+        None => {
+            let genesis_path = path.with_file_name("genesis.json");
+            let contents = std::fs::read_to_string(&genesis_path).wrap_err_with(|| {
+                format!("failed to read the genesis of chain {}", config.chain_id)
+            })?;
+            serde_json::from_str(&contents)
+                .wrap_err_with(|| format!("invalid genesis {}", genesis_path.display()))?
+        }
+    };
+
+    config.into_chain_spec(genesis).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_chainspec::{ForkCondition, Hardforks};
+    use reth_optimism_forks::OpHardfork;
+
+    const ROLLUP_JSON: &str = r#"{
+        "genesis": {
+            "l1": { "hash": "0x1111111111111111111111111111111111111111111111111111111111111111", "number": 19000000 },
+            "l2": { "hash": "0x2222222222222222222222222222222222222222222222222222222222222222", "number": 0 },
+            "l2_time": 1700000000,
+            "system_config": {
+                "batcherAddr": "0x6017f75108f251a488b045a7ce2a7c15b179d1f2",
+                "overhead": "0x0000000000000000000000000000000000000000000000000000000000000000",
+                "scalar": "0x00000000000000000000000000000000000000000000000000000000000a6fe0",
+                "gasLimit": 30000000
+            }
+        },
+        "block_time": 2,
+        "max_sequencer_drift": 600,
+        "seq_window_size": 3600,
+        "l1_chain_id": 1,
+        "l2_chain_id": 252,
+        "regolith_time": 0,
+        "canyon_time": 0,
+        "delta_time": 0,
+        "ecotone_time": 1700000100,
+        "fjord_time": 1700000200,
+        "granite_time": 1700000300,
+        "holocene_time": 1700000400,
+        "chain_op_config": {
+            "eip1559Elasticity": 6,
+            "eip1559Denominator": 50,
+            "eip1559DenominatorCanyon": 250
+        }
+    }"#;
+
+    const SUPERCHAIN_TOML: &str = r#"
+        name = "Fraxtal"
+        chain_id = 252
+        block_time = 2
+        seq_window_size = 3600
+
+        [hardforks]
+        regolith_time = 0
+        canyon_time = 0
+        delta_time = 0
+        ecotone_time = 1700000100
+        fjord_time = 1700000200
+        granite_time = 1700000300
+        holocene_time = 1700000400
+
+        [optimism]
+        eip1559_elasticity = 6
+        eip1559_denominator = 50
+        eip1559_denominator_canyon = 250
+
+        [genesis]
+        l2_time = 1700000000
+        [genesis.l1]
+        hash = "0x1111111111111111111111111111111111111111111111111111111111111111"
+        number = 19000000
+        [genesis.l2]
+        hash = "0x2222222222222222222222222222222222222222222222222222222222222222"
+        number = 0
+        [genesis.system_config]
+        batcherAddress = "0x6017f75108f251a488b045a7ce2a7c15b179d1f2"
+        overhead = "0x0000000000000000000000000000000000000000000000000000000000000000"
+        scalar = "0x00000000000000000000000000000000000000000000000000000000000a6fe0"
+        gasLimit = 30000000
+    "#;
+
+    fn genesis() -> Genesis {
+        Genesis { timestamp: 1700000000, gas_limit: 30000000, ..Default::default() }
+    }
+
+    #[test]
+    fn rollup_json_matches_superchain_toml() {
+        let rollup = RollupConfig::from_rollup_json(ROLLUP_JSON).unwrap();
+        let superchain = RollupConfig::from_superchain_toml(SUPERCHAIN_TOML).unwrap();
+        assert_eq!(rollup, superchain);
+        assert_eq!(rollup.chain_id, 252);
+        assert_eq!(rollup.genesis.system_config.unwrap().gas_limit, 30000000);
+    }
+
+    #[test]
+    fn derives_hardfork_schedule() {
+        let config = RollupConfig::from_rollup_json(ROLLUP_JSON).unwrap();
+        let spec = OpChainSpec::from(config.apply_to_genesis(genesis()).unwrap());
+
+        assert_eq!(spec.chain().id(), 252);
+        assert_eq!(spec.fork(OpHardfork::Canyon), ForkCondition::Timestamp(0));
+        assert_eq!(spec.fork(OpHardfork::Ecotone), ForkCondition::Timestamp(1700000100));
+        assert_eq!(spec.fork(OpHardfork::Holocene), ForkCondition::Timestamp(1700000400));
+        assert_eq!(spec.fork(OpHardfork::Isthmus), ForkCondition::Never);
+    }
+
+    #[test]
+    fn checks_genesis_hash() {
+        let mut config = RollupConfig::from_rollup_json(ROLLUP_JSON).unwrap();
+        let err = config.clone().into_chain_spec(genesis()).unwrap_err();
+        assert!(err.to_string().contains("genesis hash"), "{err}");
+
+        let expected = OpChainSpec::from(config.apply_to_genesis(genesis()).unwrap());
+        config.genesis.l2.hash = expected.genesis_hash();
+        assert_eq!(
+            config.into_chain_spec(genesis()).unwrap().genesis_hash(),
+            expected.genesis_hash()
+        );
+    }
+
+    #[test]
+    fn checks_genesis_gas_limit() {
+        let config = RollupConfig::from_rollup_json(ROLLUP_JSON).unwrap();
+        let genesis = Genesis { gas_limit: 1, ..genesis() };
+        let err = config.apply_to_genesis(genesis).unwrap_err();
+        assert!(err.to_string().contains("gas limit"), "{err}");
+    }
+}
@@ -9,9 +9,11 @@ use alloy_op_evm::{
     block::{OpTxEnv, receipt_builder::OpReceiptBuilder},
     evm_env_for_op_block, evm_env_for_op_next_block,
 };
-use alloy_primitives::{Bytes, U256};
+use alloy_primitives::{B256, Bytes, U256};
 use core::fmt::Debug;
-use fraxtal_op_evm::{FraxtalBlockExecutorFactory, FraxtalEvmFactory};
+use fraxtal_op_evm::{
+    FraxtalBlockExecutionCtx, FraxtalBlockExecutorFactory, FraxtalEvmFactory, block::MigrationPlan,
+};
 use op_alloy_consensus::EIP1559ParamError;
 use op_alloy_rpc_types_engine::OpExecutionData;
 use op_revm::OpSpecId;
@@ -38,6 +40,15 @@ use revm::{
     primitives::hardfork::SpecId,
 };
 
+/// Looks up the timestamps of the parent blocks of executed blocks and payloads.
+///
+/// The block and payload execution contexts only carry the parent hash, the parent timestamp
+/// deciding whether a fork activates at the executed block is read from the chain.
+pub trait BlockTimestamps: Debug + Send + Sync {
+    /// Returns the timestamp of the block with the given hash, or `None` if it is unknown.
+    fn block_timestamp(&self, hash: B256) -> Option<u64>;
+}
+
 /// Optimism-related EVM configuration.
 #[derive(Debug)]
 pub struct FraxtalEvmConfig<
@@ -50,6 +61,8 @@ pub struct FraxtalEvmConfig<
     pub executor_factory: FraxtalBlockExecutorFactory<R, Arc<ChainSpec>, EvmFactory>,
     /// Optimism block assembler.
     pub block_assembler: OpBlockAssembler<ChainSpec>,
+    /// Source of the parent block timestamps of executed blocks and payloads.
+    pub block_timestamps: Option<Arc<dyn BlockTimestamps>>,
     #[doc(hidden)]
     pub _pd: core::marker::PhantomData<N>,
 }
@@ -61,6 +74,7 @@ impl<ChainSpec, N: NodePrimitives, R: Clone, EvmFactory: Clone> Clone
         Self {
             executor_factory: self.executor_factory.clone(),
             block_assembler: self.block_assembler.clone(),
+            block_timestamps: self.block_timestamps.clone(),
             _pd: self._pd,
         }
     }
@@ -83,9 +97,26 @@ impl<ChainSpec: OpHardforks, N: NodePrimitives, R> FraxtalEvmConfig<ChainSpec, N
                 chain_spec,
                 FraxtalEvmFactory::default(),
             ),
+            block_timestamps: None,
             _pd: core::marker::PhantomData,
         }
     }
+
+    /// Overrides the built-in fork migrations of the chain with the given plan.
+    pub fn with_migrations(mut self, migrations: impl Into<Arc<MigrationPlan>>) -> Self {
+        self.executor_factory = self.executor_factory.with_migrations(migrations);
+        self
+    }
+
+    /// Reads the parent block timestamps of executed blocks and payloads from the given source.
+    ///
+    /// Without it, or for parents the source doesn't know, executed blocks assume the parent
+    /// was produced [`FALLBACK_BLOCK_TIME`](fraxtal_op_evm::block::FALLBACK_BLOCK_TIME) seconds
+    /// earlier to tell whether a fork activates.
+    pub fn with_block_timestamps(mut self, block_timestamps: Arc<dyn BlockTimestamps>) -> Self {
+        self.block_timestamps = Some(block_timestamps);
+        self
+    }
 }
 
 impl<ChainSpec, N, R, EvmFactory> FraxtalEvmConfig<ChainSpec, N, R, EvmFactory>
@@ -97,6 +128,11 @@ where
     pub const fn chain_spec(&self) -> &Arc<ChainSpec> {
         self.executor_factory.spec()
     }
+
+    /// Returns the timestamp of the parent block with the given hash, if known.
+    fn parent_timestamp(&self, parent_hash: B256) -> Option<u64> {
+        self.block_timestamps.as_ref()?.block_timestamp(parent_hash)
+    }
 }
 
 impl<ChainSpec, N, R, EvmF> ConfigureEvm for FraxtalEvmConfig<ChainSpec, N, R, EvmF>
@@ -162,24 +198,30 @@ where
     fn context_for_block(
         &self,
         block: &'_ SealedBlock<N::Block>,
-    ) -> Result<OpBlockExecutionCtx, Self::Error> {
-        Ok(OpBlockExecutionCtx {
-            parent_hash: block.header().parent_hash(),
-            parent_beacon_block_root: block.header().parent_beacon_block_root(),
-            extra_data: block.header().extra_data().clone(),
-        })
+    ) -> Result<FraxtalBlockExecutionCtx, Self::Error> {
+        Ok(FraxtalBlockExecutionCtx::new(
+            OpBlockExecutionCtx {
+                parent_hash: block.header().parent_hash(),
+                parent_beacon_block_root: block.header().parent_beacon_block_root(),
+                extra_data: block.header().extra_data().clone(),
+            },
+            self.parent_timestamp(block.header().parent_hash()),
+        ))
     }
 
     fn context_for_next_block(
         &self,
         parent: &SealedHeader<N::BlockHeader>,
         attributes: Self::NextBlockEnvCtx,
-    ) -> Result<OpBlockExecutionCtx, Self::Error> {
-        Ok(OpBlockExecutionCtx {
-            parent_hash: parent.hash(),
-            parent_beacon_block_root: attributes.parent_beacon_block_root,
-            extra_data: attributes.extra_data,
-        })
+    ) -> Result<FraxtalBlockExecutionCtx, Self::Error> {
+        Ok(FraxtalBlockExecutionCtx::new(
+            OpBlockExecutionCtx {
+                parent_hash: parent.hash(),
+                parent_beacon_block_root: attributes.parent_beacon_block_root,
+                extra_data: attributes.extra_data,
+            },
+            Some(parent.timestamp()),
+        ))
     }
 }
 
@@ -240,11 +282,14 @@ where
         &self,
         payload: &'a OpExecutionData,
     ) -> Result<ExecutionCtxFor<'a, Self>, Self::Error> {
-        Ok(OpBlockExecutionCtx {
-            parent_hash: payload.parent_hash(),
-            parent_beacon_block_root: payload.sidecar.parent_beacon_block_root(),
-            extra_data: payload.payload.as_v1().extra_data.clone(),
-        })
+        Ok(FraxtalBlockExecutionCtx::new(
+            OpBlockExecutionCtx {
+                parent_hash: payload.parent_hash(),
+                parent_beacon_block_root: payload.sidecar.parent_beacon_block_root(),
+                extra_data: payload.payload.as_v1().extra_data.clone(),
+            },
+            self.parent_timestamp(payload.parent_hash()),
+        ))
     }
 
     fn tx_iterator_for_payload(
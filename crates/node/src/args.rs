@@ -0,0 +1,204 @@
+//! Command line arguments of the Fraxtal node.
+
+use std::path::PathBuf;
+
+use alloy_primitives::Address;
+use eyre::ensure;
+use reth_optimism_node::args::RollupArgs;
+
+use crate::{
+    dev::{DevL1Config, DevPayloadAttributes},
+    tx_gossip::{TxGossipMode, TxGossipPeers, TxGossipPolicy, TxGossipType, TxGossipTypes},
+};
+
+/// Fraxtal node arguments, extending the OP [`RollupArgs`].
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct FraxtalArgs {
+    /// OP rollup arguments.
+    #[command(flatten)]
+    pub rollup: RollupArgs,
+
+    /// Path to a TOML or JSON file describing the irregular state transitions applied at fork
+    /// boundaries.
+    ///
+    /// Replaces the built-in migrations of the chain, which only exist for the Fraxtal networks.
+    #[arg(long = "fraxtal.migration-plan", value_name = "PATH")]
+    pub migration_plan: Option<PathBuf>,
+
+    /// Path to a TOML file listing the peers to stay connected to, optionally marked as trusted
+    /// or static.
+    ///
+    /// The file is watched while the node runs: added peers are dialed and removed peers are
+    /// disconnected. While it doesn't exist, the built-in bootnodes of the chain are used.
+    #[arg(long = "fraxtal.peers-file", value_name = "PATH")]
+    pub peers_file: Option<PathBuf>,
+
+    /// Path to a TOML file with the allow/deny policy of the peers, by node id, IP range or
+    /// client version.
+    ///
+    /// The policy can be viewed and replaced at runtime with `admin_peerPolicy` and
+    /// `admin_setPeerPolicy`.
+    #[arg(long = "fraxtal.peer-policy", value_name = "PATH")]
+    pub peer_policy: Option<PathBuf>,
+
+    /// Peers pooled transactions are gossiped to.
+    ///
+    /// Defaults to all peers, or none with `--rollup.disable-tx-pool-gossip`.
+    #[arg(long = "fraxtal.tx-gossip", value_name = "PEERS")]
+    pub tx_gossip: Option<TxGossipPeers>,
+
+    /// Whether peers receive the full transactions or only their hashes.
+    #[arg(long = "fraxtal.tx-gossip-mode", value_name = "MODE", default_value = "full")]
+    pub tx_gossip_mode: TxGossipMode,
+
+    /// Comma separated transaction types that are gossiped, all of them if unset.
+    ///
+    /// Pooled transactions of the other types are still included in blocks, but never gossiped.
+    #[arg(long = "fraxtal.tx-gossip-types", value_name = "TYPES", value_delimiter = ',')]
+    pub tx_gossip_types: Option<Vec<TxGossipType>>,
+
+    /// Payload attributes of the blocks produced in dev mode.
+    #[command(flatten)]
+    pub dev: DevPayloadArgs,
+}
+
+impl FraxtalArgs {
+    /// Returns the transaction gossip policy.
+    ///
+    /// Fails if `--fraxtal.tx-gossip` enables gossip that `--rollup.disable-tx-pool-gossip`
+    /// disables.
+    pub fn tx_gossip_policy(&self) -> eyre::Result<TxGossipPolicy> {
+        let types = self
+            .tx_gossip_types
+            .as_ref()
+            .map_or(TxGossipTypes::ALL, |types| TxGossipTypes::new(types.iter().copied()));
+        let policy = TxGossipPolicy::from_disable_txpool_gossip(self.rollup.disable_txpool_gossip);
+        let Some(peers) = self.tx_gossip else {
+            return Ok(TxGossipPolicy { mode: self.tx_gossip_mode, types, ..policy });
+        };
+        ensure!(
+            !self.rollup.disable_txpool_gossip || peers == TxGossipPeers::None,
+            "--fraxtal.tx-gossip {peers:?} conflicts with --rollup.disable-tx-pool-gossip"
+        );
+        Ok(TxGossipPolicy::new(peers, self.tx_gossip_mode).with_types(types))
+    }
+}
+
+/// Payload attributes of the blocks produced with `--dev`.
+///
+/// Unset attributes take their defaults. All of them can be changed while the node runs with
+/// `dev_setPayloadAttributes`.
+#[derive(Debug, Clone, Default, clap::Args)]
+#[command(next_help_heading = "Dev payload attributes")]
+pub struct DevPayloadArgs {
+    /// EIP-1559 base fee max change denominator of the dev blocks [default: 250]
+    #[arg(
+        long = "fraxtal.dev.eip1559-denominator",
+        value_name = "DENOMINATOR",
+        env = "OP_DEV_EIP1559_DENOMINATOR"
+    )]
+    pub eip1559_denominator: Option<u32>,
+
+    /// EIP-1559 elasticity multiplier of the dev blocks [default: 6]
+    #[arg(
+        long = "fraxtal.dev.eip1559-elasticity",
+        value_name = "ELASTICITY",
+        env = "OP_DEV_EIP1559_ELASTICITY"
+    )]
+    pub eip1559_elasticity: Option<u32>,
+
+    /// Gas limit of the dev blocks, the one of the parent block if unset.
+    #[arg(long = "fraxtal.dev.gas-limit", value_name = "GAS", env = "OP_DEV_GAS_LIMIT")]
+    pub gas_limit: Option<u64>,
+
+    /// Minimum base fee of the dev blocks once Jovian is active [default: 0]
+    #[arg(long = "fraxtal.dev.min-base-fee", value_name = "WEI")]
+    pub min_base_fee: Option<u64>,
+
+    /// Fee recipient of the dev blocks, a random address per block if unset, or an address
+    /// derived from `--fraxtal.dev.seed` if set.
+    #[arg(long = "fraxtal.dev.fee-recipient", value_name = "ADDRESS")]
+    pub fee_recipient: Option<Address>,
+
+    /// Seconds between the timestamps of consecutive dev blocks, at least, or exactly with
+    /// `--fraxtal.dev.seed` [default: 1]
+    #[arg(long = "fraxtal.dev.block-time", value_name = "SECONDS")]
+    pub block_time: Option<u64>,
+
+    /// Seed making dev block production deterministic.
+    ///
+    /// Block timestamps follow a logical clock advancing by the block time from the parent
+    /// block instead of the wall clock, and `prevRandao`, the parent beacon block root and the
+    /// fee recipient are derived from the seed instead of random.
+    #[arg(long = "fraxtal.dev.seed", value_name = "SEED")]
+    pub seed: Option<u64>,
+
+    /// Seconds between the blocks of the simulated L1 chain [default: 12]
+    #[arg(long = "fraxtal.dev.l1-block-time", value_name = "SECONDS")]
+    pub l1_block_time: Option<u64>,
+
+    /// Base fee the simulated L1 base fees deviate from [default: 1000000000]
+    #[arg(long = "fraxtal.dev.l1-base-fee", value_name = "WEI")]
+    pub l1_base_fee: Option<u64>,
+
+    /// Blob base fee the simulated L1 blob base fees deviate from [default: 1]
+    #[arg(long = "fraxtal.dev.l1-blob-base-fee", value_name = "WEI")]
+    pub l1_blob_base_fee: Option<u64>,
+
+    /// Maximum deviation of the simulated L1 fees, in basis points [default: 1250]
+    #[arg(long = "fraxtal.dev.l1-fee-change-bps", value_name = "BPS")]
+    pub l1_fee_change_bps: Option<u16>,
+
+    /// Base fee scalar of the L1 data fee [default: 5227]
+    #[arg(long = "fraxtal.dev.base-fee-scalar", value_name = "SCALAR")]
+    pub base_fee_scalar: Option<u32>,
+
+    /// Blob base fee scalar of the L1 data fee [default: 1014213]
+    #[arg(long = "fraxtal.dev.blob-base-fee-scalar", value_name = "SCALAR")]
+    pub blob_base_fee_scalar: Option<u32>,
+
+    /// Operator fee scalar, from Isthmus on [default: 0]
+    #[arg(long = "fraxtal.dev.operator-fee-scalar", value_name = "SCALAR")]
+    pub operator_fee_scalar: Option<u32>,
+
+    /// Operator fee constant, from Isthmus on [default: 0]
+    #[arg(long = "fraxtal.dev.operator-fee-constant", value_name = "WEI")]
+    pub operator_fee_constant: Option<u64>,
+
+    /// DA footprint gas scalar, from Jovian on [default: 0]
+    #[arg(long = "fraxtal.dev.da-footprint-gas-scalar", value_name = "SCALAR")]
+    pub da_footprint_gas_scalar: Option<u16>,
+}
+
+impl DevPayloadArgs {
+    /// Returns the dev payload attributes, the unset ones taking their defaults.
+    pub fn attributes(&self) -> DevPayloadAttributes {
+        let defaults = DevPayloadAttributes::default();
+        let l1 = defaults.l1;
+        DevPayloadAttributes {
+            eip1559_denominator: self.eip1559_denominator.unwrap_or(defaults.eip1559_denominator),
+            eip1559_elasticity: self.eip1559_elasticity.unwrap_or(defaults.eip1559_elasticity),
+            gas_limit: self.gas_limit.or(defaults.gas_limit),
+            min_base_fee: self.min_base_fee.unwrap_or(defaults.min_base_fee),
+            fee_recipient: self.fee_recipient.or(defaults.fee_recipient),
+            block_time: self.block_time.unwrap_or(defaults.block_time),
+            seed: self.seed.or(defaults.seed),
+            l1: DevL1Config {
+                block_time: self.l1_block_time.unwrap_or(l1.block_time),
+                base_fee: self.l1_base_fee.unwrap_or(l1.base_fee),
+                blob_base_fee: self.l1_blob_base_fee.unwrap_or(l1.blob_base_fee),
+                fee_change_bps: self.l1_fee_change_bps.unwrap_or(l1.fee_change_bps),
+                base_fee_scalar: self.base_fee_scalar.unwrap_or(l1.base_fee_scalar),
+                blob_base_fee_scalar: self.blob_base_fee_scalar.unwrap_or(l1.blob_base_fee_scalar),
+                operator_fee_scalar: self.operator_fee_scalar.unwrap_or(l1.operator_fee_scalar),
+                operator_fee_constant: self
+                    .operator_fee_constant
+                    .unwrap_or(l1.operator_fee_constant),
+                da_footprint_gas_scalar: self
+                    .da_footprint_gas_scalar
+                    .unwrap_or(l1.da_footprint_gas_scalar),
+                ..l1
+            },
+        }
+    }
+}
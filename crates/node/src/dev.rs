@@ -0,0 +1,367 @@
+//! Payload attributes of the blocks produced in dev mode.
+//!
+//! The attributes are set with the `--fraxtal.dev.*` flags and validated at startup. While the
+//! node runs they can be read with `dev_payloadAttributes` and changed with
+//! `dev_setPayloadAttributes`, e.g. by integration tests exercising the base fee. The latter takes
+//! a [`DevPayloadAttributesPatch`], so the attributes it leaves out keep their current values.
+//!
+//! With a seed set, dev block production is deterministic: the timestamps follow a logical clock
+//! and the randomness of the payload attributes is derived from the seed, so two runs with the
+//! same seed and transactions produce the same chain.
+//!
+//! The L1 attributes deposit of the dev blocks is taken from a simulated L1 chain, configured by
+//! [`DevL1Config`].
+
+use std::sync::Arc;
+
+use alloy_primitives::{Address, B64};
+use eyre::ensure;
+use reth_chainspec::BaseFeeParams;
+use serde::{Deserialize, Deserializer, Serialize};
+use tokio::sync::watch;
+
+/// Payload attributes of the dev blocks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct DevPayloadAttributes {
+    /// EIP-1559 base fee max change denominator.
+    pub eip1559_denominator: u32,
+    /// EIP-1559 elasticity multiplier.
+    pub eip1559_elasticity: u32,
+    /// Gas limit of the blocks, the one of the parent block if unset.
+    pub gas_limit: Option<u64>,
+    /// Minimum base fee, applied once Jovian is active.
+    pub min_base_fee: u64,
+    /// Fee recipient of the blocks, a random address per block if unset, or an address derived
+    /// from the seed if set.
+    pub fee_recipient: Option<Address>,
+    /// Seconds between the timestamps of consecutive blocks: at least without a seed, exactly
+    /// with one.
+    pub block_time: u64,
+    /// Seed of deterministic block production, which replaces the wall clock by a logical clock
+    /// and randomness by values derived from the seed.
+    pub seed: Option<u64>,
+    /// Simulated L1 chain.
+    pub l1: DevL1Config,
+}
+
+/// Simulated L1 chain the L1 attributes deposits of the dev blocks are taken from.
+///
+/// A new L1 block is produced every `block_time` seconds of L2 time. Its base fee and blob base
+/// fee deviate from the configured ones by up to `fee_change_bps`, pseudo-randomly but
+/// deterministically from the L1 block number. The chain is continued from the L1 attributes
+/// deposit of the parent block, so changed fees apply from the next dev block on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct DevL1Config {
+    /// Number of the first simulated L1 block.
+    pub start_number: u64,
+    /// Seconds between simulated L1 blocks.
+    pub block_time: u64,
+    /// Base fee the simulated L1 base fees deviate from, in wei.
+    pub base_fee: u64,
+    /// Blob base fee the simulated L1 blob base fees deviate from, in wei.
+    pub blob_base_fee: u64,
+    /// Maximum deviation of the simulated L1 fees, in basis points.
+    pub fee_change_bps: u16,
+    /// Base fee scalar of the L1 data fee.
+    pub base_fee_scalar: u32,
+    /// Blob base fee scalar of the L1 data fee.
+    pub blob_base_fee_scalar: u32,
+    /// Operator fee scalar, from Isthmus on.
+    pub operator_fee_scalar: u32,
+    /// Operator fee constant, from Isthmus on.
+    pub operator_fee_constant: u64,
+    /// DA footprint gas scalar, from Jovian on.
+    pub da_footprint_gas_scalar: u16,
+    /// Batcher address committed to in the L1 attributes.
+    pub batcher: Address,
+}
+
+impl Default for DevL1Config {
+    fn default() -> Self {
+        Self {
+            start_number: 1,
+            block_time: 12,
+            base_fee: 1_000_000_000,
+            blob_base_fee: 1,
+            fee_change_bps: 1250,
+            base_fee_scalar: 5227,
+            blob_base_fee_scalar: 1_014_213,
+            operator_fee_scalar: 0,
+            operator_fee_constant: 0,
+            da_footprint_gas_scalar: 0,
+            batcher: Address::ZERO,
+        }
+    }
+}
+
+impl Default for DevPayloadAttributes {
+    fn default() -> Self {
+        let params = BaseFeeParams::optimism();
+        Self {
+            eip1559_denominator: params.max_change_denominator as u32,
+            eip1559_elasticity: params.elasticity_multiplier as u32,
+            gas_limit: None,
+            min_base_fee: 0,
+            fee_recipient: None,
+            block_time: 1,
+            seed: None,
+            l1: DevL1Config::default(),
+        }
+    }
+}
+
+impl DevPayloadAttributes {
+    /// Checks that blocks can be built with the attributes.
+    pub fn validate(&self) -> eyre::Result<()> {
+        ensure!(self.eip1559_denominator != 0, "EIP-1559 denominator must not be zero");
+        ensure!(self.eip1559_elasticity != 0, "EIP-1559 elasticity must not be zero");
+        ensure!(self.gas_limit != Some(0), "gas limit must not be zero");
+        ensure!(self.block_time != 0, "block time must not be zero");
+        ensure!(self.l1.block_time != 0, "L1 block time must not be zero");
+        ensure!(self.l1.base_fee != 0, "L1 base fee must not be zero");
+        ensure!(self.l1.blob_base_fee != 0, "L1 blob base fee must not be zero");
+        ensure!(self.l1.fee_change_bps <= 10_000, "L1 fee change must not exceed 10000 bps");
+        Ok(())
+    }
+
+    /// Returns the Holocene `eip1559Params` of the attributes: the denominator and the
+    /// elasticity, as big-endian `u32`s.
+    pub fn eip1559_params(&self) -> B64 {
+        let mut params = [0u8; 8];
+        params[..4].copy_from_slice(&self.eip1559_denominator.to_be_bytes());
+        params[4..].copy_from_slice(&self.eip1559_elasticity.to_be_bytes());
+        B64::from(params)
+    }
+}
+
+/// Changes to the dev payload attributes.
+///
+/// Attributes left out keep their current value. The optional attributes are unset with `null`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DevPayloadAttributesPatch {
+    /// EIP-1559 base fee max change denominator.
+    pub eip1559_denominator: Option<u32>,
+    /// EIP-1559 elasticity multiplier.
+    pub eip1559_elasticity: Option<u32>,
+    /// Gas limit of the blocks.
+    #[serde(default, deserialize_with = "some")]
+    pub gas_limit: Option<Option<u64>>,
+    /// Minimum base fee.
+    pub min_base_fee: Option<u64>,
+    /// Fee recipient of the blocks.
+    #[serde(default, deserialize_with = "some")]
+    pub fee_recipient: Option<Option<Address>>,
+    /// Seconds between the timestamps of consecutive blocks.
+    pub block_time: Option<u64>,
+    /// Seed of deterministic block production.
+    #[serde(default, deserialize_with = "some")]
+    pub seed: Option<Option<u64>>,
+    /// Simulated L1 chain.
+    #[serde(default)]
+    pub l1: DevL1ConfigPatch,
+}
+
+/// Changes to the simulated L1 chain, see [`DevPayloadAttributesPatch`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct DevL1ConfigPatch {
+    /// Number of the first simulated L1 block.
+    pub start_number: Option<u64>,
+    /// Seconds between simulated L1 blocks.
+    pub block_time: Option<u64>,
+    /// Base fee the simulated L1 base fees deviate from, in wei.
+    pub base_fee: Option<u64>,
+    /// Blob base fee the simulated L1 blob base fees deviate from, in wei.
+    pub blob_base_fee: Option<u64>,
+    /// Maximum deviation of the simulated L1 fees, in basis points.
+    pub fee_change_bps: Option<u16>,
+    /// Base fee scalar of the L1 data fee.
+    pub base_fee_scalar: Option<u32>,
+    /// Blob base fee scalar of the L1 data fee.
+    pub blob_base_fee_scalar: Option<u32>,
+    /// Operator fee scalar.
+    pub operator_fee_scalar: Option<u32>,
+    /// Operator fee constant.
+    pub operator_fee_constant: Option<u64>,
+    /// DA footprint gas scalar.
+    pub da_footprint_gas_scalar: Option<u16>,
+    /// Batcher address committed to in the L1 attributes.
+    pub batcher: Option<Address>,
+}
+
+/// Deserializes a present field, including `null`, as `Some`.
+fn some<'de, T, D>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+impl DevPayloadAttributesPatch {
+    /// Returns `attributes` with the changes applied.
+    pub fn apply(&self, attributes: DevPayloadAttributes) -> DevPayloadAttributes {
+        DevPayloadAttributes {
+            eip1559_denominator: self.eip1559_denominator.unwrap_or(attributes.eip1559_denominator),
+            eip1559_elasticity: self.eip1559_elasticity.unwrap_or(attributes.eip1559_elasticity),
+            gas_limit: self.gas_limit.unwrap_or(attributes.gas_limit),
+            min_base_fee: self.min_base_fee.unwrap_or(attributes.min_base_fee),
+            fee_recipient: self.fee_recipient.unwrap_or(attributes.fee_recipient),
+            block_time: self.block_time.unwrap_or(attributes.block_time),
+            seed: self.seed.unwrap_or(attributes.seed),
+            l1: self.l1.apply(attributes.l1),
+        }
+    }
+}
+
+impl DevL1ConfigPatch {
+    /// Returns `l1` with the changes applied.
+    pub fn apply(&self, l1: DevL1Config) -> DevL1Config {
+        DevL1Config {
+            start_number: self.start_number.unwrap_or(l1.start_number),
+            block_time: self.block_time.unwrap_or(l1.block_time),
+            base_fee: self.base_fee.unwrap_or(l1.base_fee),
+            blob_base_fee: self.blob_base_fee.unwrap_or(l1.blob_base_fee),
+            fee_change_bps: self.fee_change_bps.unwrap_or(l1.fee_change_bps),
+            base_fee_scalar: self.base_fee_scalar.unwrap_or(l1.base_fee_scalar),
+            blob_base_fee_scalar: self.blob_base_fee_scalar.unwrap_or(l1.blob_base_fee_scalar),
+            operator_fee_scalar: self.operator_fee_scalar.unwrap_or(l1.operator_fee_scalar),
+            operator_fee_constant: self.operator_fee_constant.unwrap_or(l1.operator_fee_constant),
+            da_footprint_gas_scalar: self
+                .da_footprint_gas_scalar
+                .unwrap_or(l1.da_footprint_gas_scalar),
+            batcher: self.batcher.unwrap_or(l1.batcher),
+        }
+    }
+}
+
+/// Shared handle to the dev payload attributes, replaceable at runtime.
+#[derive(Debug, Clone)]
+pub struct DevPayloadAttributesHandle {
+    attributes: Arc<watch::Sender<DevPayloadAttributes>>,
+}
+
+impl Default for DevPayloadAttributesHandle {
+    fn default() -> Self {
+        Self { attributes: Arc::new(watch::Sender::new(DevPayloadAttributes::default())) }
+    }
+}
+
+impl DevPayloadAttributesHandle {
+    /// Creates a handle to `attributes`, failing if they are invalid.
+    pub fn new(attributes: DevPayloadAttributes) -> eyre::Result<Self> {
+        attributes.validate()?;
+        Ok(Self { attributes: Arc::new(watch::Sender::new(attributes)) })
+    }
+
+    /// Returns the current attributes.
+    pub fn current(&self) -> DevPayloadAttributes {
+        *self.attributes.borrow()
+    }
+
+    /// Replaces the attributes, used from the next block on, failing if they are invalid.
+    pub fn set(&self, attributes: DevPayloadAttributes) -> eyre::Result<()> {
+        attributes.validate()?;
+        self.attributes.send_replace(attributes);
+        Ok(())
+    }
+
+    /// Applies `patch` to the current attributes, used from the next block on, returning the
+    /// updated attributes. Fails, leaving the attributes unchanged, if they would be invalid.
+    pub fn update(&self, patch: &DevPayloadAttributesPatch) -> eyre::Result<DevPayloadAttributes> {
+        let mut result = Ok(());
+        let mut updated = DevPayloadAttributes::default();
+        self.attributes.send_if_modified(|attributes| {
+            updated = patch.apply(*attributes);
+            result = updated.validate();
+            if result.is_ok() {
+                *attributes = updated;
+            }
+            result.is_ok()
+        });
+        result.map(|()| updated)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_attributes() {
+        let handle = DevPayloadAttributesHandle::default();
+        let attributes = DevPayloadAttributes { eip1559_denominator: 0, ..Default::default() };
+        assert!(handle.set(attributes).is_err());
+        assert!(DevPayloadAttributesHandle::new(attributes).is_err());
+        assert_eq!(handle.current(), DevPayloadAttributes::default());
+
+        let attributes = DevPayloadAttributes { gas_limit: Some(0), ..Default::default() };
+        assert!(attributes.validate().is_err());
+        let attributes = DevPayloadAttributes { block_time: 0, ..Default::default() };
+        assert!(attributes.validate().is_err());
+        let l1 = DevL1Config { fee_change_bps: 10_001, ..Default::default() };
+        assert!(DevPayloadAttributes { l1, ..Default::default() }.validate().is_err());
+    }
+
+    #[test]
+    fn patches_current_attributes() {
+        let handle = DevPayloadAttributesHandle::new(DevPayloadAttributes {
+            fee_recipient: Some(Address::repeat_byte(1)),
+            block_time: 2,
+            seed: Some(7),
+            ..Default::default()
+        })
+        .unwrap();
+
+        let patch: DevPayloadAttributesPatch =
+            serde_json::from_str(r#"{"gasLimit": 30000000, "seed": null, "l1": {"baseFee": 7}}"#)
+                .unwrap();
+        let updated = handle.update(&patch).unwrap();
+        assert_eq!(handle.current(), updated);
+        assert_eq!(
+            updated,
+            DevPayloadAttributes {
+                gas_limit: Some(30_000_000),
+                fee_recipient: Some(Address::repeat_byte(1)),
+                block_time: 2,
+                seed: None,
+                l1: DevL1Config { base_fee: 7, ..Default::default() },
+                ..Default::default()
+            }
+        );
+
+        // An invalid update leaves the attributes unchanged.
+        let patch = DevPayloadAttributesPatch { block_time: Some(0), ..Default::default() };
+        assert!(handle.update(&patch).is_err());
+        assert_eq!(handle.current(), updated);
+        assert!(serde_json::from_str::<DevPayloadAttributesPatch>(r#"{"gas": 1}"#).is_err());
+    }
+
+    #[test]
+    fn encodes_eip1559_params() {
+        let attributes = DevPayloadAttributes {
+            eip1559_denominator: 50,
+            eip1559_elasticity: 6,
+            ..Default::default()
+        };
+        assert_eq!(attributes.eip1559_params(), B64::from([0, 0, 0, 50, 0, 0, 0, 6]));
+    }
+
+    #[test]
+    fn deserializes_partial_attributes() {
+        let attributes: DevPayloadAttributes =
+            serde_json::from_str(r#"{"gasLimit": 30000000, "minBaseFee": 100}"#).unwrap();
+        assert_eq!(
+            attributes,
+            DevPayloadAttributes {
+                gas_limit: Some(30_000_000),
+                min_base_fee: 100,
+                ..Default::default()
+            }
+        );
+        assert!(serde_json::from_str::<DevPayloadAttributes>(r#"{"gas": 1}"#).is_err());
+    }
+}
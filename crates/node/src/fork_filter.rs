@@ -0,0 +1,180 @@
+//! EIP-2124 fork id filtering of peers.
+//!
+//! The discovery bootstraps are shared with the other OP Stack chains, so their peers are
+//! discovered too. discv5 advertises the fork id of the Fraxtal chain spec under the OP execution
+//! layer ENR key and reads the fork id of discovered peers from it, see [`discv5_fork_id_filter`].
+//! Discovered peers whose ENR advertises a fork id that is incompatible with the Fraxtal chain
+//! spec are not added to the peer set, and the eth handshake rejects peers with another genesis,
+//! chain id or an incompatible fork id. Peers discovered without a fork id, e.g. over discv4, can
+//! only be checked in the handshake.
+//!
+//! Both rejections are counted in [`ForkFilterMetrics`], and discovered peers with an
+//! incompatible fork id are recorded in the [`PeerScores`]. The network removes the peers
+//! rejected in the handshake from the peer set and bans them for
+//! [`ReputationConfig::ban_duration`](crate::reputation::ReputationConfig::ban_duration).
+
+use crate::reputation::PeerScores;
+use futures_util::StreamExt;
+use reth_discv5::{ConfigBuilder, NetworkStackId};
+use reth_eth_wire::{
+    EthStreamError, UnifiedStatus,
+    errors::EthHandshakeError,
+    handshake::{EthHandshake, EthRlpxHandshake, UnauthEth},
+};
+use reth_ethereum_forks::{ForkFilter, ForkId, Head};
+use reth_metrics::{Metrics, metrics::Counter};
+use reth_network::{NetworkHandle, NetworkPrimitives};
+use reth_network_api::{DiscoveredEvent, DiscoveryEvent};
+use reth_tasks::TaskExecutor;
+use std::{future::Future, pin::Pin, time::Duration};
+use tracing::{debug, trace};
+
+/// ENR keys of the nodes sharing the discovery bootstraps that are never Fraxtal execution layer
+/// peers: Ethereum beacon nodes and OP Stack consensus layer nodes.
+pub const EXCLUDED_ENR_KEYS: [&[u8]; 2] = [NetworkStackId::ETH2, NetworkStackId::OPSTACK];
+
+/// Configures discv5 to advertise `fork_id` under the OP execution layer ENR key, and to read the
+/// fork id of the discovered peers from the same key.
+///
+/// `fork_id` is the latest fork id of the chain spec, as the network advertises it. The network
+/// then drops discovered peers with an incompatible fork id before they reach the peer set, and
+/// peers with any of the [`EXCLUDED_ENR_KEYS`] are not surfaced by discv5 at all.
+pub fn discv5_fork_id_filter(builder: ConfigBuilder, fork_id: ForkId) -> ConfigBuilder {
+    builder.fork(NetworkStackId::OPEL, fork_id).must_not_include_keys(&EXCLUDED_ENR_KEYS)
+}
+
+/// Peers rejected for not following the Fraxtal chain.
+#[derive(Metrics, Clone, Debug)]
+#[metrics(scope = "fraxtal.network")]
+pub struct ForkFilterMetrics {
+    /// Discovered peers whose ENR advertises an incompatible fork id.
+    pub discovered_incompatible_fork_id: Counter,
+    /// Peers rejected in the eth handshake for an incompatible fork id.
+    pub handshake_incompatible_fork_id: Counter,
+    /// Peers rejected in the eth handshake for another genesis.
+    pub handshake_mismatched_genesis: Counter,
+    /// Peers rejected in the eth handshake for another chain id.
+    pub handshake_mismatched_chain: Counter,
+}
+
+impl ForkFilterMetrics {
+    /// Counts the handshake `error` if it rejected a peer of another chain, returning whether it
+    /// did.
+    pub fn record_handshake_error(&self, error: &EthStreamError) -> bool {
+        let EthStreamError::EthHandshakeError(error) = error else { return false };
+        match error {
+            EthHandshakeError::InvalidFork(_) => self.handshake_incompatible_fork_id.increment(1),
+            EthHandshakeError::MismatchedGenesis(_) => {
+                self.handshake_mismatched_genesis.increment(1)
+            }
+            EthHandshakeError::MismatchedChain(_) => self.handshake_mismatched_chain.increment(1),
+            _ => return false,
+        }
+        true
+    }
+}
+
+/// Eth handshake counting the peers rejected for not following the Fraxtal chain.
+///
+/// The fork id validation itself is done by the wrapped [`EthHandshake`], against the fork
+/// filter the network derives from the chain spec.
+#[derive(Debug, Default)]
+pub struct ForkIdHandshake {
+    inner: EthHandshake,
+    metrics: ForkFilterMetrics,
+}
+
+impl ForkIdHandshake {
+    /// Creates the handshake, recording rejections in `metrics`.
+    pub fn new(metrics: ForkFilterMetrics) -> Self {
+        Self { inner: EthHandshake::default(), metrics }
+    }
+}
+
+impl EthRlpxHandshake for ForkIdHandshake {
+    fn handshake<'a>(
+        &'a self,
+        unauth: &'a mut dyn UnauthEth,
+        status: UnifiedStatus,
+        fork_filter: ForkFilter,
+        timeout_limit: Duration,
+    ) -> Pin<Box<dyn Future<Output = Result<UnifiedStatus, EthStreamError>> + 'a + Send>> {
+        Box::pin(async move {
+            let result = self.inner.handshake(unauth, status, fork_filter, timeout_limit).await;
+            if let Err(err) = &result
+                && self.metrics.record_handshake_error(err)
+            {
+                debug!(target: "fraxtal::network", %err, "Rejected peer of another chain");
+            }
+            result
+        })
+    }
+}
+
+/// Spawns a task counting the discovered peers that advertise a fork id incompatible with
+/// `fork_filter`, and recording them in the peer scores.
+///
+/// The forks of the OP Stack activate by timestamp, so like the fork filter of the network,
+/// `fork_filter` is moved to the canonical head returned by `head` before each check.
+///
+/// The network doesn't add these peers to the peer set, so they have no reputation to penalize.
+pub fn spawn_discovery_filter_metrics<N: NetworkPrimitives>(
+    executor: &TaskExecutor,
+    network: NetworkHandle<N>,
+    mut fork_filter: ForkFilter,
+    head: impl Fn() -> Option<Head> + Send + 'static,
+    metrics: ForkFilterMetrics,
+    scores: PeerScores,
+) {
+    let mut events = network.discovery_listener();
+    executor.spawn_critical_task("fraxtal-fork-filter-metrics", async move {
+        while let Some(event) = events.next().await {
+            let DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued {
+                peer_id,
+                fork_id: Some(fork_id),
+                ..
+            }) = event
+            else {
+                continue
+            };
+            if let Some(head) = head() {
+                fork_filter.set_head(head);
+            }
+            if let Err(err) = fork_filter.validate(fork_id) {
+                trace!(target: "fraxtal::network", %peer_id, %err, "Discovered peer of another chain");
+                metrics.discovered_incompatible_fork_id.increment(1);
+                scores.record_fork_id_mismatch(peer_id);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::B256;
+    use reth_ethereum_forks::{ForkHash, ForkId, ValidationError};
+    use reth_primitives_traits::{GotExpected, GotExpectedBoxed};
+
+    #[test]
+    fn records_rejections() {
+        let metrics = ForkFilterMetrics::default();
+        let fork_id = ForkId { hash: ForkHash([0; 4]), next: 0 };
+
+        let invalid_fork = EthStreamError::EthHandshakeError(EthHandshakeError::InvalidFork(
+            ValidationError::LocalIncompatibleOrStale { local: fork_id, remote: fork_id },
+        ));
+        assert!(metrics.record_handshake_error(&invalid_fork));
+
+        let mismatched_genesis = EthStreamError::EthHandshakeError(
+            EthHandshakeError::MismatchedGenesis(GotExpectedBoxed(Box::new(GotExpected {
+                got: B256::ZERO,
+                expected: B256::repeat_byte(1),
+            }))),
+        );
+        assert!(metrics.record_handshake_error(&mismatched_genesis));
+
+        let unrelated = EthStreamError::EthHandshakeError(EthHandshakeError::NoResponse);
+        assert!(!metrics.record_handshake_error(&unrelated));
+    }
+}
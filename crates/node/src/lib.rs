@@ -1,3 +1,14 @@
+pub mod args;
+pub mod dev;
+pub mod fork_filter;
+pub mod migrations;
 pub mod network;
 pub mod node;
+pub mod peer_policy;
+pub mod peers;
+pub mod pool;
 pub mod proof_history;
+pub mod reports;
+pub mod reputation;
+pub mod rpc;
+pub mod tx_gossip;
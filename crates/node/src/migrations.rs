@@ -0,0 +1,136 @@
+//! Loading of fork migration plans from disk.
+
+use std::path::Path;
+
+use eyre::{WrapErr, ensure};
+use fraxtal_op_evm::block::MigrationPlan;
+
+/// Reads a [`MigrationPlan`] from the given file.
+///
+/// Files with a `.json` extension are parsed as JSON, everything else as TOML.
+pub fn load_migration_plan(path: &Path) -> eyre::Result<MigrationPlan> {
+    let contents = std::fs::read_to_string(path)
+        .wrap_err_with(|| format!("failed to read migration plan {}", path.display()))?;
+    let plan = parse_migration_plan(&contents, path.extension().is_some_and(|ext| ext == "json"))
+        .wrap_err_with(|| format!("invalid migration plan {}", path.display()))?;
+    Ok(plan)
+}
+
+fn parse_migration_plan(contents: &str, json: bool) -> eyre::Result<MigrationPlan> {
+    let plan: MigrationPlan =
+        if json { serde_json::from_str(contents)? } else { toml::from_str(contents)? };
+    ensure!(plan.is_ordered(), "forks must be unique and ordered by activation");
+    Ok(plan)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256, bytes};
+    use fraxtal_op_evm::block::transition::{
+        BytecodePatch, CodeHashCheck, IrregularStateTransition, ProxyUpgrade, StorageWrite,
+    };
+    use reth_optimism_forks::OpHardfork;
+
+    #[test]
+    fn parse_toml_plan() {
+        let plan = parse_migration_plan(
+            r#"
+            [[forks]]
+            fork = "granite"
+
+            [[forks.transitions]]
+            type = "proxy_upgrade"
+            address = "0xfc00000000000000000000000000000000000001"
+
+            [[forks.transitions]]
+            type = "storage_write"
+            address = "0xfc00000000000000000000000000000000000001"
+            slot = "0x0000000000000000000000000000000000000000000000000000000000000003"
+            value = "0x4672617820555344000000000000000000000000000000000000000000000010"
+            "#,
+            false,
+        )
+        .unwrap();
+
+        let token = address!("fc00000000000000000000000000000000000001");
+        let transitions: Vec<_> = plan.transitions(OpHardfork::Granite).cloned().collect();
+        assert_eq!(
+            transitions,
+            vec![
+                IrregularStateTransition::ProxyUpgrade(ProxyUpgrade::fraxtal(token)),
+                IrregularStateTransition::StorageWrite(StorageWrite {
+                    address: token,
+                    slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+                    value: b256!(
+                        "4672617820555344000000000000000000000000000000000000000000000010"
+                    ),
+                }),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_json_plan() {
+        let plan = parse_migration_plan(
+            r#"{"forks":[{"fork":"Holocene","transitions":[{"type":"force_deploy","address":"0x13b0D85CcB8bf860b6b79AF3029fCA081AE9beF2","code":"0x00"}]}]}"#,
+            true,
+        )
+        .unwrap();
+        assert_eq!(plan.transitions(OpHardfork::Holocene).count(), 1);
+        assert!(plan.transitions(OpHardfork::Granite).next().is_none());
+    }
+
+    #[test]
+    fn parse_expected_code_hashes() {
+        let plan = parse_migration_plan(
+            r#"
+            [[forks]]
+            fork = "holocene"
+
+            [[forks.transitions]]
+            type = "bytecode_patch"
+            address = "0xfc00000000000000000000000000000000000001"
+            offset = 4
+            value = "0xff"
+            expected = { pre = "0x1111111111111111111111111111111111111111111111111111111111111111" }
+            "#,
+            false,
+        )
+        .unwrap();
+
+        let transitions: Vec<_> = plan.transitions(OpHardfork::Holocene).cloned().collect();
+        assert_eq!(
+            transitions,
+            vec![IrregularStateTransition::BytecodePatch(BytecodePatch {
+                address: address!("fc00000000000000000000000000000000000001"),
+                offset: 4,
+                value: bytes!("ff"),
+                expected: CodeHashCheck {
+                    pre: Some(b256!(
+                        "1111111111111111111111111111111111111111111111111111111111111111"
+                    )),
+                    post: None,
+                },
+            })]
+        );
+    }
+
+    #[test]
+    fn reject_unordered_plan() {
+        let err = parse_migration_plan(
+            r#"
+            [[forks]]
+            fork = "holocene"
+            transitions = []
+
+            [[forks]]
+            fork = "granite"
+            transitions = []
+            "#,
+            false,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("ordered"));
+    }
+}
@@ -3,9 +3,37 @@
 //! Mirrors `reth_optimism_node::node::OpNetworkBuilder` but overrides the fallback bootnodes
 //! (`mainnet_nodes()` in upstream reth) with Fraxtal's bootnodes for known chain ids.
 //! The user-supplied `--bootnodes` CLI flag still takes precedence.
+//!
+//! Chains that publish an EIP-1459 DNS discovery tree additionally discover peers through it,
+//! unless `--disable-dns-discovery` is set.
+//!
+//! Peers of other chains are rejected by their EIP-2124 fork id, see [`crate::fork_filter`].
+//!
+//! Peers are checked against the peer policy, see [`crate::peer_policy`], and scored with
+//! Fraxtal-specific reputation weights and penalties, see [`crate::reputation`].
+//!
+//! Pooled transactions are gossiped according to the [`TxGossipPolicy`].
+//!
+//! With a peers file configured, the peers of the file replace the built-in bootnodes and are
+//! kept in sync with the file while the node runs, see [`crate::peers`].
+
+use std::{collections::HashSet, path::PathBuf, sync::Arc};
 
-use fraxtal_chainspec::fraxtal_bootnodes;
+use crate::{
+    fork_filter::{
+        ForkFilterMetrics, ForkIdHandshake, discv5_fork_id_filter, spawn_discovery_filter_metrics,
+    },
+    peer_policy::{PeerPolicyHandle, spawn_peer_policy},
+    peers::{PeersFileWatcher, spawn_peers_file_watcher},
+    reputation::{PeerScores, spawn_peer_scoring},
+    tx_gossip::TxGossipPolicy,
+};
+use alloy_consensus::BlockHeader;
+use eyre::WrapErr;
+use fraxtal_chainspec::{fraxtal_bootnodes, fraxtal_dns_networks};
 use reth_chainspec::{EthChainSpec, Hardforks};
+use reth_dns_discovery::{DnsDiscoveryConfig, tree::LinkEntry};
+use reth_ethereum_forks::Head;
 use reth_network::{
     NetworkConfig, NetworkHandle, NetworkManager, NetworkPrimitives, PeersInfo,
     types::BasicNetworkPrimitives,
@@ -16,25 +44,78 @@ use reth_node_builder::{
     components::NetworkBuilder,
     node::{FullNodeTypes, NodeTypes},
 };
+use reth_provider::{BlockNumReader, HeaderProvider};
 use reth_transaction_pool::{PoolPooledTx, PoolTransaction, TransactionPool};
 use tracing::info;
 
 /// Network builder for Fraxtal nodes.
 #[derive(Debug, Default, Clone)]
 pub struct FraxtalNetworkBuilder {
-    /// Disable transaction pool gossip.
-    pub disable_txpool_gossip: bool,
+    /// Policy for gossiping pooled transactions.
+    pub tx_gossip: TxGossipPolicy,
     /// Disable discovery v4.
     pub disable_discovery_v4: bool,
+    /// Peers file watched for peers to dial and disconnect at runtime.
+    pub peers_file: Option<PathBuf>,
+    /// Allow/deny policy applied to inbound and outbound peers.
+    pub peer_policy: PeerPolicyHandle,
+    /// Reputation configuration and scores of the peers.
+    pub peer_scores: PeerScores,
 }
 
 impl FraxtalNetworkBuilder {
     /// Creates a new `FraxtalNetworkBuilder`.
-    pub const fn new(disable_txpool_gossip: bool, disable_discovery_v4: bool) -> Self {
-        Self { disable_txpool_gossip, disable_discovery_v4 }
+    pub const fn new(tx_gossip: TxGossipPolicy, disable_discovery_v4: bool) -> Self {
+        Self {
+            tx_gossip,
+            disable_discovery_v4,
+            peers_file: None,
+            peer_policy: PeerPolicyHandle::default(),
+            peer_scores: PeerScores::default(),
+        }
+    }
+
+    /// Sets the reputation configuration and scores of the peers.
+    pub fn with_peer_scores(mut self, peer_scores: PeerScores) -> Self {
+        self.peer_scores = peer_scores;
+        self
+    }
+
+    /// Sets the allow/deny policy applied to inbound and outbound peers.
+    pub fn with_peer_policy(mut self, peer_policy: PeerPolicyHandle) -> Self {
+        self.peer_policy = peer_policy;
+        self
+    }
+
+    /// Sets the peers file watched for peers to dial and disconnect at runtime.
+    pub fn with_peers_file(mut self, peers_file: Option<PathBuf>) -> Self {
+        self.peers_file = peers_file;
+        self
+    }
+
+    /// Loads the peers file, falling back to the bootnodes of `--bootnodes`, or the built-in
+    /// bootnodes of the chain if unset, while it doesn't exist.
+    fn peers_file_watcher<Node>(
+        &self,
+        ctx: &BuilderContext<Node>,
+    ) -> eyre::Result<Option<PeersFileWatcher>>
+    where
+        Node: FullNodeTypes<Types: NodeTypes<ChainSpec: Hardforks + EthChainSpec>>,
+    {
+        let Some(path) = &self.peers_file else { return Ok(None) };
+        let fallback = ctx
+            .config()
+            .network
+            .resolved_bootnodes()
+            .or_else(|| fraxtal_bootnodes(&*ctx.chain_spec()))
+            .unwrap_or_default();
+        PeersFileWatcher::new(path, fallback).map(Some)
     }
 
-    /// Builds the [`NetworkConfig`] for the node, injecting Fraxtal bootnodes for known chains.
+    /// Builds the [`NetworkConfig`] for the node, injecting Fraxtal bootnodes for known chains, or
+    /// the peers of the peers file if it exists.
+    ///
+    /// Bootnodes rejected by the peer policy are skipped.
     pub fn network_config<Node, NetworkP>(
         &self,
         ctx: &BuilderContext<Node>,
@@ -43,15 +124,36 @@ impl FraxtalNetworkBuilder {
         Node: FullNodeTypes<Types: NodeTypes<ChainSpec: Hardforks + EthChainSpec>>,
         NetworkP: NetworkPrimitives,
     {
-        let disable_txpool_gossip = self.disable_txpool_gossip;
+        let peers_file = self.peers_file_watcher(ctx)?;
+        self.network_config_with_peers(ctx, peers_file.as_ref())
+    }
+
+    /// Builds the [`NetworkConfig`] for the node, bootstrapping from the peers of the loaded
+    /// peers file instead of the built-in bootnodes.
+    fn network_config_with_peers<Node, NetworkP>(
+        &self,
+        ctx: &BuilderContext<Node>,
+        peers_file: Option<&PeersFileWatcher>,
+    ) -> eyre::Result<NetworkConfig<Node::Provider, NetworkP>>
+    where
+        Node: FullNodeTypes<Types: NodeTypes<ChainSpec: Hardforks + EthChainSpec>>,
+        NetworkP: NetworkPrimitives,
+    {
         let disable_discovery_v4 = self.disable_discovery_v4;
         let args = &ctx.config().network;
 
         let chain_id = ctx.chain_spec().chain().id();
         // Respect --bootnodes when set; otherwise fall back to Fraxtal's built-in list
         // (upstream reth would fall back to Ethereum mainnet bootnodes here).
-        let fraxtal_nodes =
-            args.bootnodes.is_none().then(|| fraxtal_bootnodes(chain_id)).flatten();
+        let file_nodes = peers_file
+            .filter(|watcher| watcher.is_loaded())
+            .map(|watcher| watcher.peers().into_iter().map(|peer| peer.record).collect::<Vec<_>>());
+        let fraxtal_nodes = args
+            .bootnodes
+            .is_none()
+            .then(|| file_nodes.or_else(|| fraxtal_bootnodes(&*ctx.chain_spec())))
+            .flatten()
+            .map(|nodes| self.peer_policy.current().filter_nodes(nodes));
 
         let mut builder = ctx.network_config_builder()?;
         if let Some(nodes) = &fraxtal_nodes {
@@ -64,6 +166,26 @@ impl FraxtalNetworkBuilder {
             builder = builder.boot_nodes(nodes.clone());
         }
 
+        let dns_networks =
+            fraxtal_dns_networks(&*ctx.chain_spec()).filter(|networks| !networks.is_empty());
+        if let Some(networks) = dns_networks
+            && !args.discovery.disable_dns_discovery
+        {
+            info!(
+                target: "reth::cli",
+                count = networks.len(),
+                chain_id,
+                "Using Fraxtal DNS discovery trees",
+            );
+            builder = builder.dns_discovery(dns_discovery_config(&networks)?);
+        }
+
+        // Count the peers of other chains rejected in the eth handshake.
+        builder = builder
+            .eth_rlpx_handshake(Arc::new(ForkIdHandshake::new(ForkFilterMetrics::default())));
+
+        // discv5 advertises and filters by the fork id of the chain spec.
+        let fork_id = ctx.chain_spec().latest_fork_id();
         let network_builder = builder.apply(|mut builder| {
             let rlpx_socket = (args.addr, args.port).into();
             if disable_discovery_v4 || args.discovery.disable_discovery {
@@ -75,15 +197,20 @@ impl FraxtalNetworkBuilder {
                     .or(fraxtal_nodes)
                     .or_else(|| ctx.chain_spec().bootnodes())
                     .unwrap_or_default();
-                builder = builder.discovery_v5(
+                builder = builder.discovery_v5(discv5_fork_id_filter(
                     args.discovery.discovery_v5_builder(rlpx_socket, discv5_bootnodes),
-                );
+                    fork_id,
+                ));
             }
             builder
         });
 
         let mut network_config = ctx.build_network_config(network_builder);
-        network_config.tx_gossip_disabled = disable_txpool_gossip;
+        network_config.tx_gossip_disabled = self.tx_gossip.is_disabled();
+        let reputation = self.peer_scores.config();
+        network_config.peers_config.reputation_weights = reputation.weights;
+        network_config.peers_config.ban_duration = reputation.ban_duration;
+        self.peer_policy.current().restrict_peers_config(&mut network_config.peers_config);
 
         Ok(network_config)
     }
@@ -104,10 +231,121 @@ where
         ctx: &BuilderContext<Node>,
         pool: Pool,
     ) -> eyre::Result<Self::Network> {
-        let network_config = self.network_config(ctx)?;
+        let peers_file = self.peers_file_watcher(ctx)?;
+        let network_config = self.network_config_with_peers(ctx, peers_file.as_ref())?;
         let network = NetworkManager::builder(network_config).await?;
-        let handle = ctx.start_network(network, pool);
+        let tx_config = self
+            .tx_gossip
+            .transactions_manager_config(ctx.config().network.transactions_manager_config());
+        let handle =
+            ctx.start_network_with(network, pool, tx_config, self.tx_gossip.propagation_kind());
         info!(target: "reth::cli", enode = %handle.local_node_record(), "P2P networking initialized");
+        let provider = ctx.provider().clone();
+        spawn_discovery_filter_metrics(
+            ctx.task_executor(),
+            handle.clone(),
+            ctx.chain_spec().fork_filter(ctx.head()),
+            move || {
+                let number = provider.best_block_number().ok()?;
+                let header = provider.sealed_header(number).ok()??;
+                Some(Head {
+                    number,
+                    hash: header.hash(),
+                    timestamp: header.timestamp(),
+                    ..Default::default()
+                })
+            },
+            ForkFilterMetrics::default(),
+            self.peer_scores.clone(),
+        );
+        let provider = ctx.provider().clone();
+        spawn_peer_scoring(
+            ctx.task_executor(),
+            handle.clone(),
+            self.peer_scores.clone(),
+            move || provider.best_block_number().ok(),
+        );
+        spawn_peer_policy(ctx.task_executor(), handle.clone(), self.peer_policy.clone());
+        if let Some(watcher) = peers_file {
+            spawn_peers_file_watcher(
+                ctx.task_executor(),
+                handle.clone(),
+                watcher,
+                self.peer_policy.clone(),
+            );
+        }
         Ok(handle)
     }
 }
+
+/// Returns the DNS discovery config syncing the given `enrtree://` links.
+pub fn dns_discovery_config(networks: &[String]) -> eyre::Result<DnsDiscoveryConfig> {
+    let links = networks
+        .iter()
+        .map(|link| {
+            link.parse::<LinkEntry>().wrap_err_with(|| format!("invalid DNS discovery tree {link}"))
+        })
+        .collect::<eyre::Result<HashSet<_>>>()?;
+    Ok(DnsDiscoveryConfig { bootstrap_dns_networks: Some(links), ..Default::default() })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reth_dns_discovery::{DnsDiscoveryService, Resolver};
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    /// Tree of the EIP-1459 example.
+    const LINK: &str =
+        "enrtree://AKA3AM6LPBYEUDMVNU3BSVQJ5AD45Y7YPOHJLEF6W26QOE4VTUDPE@nodes.example.org";
+    const ROOT: &str = "enrtree-root:v1 e=JWXYDBPXYWG6FX3GMDIBFA6CJ4 l=C7HRFPF3BLGF3YR4DY5KX3SMBE seq=1 sig=o908WmNp7LibOfPsr4btQwatZJ5URBr2ZAuxvK4UWHlsB9sUOTJQaGAlLPVAhM__XJesCHxLISo94z5Z2a463gA";
+
+    /// Resolver serving fixed TXT records and recording every lookup.
+    #[derive(Debug, Default)]
+    struct StubResolver {
+        records: HashMap<String, String>,
+        queries: Mutex<Vec<String>>,
+    }
+
+    impl StubResolver {
+        fn queried(&self, name: &str) -> bool {
+            self.queries.lock().unwrap().iter().any(|query| query == name)
+        }
+    }
+
+    impl Resolver for StubResolver {
+        async fn lookup_txt(&self, query: &str) -> Option<String> {
+            self.queries.lock().unwrap().push(query.to_string());
+            self.records.get(query).cloned()
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_tree() {
+        assert!(dns_discovery_config(&["enrtree://nope".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn syncs_configured_tree() {
+        let resolver = Arc::new(StubResolver {
+            records: HashMap::from([("nodes.example.org".to_string(), ROOT.to_string())]),
+            ..Default::default()
+        });
+        let config = dns_discovery_config(&[LINK.to_string()]).unwrap();
+        let (service, _handle) = DnsDiscoveryService::new_pair(resolver.clone(), config);
+        let _task = service.spawn();
+
+        // Once the signed root is verified, the sync continues with the ENR subtree it links.
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while !resolver.queried("JWXYDBPXYWG6FX3GMDIBFA6CJ4.nodes.example.org") {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("ENR subtree was not requested");
+    }
+}
@@ -1,8 +1,23 @@
-use std::sync::Arc;
+use std::{
+    path::PathBuf,
+    sync::{Arc, OnceLock},
+};
 
-use crate::network::FraxtalNetworkBuilder;
-use fraxtal_evm::FraxtalEvmConfig;
-use reth_chainspec::{BaseFeeParams, EthereumHardforks};
+use crate::{
+    dev::{DevL1Config, DevPayloadAttributesHandle},
+    network::FraxtalNetworkBuilder,
+    peer_policy::PeerPolicyHandle,
+    pool::FraxtalPoolBuilder,
+    reputation::PeerScores,
+    tx_gossip::TxGossipPolicy,
+};
+use alloy_consensus::BlockHeader;
+use alloy_eips::Encodable2718;
+use alloy_primitives::{Address, B256, Bytes, TxKind, U256, address, keccak256};
+use fraxtal_evm::{BlockTimestamps, FraxtalEvmConfig};
+use fraxtal_op_evm::block::MigrationPlan;
+use op_alloy_consensus::TxDeposit;
+use reth_chainspec::EthereumHardforks;
 use reth_node_api::{FullNodeComponents, PayloadAttributesBuilder, PayloadTypes};
 use reth_node_builder::{
     BuilderContext, DebugNode, Node, NodeAdapter, NodeComponentsBuilder,
@@ -16,10 +31,7 @@ use reth_optimism_forks::OpHardforks;
 use reth_optimism_node::{
     OpAddOnsBuilder, OpEngineApiBuilder, OpEngineTypes, OpFullNodeTypes, OpStorage,
     args::RollupArgs,
-    node::{
-        OpAddOns, OpConsensusBuilder, OpEngineValidatorBuilder, OpNodeTypes, OpPayloadBuilder,
-        OpPoolBuilder,
-    },
+    node::{OpAddOns, OpConsensusBuilder, OpEngineValidatorBuilder, OpNodeTypes, OpPayloadBuilder},
 };
 use reth_optimism_payload_builder::{
     OpPayloadAttrs,
@@ -27,31 +39,294 @@ use reth_optimism_payload_builder::{
 };
 use reth_optimism_primitives::OpPrimitives;
 use reth_optimism_rpc::eth::OpEthApiBuilder;
-use reth_provider::providers::ProviderFactoryBuilder;
+use reth_primitives_traits::SealedHeader;
+use reth_provider::{HeaderProvider, providers::ProviderFactoryBuilder};
 use reth_rpc_api::eth::RpcTypes;
+use tracing::info;
+
+/// Depositor account of the L1 attributes deposit.
+const L1_INFO_DEPOSITOR: Address = address!("0xdeaddeaddeaddeaddeaddeaddeaddeaddead0001");
+
+/// Predeploy storing the L1 attributes.
+const L1_BLOCK_PREDEPLOY: Address = address!("0x4200000000000000000000000000000000000015");
+
+/// Gas limit of the L1 attributes deposit.
+const L1_INFO_DEPOSIT_GAS: u64 = 1_000_000;
+
+/// Encoding of the L1 attributes, depending on the active fork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum L1InfoFormat {
+    /// `setL1BlockValuesEcotone`.
+    Ecotone,
+    /// `setL1BlockValuesIsthmus`, adding the operator fee parameters.
+    Isthmus,
+    /// `setL1BlockValuesJovian`, adding the DA footprint gas scalar.
+    Jovian,
+}
+
+impl L1InfoFormat {
+    /// Returns the format of the L1 attributes of a block whose parent has the given
+    /// `parent_timestamp`.
+    ///
+    /// The activation block of a fork still uses the format of the previous fork, since the
+    /// `L1Block` predeploy is only upgraded by that block.
+    fn at<C: OpHardforks>(chain_spec: &C, parent_timestamp: u64) -> Self {
+        if chain_spec.is_jovian_active_at_timestamp(parent_timestamp) {
+            Self::Jovian
+        } else if chain_spec.is_isthmus_active_at_timestamp(parent_timestamp) {
+            Self::Isthmus
+        } else {
+            Self::Ecotone
+        }
+    }
+
+    /// Returns the selector of the `L1Block` function setting the L1 attributes.
+    const fn selector(self) -> [u8; 4] {
+        match self {
+            Self::Ecotone => [0x44, 0x0a, 0x5e, 0x20],
+            Self::Isthmus => [0x09, 0x89, 0x99, 0xbe],
+            Self::Jovian => [0x3d, 0xb6, 0xbe, 0x2b],
+        }
+    }
+}
+
+/// Block of the simulated L1 chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct L1Origin {
+    number: u64,
+    timestamp: u64,
+    hash: B256,
+    base_fee: u64,
+    blob_base_fee: u64,
+}
+
+impl L1Origin {
+    /// Returns the simulated L1 block `number`, produced at `timestamp`.
+    ///
+    /// Its hash and fees only depend on the number, so they are the same whenever it's derived.
+    fn new(config: &DevL1Config, number: u64, timestamp: u64) -> Self {
+        let hash = keccak256(number.to_be_bytes());
+        let deviate = |fee: u64, seed: [u8; 2]| {
+            let bps = i128::from(config.fee_change_bps);
+            let change = i128::from(u16::from_be_bytes(seed)) % (2 * bps + 1) - bps;
+            let fee = i128::from(fee) * (10_000 + change) / 10_000;
+            fee.clamp(1, i128::from(u64::MAX)) as u64
+        };
+        Self {
+            number,
+            timestamp,
+            hash,
+            base_fee: deviate(config.base_fee, [hash[0], hash[1]]),
+            blob_base_fee: deviate(config.blob_base_fee, [hash[2], hash[3]]),
+        }
+    }
+
+    /// Returns the L1 origin and sequence number of the L2 block at `timestamp`, continuing
+    /// the simulated L1 chain from the ones of its `parent`.
+    ///
+    /// Without a parent origin, e.g. on top of genesis, the chain starts at the configured
+    /// start number. Skipped L1 blocks keep the L1 timestamps on the L1 block time.
+    fn next(config: &DevL1Config, parent: Option<(Self, u64)>, timestamp: u64) -> (Self, u64) {
+        let Some((parent, sequence_number)) = parent else {
+            return (Self::new(config, config.start_number, timestamp), 0);
+        };
+        let blocks = timestamp.saturating_sub(parent.timestamp) / config.block_time;
+        if blocks == 0 {
+            (Self::new(config, parent.number, parent.timestamp), sequence_number + 1)
+        } else {
+            let number = parent.number + blocks;
+            (Self::new(config, number, parent.timestamp + blocks * config.block_time), 0)
+        }
+    }
+
+    /// Decodes the L1 origin and sequence number from the calldata of an L1 attributes deposit
+    /// of any format.
+    fn decode(calldata: &[u8]) -> Option<(Self, u64)> {
+        let formats = [L1InfoFormat::Ecotone, L1InfoFormat::Isthmus, L1InfoFormat::Jovian];
+        if calldata.len() < 164 || !formats.iter().any(|format| calldata[..4] == format.selector())
+        {
+            return None;
+        }
+        let word = |offset: usize| U256::from_be_slice(&calldata[offset..offset + 32]);
+        let u64_at = |offset: usize| {
+            u64::from_be_bytes(calldata[offset..offset + 8].try_into().expect("8 bytes"))
+        };
+        let origin = Self {
+            number: u64_at(28),
+            timestamp: u64_at(20),
+            hash: B256::from_slice(&calldata[100..132]),
+            base_fee: word(36).saturating_to(),
+            blob_base_fee: word(68).saturating_to(),
+        };
+        Some((origin, u64_at(12)))
+    }
+
+    /// Returns the calldata of the L1 attributes deposit of the L2 block at `sequence_number`
+    /// in this epoch.
+    fn calldata(&self, config: &DevL1Config, format: L1InfoFormat, sequence_number: u64) -> Bytes {
+        let mut data = Vec::with_capacity(178);
+        data.extend_from_slice(&format.selector());
+        data.extend_from_slice(&config.base_fee_scalar.to_be_bytes());
+        data.extend_from_slice(&config.blob_base_fee_scalar.to_be_bytes());
+        data.extend_from_slice(&sequence_number.to_be_bytes());
+        data.extend_from_slice(&self.timestamp.to_be_bytes());
+        data.extend_from_slice(&self.number.to_be_bytes());
+        data.extend_from_slice(&U256::from(self.base_fee).to_be_bytes::<32>());
+        data.extend_from_slice(&U256::from(self.blob_base_fee).to_be_bytes::<32>());
+        data.extend_from_slice(self.hash.as_slice());
+        data.extend_from_slice(config.batcher.into_word().as_slice());
+        if matches!(format, L1InfoFormat::Isthmus | L1InfoFormat::Jovian) {
+            data.extend_from_slice(&config.operator_fee_scalar.to_be_bytes());
+            data.extend_from_slice(&config.operator_fee_constant.to_be_bytes());
+        }
+        if format == L1InfoFormat::Jovian {
+            data.extend_from_slice(&config.da_footprint_gas_scalar.to_be_bytes());
+        }
+        data.into()
+    }
+
+    /// Returns the EIP-2718 encoded L1 attributes deposit of the L2 block at `sequence_number`
+    /// in this epoch.
+    fn deposit(&self, config: &DevL1Config, format: L1InfoFormat, sequence_number: u64) -> Bytes {
+        // Source hash of the L1 info deposited domain.
+        let deposit_id =
+            keccak256([self.hash.0, U256::from(sequence_number).to_be_bytes::<32>()].concat());
+        let source_hash = keccak256([U256::from(1).to_be_bytes::<32>(), deposit_id.0].concat());
+        TxDeposit {
+            source_hash,
+            from: L1_INFO_DEPOSITOR,
+            to: TxKind::Call(L1_BLOCK_PREDEPLOY),
+            mint: 0,
+            value: U256::ZERO,
+            gas_limit: L1_INFO_DEPOSIT_GAS,
+            is_system_transaction: false,
+            input: self.calldata(config, format, sequence_number),
+        }
+        .encoded_2718()
+        .into()
+    }
+}
+
+/// Reads the calldata of the L1 attributes deposit of a block, its first transaction, by block
+/// hash.
+///
+/// The reader is set once the provider of the node is available. Until then, and for blocks
+/// without the deposit, the simulated L1 chain starts over.
+#[derive(Clone, Default)]
+pub struct L1InfoReader(Arc<OnceLock<L1InfoReadFn>>);
+
+type L1InfoReadFn = Box<dyn Fn(B256) -> Option<Bytes> + Send + Sync>;
+
+impl core::fmt::Debug for L1InfoReader {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("L1InfoReader").field("set", &self.0.get().is_some()).finish()
+    }
+}
+
+impl L1InfoReader {
+    /// Sets how the calldata is read, unless already set.
+    pub fn set(&self, read: impl Fn(B256) -> Option<Bytes> + Send + Sync + 'static) {
+        let _ = self.0.set(Box::new(read));
+    }
+
+    /// Returns the calldata of the L1 attributes deposit of the block `hash`.
+    fn read(&self, hash: B256) -> Option<Bytes> {
+        self.0.get().and_then(|read| read(hash))
+    }
+}
+
+/// Source of the random values of a dev block: derived from the dev seed if set, random
+/// otherwise.
+#[derive(Debug, Clone, Copy)]
+struct DevRandomness {
+    seed: Option<u64>,
+    number: u64,
+}
+
+impl DevRandomness {
+    /// Returns the randomness of the dev block `number`.
+    const fn new(seed: Option<u64>, number: u64) -> Self {
+        Self { seed, number }
+    }
+
+    /// Returns a value derived from the seed and `domain`, for the block if `per_block`.
+    fn derive(&self, domain: &[u8], per_block: bool) -> B256 {
+        let Some(seed) = self.seed else { return B256::random() };
+        let number = if per_block { self.number } else { 0 };
+        keccak256([&seed.to_be_bytes()[..], domain, &number.to_be_bytes()].concat())
+    }
+
+    fn prev_randao(&self) -> B256 {
+        self.derive(b"prevRandao", true)
+    }
+
+    fn parent_beacon_block_root(&self) -> B256 {
+        self.derive(b"parentBeaconBlockRoot", true)
+    }
+
+    /// Returns the fee recipient, the same for all the blocks of a seed.
+    fn fee_recipient(&self) -> Address {
+        Address::from_word(self.derive(b"feeRecipient", false))
+    }
+}
 
 /// Builds [`OpPayloadAttrs`] for local/dev-mode payload generation.
-struct OpLocalPayloadAttributesBuilder {
+#[derive(Debug)]
+pub struct OpLocalPayloadAttributesBuilder {
     chain_spec: Arc<OpChainSpec>,
+    attributes: DevPayloadAttributesHandle,
+    l1_info: L1InfoReader,
 }
 
-impl PayloadAttributesBuilder<OpPayloadAttrs> for OpLocalPayloadAttributesBuilder {
-    fn build(
+impl OpLocalPayloadAttributesBuilder {
+    /// Creates a builder using the current dev payload `attributes` for every block, continuing
+    /// the simulated L1 chain from the deposits read by `l1_info`.
+    pub fn new(
+        chain_spec: Arc<OpChainSpec>,
+        attributes: DevPayloadAttributesHandle,
+        l1_info: L1InfoReader,
+    ) -> Self {
+        Self { chain_spec, attributes, l1_info }
+    }
+
+    /// Returns the L1 attributes deposit of the dev block at `timestamp` on top of `parent`.
+    fn l1_info_deposit(
         &self,
-        parent: &reth_primitives_traits::SealedHeader<alloy_consensus::Header>,
-    ) -> OpPayloadAttrs {
-        use alloy_consensus::BlockHeader;
-        use alloy_primitives::{Address, B64};
-
-        let timestamp = std::cmp::max(
-            parent.timestamp().saturating_add(1),
-            std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs(),
-        );
+        config: DevL1Config,
+        parent: &SealedHeader<alloy_consensus::Header>,
+        timestamp: u64,
+    ) -> Bytes {
+        let parent_origin =
+            self.l1_info.read(parent.hash()).and_then(|calldata| L1Origin::decode(&calldata));
+        let (origin, sequence_number) = L1Origin::next(&config, parent_origin, timestamp);
+        let format = L1InfoFormat::at(&*self.chain_spec, parent.timestamp());
+        origin.deposit(&config, format, sequence_number)
+    }
+}
+
+impl PayloadAttributesBuilder<OpPayloadAttrs> for OpLocalPayloadAttributesBuilder {
+    fn build(&self, parent: &SealedHeader<alloy_consensus::Header>) -> OpPayloadAttrs {
+        let attributes = self.attributes.current();
+        let randomness = DevRandomness::new(attributes.seed, parent.number() + 1);
+        let logical_timestamp = parent.timestamp().saturating_add(attributes.block_time);
+        let timestamp = if attributes.seed.is_some() {
+            logical_timestamp
+        } else {
+            std::cmp::max(
+                logical_timestamp,
+                std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap()
+                    .as_secs(),
+            )
+        };
 
         let eth_attrs = alloy_rpc_types_engine::PayloadAttributes {
             timestamp,
-            prev_randao: alloy_primitives::B256::random(),
-            suggested_fee_recipient: Address::random(),
+            prev_randao: randomness.prev_randao(),
+            suggested_fee_recipient: attributes
+                .fee_recipient
+                .unwrap_or_else(|| randomness.fee_recipient()),
             withdrawals: self
                 .chain_spec
                 .is_shanghai_active_at_timestamp(timestamp)
@@ -59,37 +334,18 @@ impl PayloadAttributesBuilder<OpPayloadAttrs> for OpLocalPayloadAttributesBuilde
             parent_beacon_block_root: self
                 .chain_spec
                 .is_cancun_active_at_timestamp(timestamp)
-                .then(alloy_primitives::B256::random),
+                .then(|| randomness.parent_beacon_block_root()),
         };
 
-        /// Dummy system transaction for dev mode.
-        /// OP Mainnet transaction at index 0 in block 124665056.
-        const TX_SET_L1_BLOCK: [u8; 251] = alloy_primitives::hex!(
-            "7ef8f8a0683079df94aa5b9cf86687d739a60a9b4f0835e520ec4d664e2e415dca17a6df94deaddeaddeaddeaddeaddeaddeaddeaddead00019442000000000000000000000000000000000000158080830f424080b8a4440a5e200000146b000f79c500000000000000040000000066d052e700000000013ad8a3000000000000000000000000000000000000000000000000000000003ef1278700000000000000000000000000000000000000000000000000000000000000012fdf87b89884a61e74b322bbcf60386f543bfae7827725efaaf0ab1de2294a590000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f32985"
-        );
-
-        let default_params = BaseFeeParams::optimism();
-        let denominator = std::env::var("OP_DEV_EIP1559_DENOMINATOR")
-            .ok()
-            .and_then(|v| v.parse::<u32>().ok())
-            .unwrap_or(default_params.max_change_denominator as u32);
-        let elasticity = std::env::var("OP_DEV_EIP1559_ELASTICITY")
-            .ok()
-            .and_then(|v| v.parse::<u32>().ok())
-            .unwrap_or(default_params.elasticity_multiplier as u32);
-        let gas_limit = std::env::var("OP_DEV_GAS_LIMIT").ok().and_then(|v| v.parse::<u64>().ok());
-
-        let mut eip1559_bytes = [0u8; 8];
-        eip1559_bytes[0..4].copy_from_slice(&denominator.to_be_bytes());
-        eip1559_bytes[4..8].copy_from_slice(&elasticity.to_be_bytes());
+        let l1_info = self.l1_info_deposit(attributes.l1, parent, timestamp);
 
         OpPayloadAttrs(op_alloy_rpc_types_engine::OpPayloadAttributes {
             payload_attributes: eth_attrs,
-            transactions: Some(vec![TX_SET_L1_BLOCK.into()]),
+            transactions: Some(vec![l1_info]),
             no_tx_pool: None,
-            gas_limit,
-            eip_1559_params: Some(B64::from(eip1559_bytes)),
-            min_base_fee: Some(0),
+            gas_limit: attributes.gas_limit,
+            eip_1559_params: Some(attributes.eip1559_params()),
+            min_base_fee: Some(attributes.min_base_fee),
         })
     }
 }
@@ -111,12 +367,22 @@ pub struct FraxtalNode {
     /// Used to control the gas limit of the blocks produced by the OP builder.(configured by the
     /// batcher via the `miner_` api)
     pub gas_limit_config: OpGasLimitConfig,
+    /// Fork migrations overriding the built-in plan of the chain.
+    pub migrations: Option<Arc<MigrationPlan>>,
+    /// Peers file watched for peers to dial and disconnect at runtime.
+    pub peers_file: Option<PathBuf>,
+    /// Allow/deny policy applied to inbound and outbound peers.
+    pub peer_policy: PeerPolicyHandle,
+    /// Reputation configuration and scores of the peers.
+    pub peer_scores: PeerScores,
+    /// Policy for gossiping pooled transactions.
+    pub tx_gossip: TxGossipPolicy,
 }
 
 /// A [`ComponentsBuilder`] with its generic arguments set to a stack of Optimism specific builders.
 pub type FraxtalNodeComponentBuilder<Node, Payload = OpPayloadBuilder> = ComponentsBuilder<
     Node,
-    OpPoolBuilder,
+    FraxtalPoolBuilder,
     BasicPayloadServiceBuilder<Payload>,
     FraxtalNetworkBuilder,
     FraxtalExecutorBuilder,
@@ -126,10 +392,16 @@ pub type FraxtalNodeComponentBuilder<Node, Payload = OpPayloadBuilder> = Compone
 impl FraxtalNode {
     /// Creates a new instance of the Optimism node type.
     pub fn new(args: RollupArgs) -> Self {
+        let tx_gossip = TxGossipPolicy::from_disable_txpool_gossip(args.disable_txpool_gossip);
         Self {
             args,
             da_config: OpDAConfig::default(),
             gas_limit_config: OpGasLimitConfig::default(),
+            migrations: None,
+            peers_file: None,
+            peer_policy: PeerPolicyHandle::default(),
+            peer_scores: PeerScores::default(),
+            tx_gossip,
         }
     }
 
@@ -145,30 +417,66 @@ impl FraxtalNode {
         self
     }
 
+    /// Configure the fork migrations, replacing the built-in plan of the chain.
+    pub fn with_migrations(mut self, migrations: impl Into<Arc<MigrationPlan>>) -> Self {
+        self.migrations = Some(migrations.into());
+        self
+    }
+
+    /// Configure the peers file watched for peers to dial and disconnect at runtime.
+    pub fn with_peers_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.peers_file = Some(path.into());
+        self
+    }
+
+    /// Configure the allow/deny policy applied to inbound and outbound peers.
+    pub fn with_peer_policy(mut self, peer_policy: PeerPolicyHandle) -> Self {
+        self.peer_policy = peer_policy;
+        self
+    }
+
+    /// Configure the reputation configuration and scores of the peers.
+    pub fn with_peer_scores(mut self, peer_scores: PeerScores) -> Self {
+        self.peer_scores = peer_scores;
+        self
+    }
+
+    /// Configure the policy for gossiping pooled transactions, replacing the one of
+    /// `--rollup.disable-tx-pool-gossip`.
+    pub const fn with_tx_gossip(mut self, tx_gossip: TxGossipPolicy) -> Self {
+        self.tx_gossip = tx_gossip;
+        self
+    }
+
     /// Returns the components for the given [`RollupArgs`].
     pub fn components<Node>(&self) -> FraxtalNodeComponentBuilder<Node>
     where
         Node: FullNodeTypes<Types: OpNodeTypes>,
     {
-        let RollupArgs { disable_txpool_gossip, compute_pending_block, discovery_v4, .. } =
-            self.args;
+        let RollupArgs { compute_pending_block, discovery_v4, .. } = self.args;
         ComponentsBuilder::default()
             .node_types::<Node>()
-            .executor(FraxtalExecutorBuilder::default())
+            .executor(FraxtalExecutorBuilder::new(self.migrations.clone()))
             .pool(
-                OpPoolBuilder::default()
+                FraxtalPoolBuilder::default()
                     .with_enable_tx_conditional(self.args.enable_tx_conditional)
                     .with_supervisor(
                         self.args.supervisor_http.clone(),
                         self.args.supervisor_safety_level,
-                    ),
+                    )
+                    .with_gossip_types(self.tx_gossip.types),
             )
             .payload(BasicPayloadServiceBuilder::new(
                 OpPayloadBuilder::new(compute_pending_block)
                     .with_da_config(self.da_config.clone())
                     .with_gas_limit_config(self.gas_limit_config.clone()),
             ))
-            .network(FraxtalNetworkBuilder::new(disable_txpool_gossip, !discovery_v4))
+            .network(
+                FraxtalNetworkBuilder::new(self.tx_gossip, !discovery_v4)
+                    .with_peers_file(self.peers_file.clone())
+                    .with_peer_policy(self.peer_policy.clone())
+                    .with_peer_scores(self.peer_scores.clone()),
+            )
             .consensus(OpConsensusBuilder::default())
     }
 
@@ -232,7 +540,7 @@ where
 {
     type ComponentsBuilder = ComponentsBuilder<
         N,
-        OpPoolBuilder,
+        FraxtalPoolBuilder,
         BasicPayloadServiceBuilder<OpPayloadBuilder>,
         FraxtalNetworkBuilder,
         FraxtalExecutorBuilder,
@@ -269,7 +577,11 @@ where
     fn local_payload_attributes_builder(
         chain_spec: &Self::ChainSpec,
     ) -> impl PayloadAttributesBuilder<<Self::Payload as PayloadTypes>::PayloadAttributes> {
-        OpLocalPayloadAttributesBuilder { chain_spec: Arc::new(chain_spec.clone()) }
+        OpLocalPayloadAttributesBuilder::new(
+            Arc::new(chain_spec.clone()),
+            DevPayloadAttributesHandle::default(),
+            L1InfoReader::default(),
+        )
     }
 }
 
@@ -281,9 +593,19 @@ impl NodeTypes for FraxtalNode {
 }
 
 /// A regular optimism evm and executor builder.
-#[derive(Debug, Copy, Clone, Default)]
+#[derive(Debug, Clone, Default)]
 #[non_exhaustive]
-pub struct FraxtalExecutorBuilder;
+pub struct FraxtalExecutorBuilder {
+    /// Fork migrations overriding the built-in plan of the chain.
+    pub migrations: Option<Arc<MigrationPlan>>,
+}
+
+impl FraxtalExecutorBuilder {
+    /// Creates a new [`FraxtalExecutorBuilder`] with the given fork migrations override.
+    pub const fn new(migrations: Option<Arc<MigrationPlan>>) -> Self {
+        Self { migrations }
+    }
+}
 
 impl<Node> ExecutorBuilder<Node> for FraxtalExecutorBuilder
 where
@@ -295,8 +617,232 @@ where
     >;
 
     async fn build_evm(self, ctx: &BuilderContext<Node>) -> eyre::Result<Self::EVM> {
-        let evm_config = FraxtalEvmConfig::new(ctx.chain_spec(), OpRethReceiptBuilder::default());
+        let mut evm_config =
+            FraxtalEvmConfig::new(ctx.chain_spec(), OpRethReceiptBuilder::default())
+                .with_block_timestamps(Arc::new(ProviderBlockTimestamps(ctx.provider().clone())));
+        if let Some(migrations) = self.migrations {
+            info!(target: "reth::cli", "Using custom fork migration plan");
+            evm_config = evm_config.with_migrations(migrations);
+        }
 
         Ok(evm_config)
     }
 }
+
+/// Reads the timestamps of the parent blocks of executed blocks from the node's provider.
+struct ProviderBlockTimestamps<P>(P);
+
+impl<P> core::fmt::Debug for ProviderBlockTimestamps<P> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ProviderBlockTimestamps").finish_non_exhaustive()
+    }
+}
+
+impl<P> BlockTimestamps for ProviderBlockTimestamps<P>
+where
+    P: HeaderProvider + Send + Sync,
+{
+    fn block_timestamp(&self, hash: B256) -> Option<u64> {
+        self.0.header(hash).ok().flatten().map(|header| header.timestamp())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dev::DevPayloadAttributes;
+    use alloy_consensus::Transaction;
+    use alloy_eips::Decodable2718;
+    use alloy_primitives::{b256, hex};
+    use op_alloy_consensus::OpTxEnvelope;
+    use reth_optimism_chainspec::OP_DEV;
+    use std::{collections::HashMap, sync::Mutex};
+
+    /// OP Mainnet L1 attributes deposit at index 0 of block 124665056.
+    const OP_MAINNET_L1_INFO: [u8; 251] = hex!(
+        "7ef8f8a0683079df94aa5b9cf86687d739a60a9b4f0835e520ec4d664e2e415dca17a6df94deaddeaddeaddeaddeaddeaddeaddeaddead00019442000000000000000000000000000000000000158080830f424080b8a4440a5e200000146b000f79c500000000000000040000000066d052e700000000013ad8a3000000000000000000000000000000000000000000000000000000003ef1278700000000000000000000000000000000000000000000000000000000000000012fdf87b89884a61e74b322bbcf60386f543bfae7827725efaaf0ab1de2294a590000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f32985"
+    );
+
+    #[test]
+    fn encodes_l1_info_deposit() {
+        let config = DevL1Config {
+            base_fee_scalar: 5227,
+            blob_base_fee_scalar: 1_014_213,
+            batcher: address!("0x6887246668a3b87f54deb3b94ba47a6f63f32985"),
+            ..Default::default()
+        };
+        let origin = L1Origin {
+            number: 0x013ad8a3,
+            timestamp: 0x66d052e7,
+            hash: b256!("0x2fdf87b89884a61e74b322bbcf60386f543bfae7827725efaaf0ab1de2294a59"),
+            base_fee: 0x3ef12787,
+            blob_base_fee: 1,
+        };
+        assert_eq!(origin.deposit(&config, L1InfoFormat::Ecotone, 4), OP_MAINNET_L1_INFO[..]);
+
+        assert_eq!(origin.calldata(&config, L1InfoFormat::Ecotone, 4).len(), 164);
+        assert_eq!(origin.calldata(&config, L1InfoFormat::Isthmus, 4).len(), 176);
+        assert_eq!(origin.calldata(&config, L1InfoFormat::Jovian, 4).len(), 178);
+    }
+
+    #[test]
+    fn decodes_l1_info() {
+        let config = DevL1Config {
+            operator_fee_scalar: 3,
+            da_footprint_gas_scalar: 9,
+            ..Default::default()
+        };
+        let origin = L1Origin::new(&config, 42, 1_000);
+        for format in [L1InfoFormat::Ecotone, L1InfoFormat::Isthmus, L1InfoFormat::Jovian] {
+            let calldata = origin.calldata(&config, format, 3);
+            assert_eq!(L1Origin::decode(&calldata), Some((origin, 3)));
+
+            // Cross-checked with the decoder of the OP EVM.
+            let info = reth_optimism_evm::parse_l1_info(&calldata).unwrap();
+            assert_eq!(info.l1_base_fee, U256::from(origin.base_fee));
+            assert_eq!(info.l1_blob_base_fee, Some(U256::from(origin.blob_base_fee)));
+            assert_eq!(info.l1_base_fee_scalar, U256::from(config.base_fee_scalar));
+            if format != L1InfoFormat::Ecotone {
+                assert_eq!(info.operator_fee_scalar, Some(U256::from(3)));
+            }
+            if format == L1InfoFormat::Jovian {
+                assert_eq!(info.da_footprint_gas_scalar, Some(9));
+            }
+        }
+
+        let deposit = OpTxEnvelope::decode_2718(&mut &OP_MAINNET_L1_INFO[..]).unwrap();
+        let (origin, sequence_number) = L1Origin::decode(deposit.input()).unwrap();
+        assert_eq!((origin.number, origin.timestamp, sequence_number), (0x013ad8a3, 0x66d052e7, 4));
+
+        assert_eq!(L1Origin::decode(&[0; 164]), None);
+    }
+
+    #[test]
+    fn continues_l1_chain() {
+        let config = DevL1Config::default();
+
+        let first = L1Origin::next(&config, None, 1_000);
+        assert_eq!((first.0.number, first.0.timestamp, first.1), (1, 1_000, 0));
+        let next = L1Origin::next(&config, Some(first), 1_011);
+        assert_eq!((next.0, next.1), (first.0, 1));
+
+        // A new L1 block starts a new epoch, with deviated fees.
+        let (second, sequence_number) = L1Origin::next(&config, Some(next), 1_012);
+        assert_eq!((second.number, second.timestamp, sequence_number), (2, 1_012, 0));
+        let max_change = config.base_fee / 10_000 * u64::from(config.fee_change_bps);
+        assert!(second.base_fee.abs_diff(config.base_fee) <= max_change);
+
+        // Skipped L1 blocks keep the L1 timestamps on the L1 block time.
+        let (third, _) = L1Origin::next(&config, Some((second, 0)), 1_050);
+        assert_eq!((third.number, third.timestamp), (5, 1_048));
+
+        // Replaced fees apply from the next L2 block on.
+        let config = DevL1Config { base_fee: 7, fee_change_bps: 0, ..config };
+        let (origin, sequence_number) = L1Origin::next(&config, Some((third, 0)), 1_051);
+        assert_eq!((origin.number, origin.base_fee, sequence_number), (5, 7, 1));
+    }
+
+    #[test]
+    fn builds_deterministic_attributes() {
+        let attributes =
+            DevPayloadAttributes { seed: Some(7), block_time: 2, ..Default::default() };
+        let build = || {
+            let builder = OpLocalPayloadAttributesBuilder::new(
+                OP_DEV.clone(),
+                DevPayloadAttributesHandle::new(attributes).unwrap(),
+                L1InfoReader::default(),
+            );
+            let parent = SealedHeader::seal_slow(alloy_consensus::Header {
+                number: 10,
+                timestamp: 1_000,
+                ..Default::default()
+            });
+            builder.build(&parent).0
+        };
+
+        let first = build();
+        assert_eq!(first, build());
+        assert_eq!(first.payload_attributes.timestamp, 1_002);
+
+        let randomness = DevRandomness::new(Some(7), 11);
+        assert_eq!(first.payload_attributes.prev_randao, randomness.prev_randao());
+        // The fee recipient is the same for all the blocks of a seed.
+        assert_eq!(
+            first.payload_attributes.suggested_fee_recipient,
+            DevRandomness::new(Some(7), 12).fee_recipient()
+        );
+        assert_ne!(randomness.prev_randao(), DevRandomness::new(Some(7), 12).prev_randao());
+    }
+
+    #[test]
+    fn builds_reproducible_chain() {
+        let attributes = DevPayloadAttributes {
+            seed: Some(7),
+            block_time: 2,
+            l1: DevL1Config { block_time: 6, ..Default::default() },
+            ..Default::default()
+        };
+        // Builds a chain of dev blocks, reading the deposits of the parents from the built ones.
+        let build_chain = |blocks: usize| {
+            let deposits = Arc::new(Mutex::new(HashMap::<B256, Bytes>::new()));
+            let l1_info = L1InfoReader::default();
+            let read = deposits.clone();
+            l1_info.set(move |hash| read.lock().unwrap().get(&hash).cloned());
+            let builder = OpLocalPayloadAttributesBuilder::new(
+                OP_DEV.clone(),
+                DevPayloadAttributesHandle::new(attributes).unwrap(),
+                l1_info,
+            );
+
+            let mut parent = SealedHeader::seal_slow(alloy_consensus::Header {
+                number: 10,
+                timestamp: 1_000,
+                ..Default::default()
+            });
+            let mut chain = Vec::new();
+            for _ in 0..blocks {
+                let attrs = builder.build(&parent).0;
+                // Building on the same parent again gives the same attributes.
+                assert_eq!(builder.build(&parent).0, attrs);
+
+                let header = SealedHeader::seal_slow(alloy_consensus::Header {
+                    parent_hash: parent.hash(),
+                    number: parent.number + 1,
+                    timestamp: attrs.payload_attributes.timestamp,
+                    ..Default::default()
+                });
+                let deposit = &attrs.transactions.as_ref().unwrap()[0];
+                let deposit = OpTxEnvelope::decode_2718(&mut deposit.as_ref()).unwrap();
+                deposits.lock().unwrap().insert(header.hash(), deposit.input().clone());
+                chain.push(attrs);
+                parent = header;
+            }
+            chain
+        };
+
+        let chain = build_chain(6);
+        // A restarted node builds the same chain.
+        assert_eq!(chain, build_chain(6));
+
+        let origins = chain
+            .iter()
+            .map(|attrs| {
+                let deposit = &attrs.transactions.as_ref().unwrap()[0];
+                let deposit = OpTxEnvelope::decode_2718(&mut deposit.as_ref()).unwrap();
+                let (origin, sequence_number) = L1Origin::decode(deposit.input()).unwrap();
+                (origin.number, origin.timestamp, sequence_number)
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            origins,
+            [
+                (1, 1_002, 0),
+                (1, 1_002, 1),
+                (1, 1_002, 2),
+                (2, 1_008, 0),
+                (2, 1_008, 1),
+                (2, 1_008, 2)
+            ]
+        );
+    }
+}
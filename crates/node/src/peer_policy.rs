@@ -0,0 +1,494 @@
+//! Allow/deny policy for the peers of the node.
+//!
+//! The policy restricts the peers by node id, IP range or client version:
+//!
+//! ```toml
+//! [allow]
+//! node_ids = ["0x6f8a…"]
+//! cidrs = ["10.0.0.0/8"]
+//!
+//! [deny]
+//! client_versions = ["^Geth/"]
+//! ```
+//!
+//! Without an allow list every peer that is not denied is accepted; with one, a peer has to match
+//! at least one of its rules. The deny list takes precedence.
+//!
+//! The policy the node starts with is enforced by the peer set of the network, before dialing and
+//! before accepting inbound connections, see [`PeerPolicy::restrict_peers_config`]: denied node
+//! ids and addresses are banned, and an allow list of IP ranges only becomes the IP filter. The
+//! other rules, and the rules of a policy replaced at runtime, are checked as peers are
+//! discovered and when a session is established, inbound or outbound. Peers violating them are
+//! banned, so they are disconnected and not dialed again.
+//!
+//! The policy can be viewed and replaced at runtime through `admin_peerPolicy` and
+//! `admin_setPeerPolicy`, which re-checks the connected peers.
+
+use std::{collections::HashSet, fmt, net::IpAddr, path::Path, str::FromStr, sync::Arc};
+
+use eyre::{WrapErr, bail, ensure};
+use futures_util::StreamExt;
+use regex::Regex;
+use reth_net_banlist::IpFilter;
+use reth_network::{NetworkHandle, NetworkPrimitives};
+use reth_network_api::{
+    DiscoveredEvent, DiscoveryEvent, NetworkEvent, Peers, ReputationChangeKind,
+};
+use reth_network_peers::{NodeRecord, PeerId};
+use reth_network_types::PeersConfig;
+use reth_tasks::TaskExecutor;
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use tracing::{debug, info, warn};
+
+/// Reputation change banning a peer, whatever its reputation.
+const POLICY_BAN: i32 = i32::MIN;
+
+/// Peer rules as read from the policy file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerRulesConfig {
+    /// Node ids.
+    #[serde(default)]
+    pub node_ids: Vec<PeerId>,
+    /// IP ranges in CIDR notation, or single IP addresses.
+    #[serde(default)]
+    pub cidrs: Vec<String>,
+    /// Regular expressions matched against the client version of the RLPx hello message.
+    #[serde(default)]
+    pub client_versions: Vec<String>,
+}
+
+/// Peer policy as read from the policy file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PeerPolicyConfig {
+    /// Peers to accept. Without an allow list every peer that is not denied is accepted.
+    pub allow: Option<PeerRulesConfig>,
+    /// Peers to reject.
+    #[serde(default)]
+    pub deny: PeerRulesConfig,
+}
+
+/// An IP range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Returns `true` if the range is a single address.
+    const fn is_address(&self) -> bool {
+        self.prefix == if self.network.is_ipv4() { 32 } else { 128 }
+    }
+
+    /// Returns `true` if `ip` is in the range.
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - u32::from(self.prefix)).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - u32::from(self.prefix)).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+impl FromStr for Cidr {
+    type Err = eyre::Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (network, prefix) = s.split_once('/').map_or((s, None), |(ip, len)| (ip, Some(len)));
+        let network: IpAddr = network.parse().wrap_err_with(|| format!("invalid CIDR {s}"))?;
+        let max = if network.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix.parse().wrap_err_with(|| format!("invalid CIDR {s}"))?,
+            None => max,
+        };
+        ensure!(prefix <= max, "invalid CIDR {s}, prefix longer than {max} bits");
+        Ok(Self { network: network.to_canonical(), prefix })
+    }
+}
+
+/// Compiled [`PeerRulesConfig`].
+#[derive(Debug, Clone, Default)]
+struct PeerRules {
+    node_ids: HashSet<PeerId>,
+    cidrs: Vec<Cidr>,
+    client_versions: Vec<Regex>,
+}
+
+impl PeerRules {
+    fn new(config: &PeerRulesConfig) -> eyre::Result<Self> {
+        Ok(Self {
+            node_ids: config.node_ids.iter().copied().collect(),
+            cidrs: config.cidrs.iter().map(|cidr| cidr.parse()).collect::<eyre::Result<_>>()?,
+            client_versions: config
+                .client_versions
+                .iter()
+                .map(|pattern| {
+                    Regex::new(pattern)
+                        .wrap_err_with(|| format!("invalid client version pattern {pattern}"))
+                })
+                .collect::<eyre::Result<_>>()?,
+        })
+    }
+
+    fn matches_node(&self, id: PeerId, ip: IpAddr) -> bool {
+        self.node_ids.contains(&id) || self.cidrs.iter().any(|cidr| cidr.contains(ip))
+    }
+
+    fn matches_client(&self, client_version: &str) -> bool {
+        self.client_versions.iter().any(|pattern| pattern.is_match(client_version))
+    }
+}
+
+/// Why a peer violates the policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolicyViolation {
+    /// The node id or IP address of the peer is denied.
+    DeniedNode,
+    /// The client version of the peer is denied.
+    DeniedClient,
+    /// The peer matches no rule of the allow list.
+    NotAllowed,
+}
+
+impl fmt::Display for PolicyViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::DeniedNode => "node is denied",
+            Self::DeniedClient => "client version is denied",
+            Self::NotAllowed => "peer is not allowed",
+        })
+    }
+}
+
+/// Compiled peer policy.
+#[derive(Debug, Clone, Default)]
+pub struct PeerPolicy {
+    config: PeerPolicyConfig,
+    allow: Option<PeerRules>,
+    deny: PeerRules,
+}
+
+impl PeerPolicy {
+    /// Compiles the policy, failing on an invalid CIDR or client version pattern, or an empty
+    /// allow list that would reject every peer.
+    pub fn new(config: PeerPolicyConfig) -> eyre::Result<Self> {
+        if config.allow.as_ref().is_some_and(|allow| allow == &PeerRulesConfig::default()) {
+            bail!("the allow list is empty and would reject every peer");
+        }
+        let allow = config.allow.as_ref().map(PeerRules::new).transpose()?;
+        let deny = PeerRules::new(&config.deny)?;
+        Ok(Self { config, allow, deny })
+    }
+
+    /// Reads the policy from a TOML file.
+    pub fn load(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("failed to read peer policy {}", path.display()))?;
+        let config = toml::from_str(&contents)
+            .wrap_err_with(|| format!("invalid peer policy {}", path.display()))?;
+        Self::new(config).wrap_err_with(|| format!("invalid peer policy {}", path.display()))
+    }
+
+    /// Returns the configuration the policy was compiled from.
+    pub const fn config(&self) -> &PeerPolicyConfig {
+        &self.config
+    }
+
+    /// Checks a peer against the policy.
+    ///
+    /// Before the RLPx handshake the client version is unknown. Client version rules are then
+    /// skipped, and an allow list with client version rules doesn't reject the peer yet.
+    pub fn check(
+        &self,
+        id: PeerId,
+        ip: IpAddr,
+        client_version: Option<&str>,
+    ) -> Result<(), PolicyViolation> {
+        if self.deny.matches_node(id, ip) {
+            return Err(PolicyViolation::DeniedNode);
+        }
+        if client_version.is_some_and(|client| self.deny.matches_client(client)) {
+            return Err(PolicyViolation::DeniedClient);
+        }
+        let Some(allow) = &self.allow else { return Ok(()) };
+        let allowed = allow.matches_node(id, ip)
+            || match client_version {
+                Some(client) => allow.matches_client(client),
+                None => !allow.client_versions.is_empty(),
+            };
+        if allowed { Ok(()) } else { Err(PolicyViolation::NotAllowed) }
+    }
+
+    /// Returns the bootnodes the policy doesn't reject.
+    pub fn filter_nodes(&self, nodes: Vec<NodeRecord>) -> Vec<NodeRecord> {
+        nodes.into_iter().filter(|node| self.check(node.id, node.address, None).is_ok()).collect()
+    }
+
+    /// Returns the IP filter equivalent to the allow list, if it only has IP ranges.
+    fn ip_filter(&self) -> Option<IpFilter> {
+        let allow = self.allow.as_ref()?;
+        if !allow.node_ids.is_empty() || !allow.client_versions.is_empty() {
+            return None;
+        }
+        let cidrs = allow.cidrs.iter().map(Cidr::to_string).collect::<Vec<_>>().join(",");
+        IpFilter::from_cidr_string(&cidrs).ok()
+    }
+
+    /// Enforces the policy in the peer set of the network, before dialing and accepting peers.
+    ///
+    /// Denied node ids and single addresses are added to the ban list. An allow list of IP ranges
+    /// only becomes the IP filter, unless one is already set by `--netrestrict`. Rules that can't
+    /// be expressed this way are checked once peers are discovered or connected, see
+    /// [`spawn_peer_policy`].
+    pub fn restrict_peers_config(&self, config: &mut PeersConfig) {
+        for id in &self.deny.node_ids {
+            config.ban_list.ban_peer(*id);
+        }
+        for cidr in self.deny.cidrs.iter().filter(|cidr| cidr.is_address()) {
+            config.ban_list.ban_ip(cidr.network);
+        }
+        if let Some(filter) = self.ip_filter() {
+            if config.ip_filter.has_restrictions() {
+                warn!(
+                    target: "fraxtal::network",
+                    "Not applying the IP ranges of the peer policy, --netrestrict is set",
+                );
+            } else {
+                config.ip_filter = filter;
+            }
+        }
+    }
+}
+
+/// Shared handle to the peer policy, updated at runtime.
+#[derive(Debug, Clone)]
+pub struct PeerPolicyHandle {
+    policy: Arc<watch::Sender<Arc<PeerPolicy>>>,
+}
+
+impl Default for PeerPolicyHandle {
+    fn default() -> Self {
+        Self::new(PeerPolicy::default())
+    }
+}
+
+impl PeerPolicyHandle {
+    /// Creates a handle to `policy`.
+    pub fn new(policy: PeerPolicy) -> Self {
+        Self { policy: Arc::new(watch::Sender::new(Arc::new(policy))) }
+    }
+
+    /// Returns the current policy.
+    pub fn current(&self) -> Arc<PeerPolicy> {
+        self.policy.borrow().clone()
+    }
+
+    /// Replaces the policy.
+    pub fn set(&self, policy: PeerPolicy) {
+        self.policy.send_replace(Arc::new(policy));
+    }
+
+    /// Returns a receiver notified of every policy change.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<PeerPolicy>> {
+        self.policy.subscribe()
+    }
+}
+
+/// Bans a peer violating the policy.
+///
+/// Unlike removing the peer, the ban keeps it in the peer set, so discovery doesn't add it back
+/// and it isn't dialed again.
+fn ban<N: Peers>(network: &N, peer_id: PeerId) {
+    network.reputation_change(peer_id, ReputationChangeKind::Other(POLICY_BAN));
+    network.disconnect_peer(peer_id);
+}
+
+/// Spawns the tasks enforcing the policy: discovered peers are checked as they are queued for
+/// dialing, new sessions as they are established, and all connected peers are re-checked when
+/// the policy changes.
+pub fn spawn_peer_policy<N: NetworkPrimitives>(
+    executor: &TaskExecutor,
+    network: NetworkHandle<N>,
+    policy: PeerPolicyHandle,
+) {
+    let mut discovered = network.discovery_listener();
+    let discovery = network.clone();
+    let discovery_policy = policy.clone();
+    executor.spawn_critical_task("fraxtal-peer-policy-discovery", async move {
+        while let Some(event) = discovered.next().await {
+            let DiscoveryEvent::NewNode(DiscoveredEvent::EventQueued { peer_id, addr, .. }) = event
+            else {
+                continue;
+            };
+            if let Err(violation) = discovery_policy.current().check(peer_id, addr.tcp().ip(), None)
+            {
+                debug!(
+                    target: "fraxtal::network",
+                    %peer_id,
+                    %violation,
+                    "Banning discovered peer violating the peer policy",
+                );
+                ban(&discovery, peer_id);
+            }
+        }
+    });
+
+    let mut events = network.event_listener();
+    let sessions = network.clone();
+    let sessions_policy = policy.clone();
+    executor.spawn_critical_task("fraxtal-peer-policy-sessions", async move {
+        while let Some(event) = events.next().await {
+            let NetworkEvent::ActivePeerSession { info, .. } = event else { continue };
+            let check = sessions_policy.current().check(
+                info.peer_id,
+                info.remote_addr.ip(),
+                Some(&*info.client_version),
+            );
+            if let Err(violation) = check {
+                debug!(
+                    target: "fraxtal::network",
+                    peer_id = %info.peer_id,
+                    client_version = %info.client_version,
+                    %violation,
+                    "Banning peer violating the peer policy",
+                );
+                ban(&sessions, info.peer_id);
+            }
+        }
+    });
+
+    let mut updates = policy.subscribe();
+    executor.spawn_critical_task("fraxtal-peer-policy-updates", async move {
+        while updates.changed().await.is_ok() {
+            let policy = updates.borrow_and_update().clone();
+            let peers = match network.get_all_peers().await {
+                Ok(peers) => peers,
+                Err(err) => {
+                    warn!(target: "fraxtal::network", %err, "Failed to list peers for the peer policy");
+                    continue
+                }
+            };
+            let mut banned = 0;
+            for peer in peers {
+                let check = policy.check(
+                    peer.remote_id,
+                    peer.remote_addr.ip(),
+                    Some(&*peer.client_version),
+                );
+                if check.is_err() {
+                    ban(&network, peer.remote_id);
+                    banned += 1;
+                }
+            }
+            info!(target: "fraxtal::network", banned, "Applied updated peer policy");
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::Ipv4Addr;
+
+    fn policy(toml: &str) -> PeerPolicy {
+        PeerPolicy::new(toml::from_str(toml).unwrap()).unwrap()
+    }
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    #[test]
+    fn matches_cidrs() {
+        let cidr: Cidr = "10.1.0.0/16".parse().unwrap();
+        assert!(cidr.contains(ip("10.1.2.3")));
+        assert!(cidr.contains(ip("::ffff:10.1.2.3")));
+        assert!(!cidr.contains(ip("10.2.0.1")));
+        assert!("0.0.0.0/0".parse::<Cidr>().unwrap().contains(ip("1.2.3.4")));
+        assert!("fd00::/8".parse::<Cidr>().unwrap().contains(ip("fd12::1")));
+        assert!("10.0.0.1".parse::<Cidr>().unwrap().contains(ip("10.0.0.1")));
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("nope/8".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn deny_takes_precedence() {
+        let id = PeerId::repeat_byte(1);
+        let policy = policy(&format!(
+            "[allow]\ncidrs = [\"10.0.0.0/8\"]\n\n[deny]\nnode_ids = [\"{id}\"]\nclient_versions = [\"^Geth/\"]\n"
+        ));
+        let other = PeerId::repeat_byte(2);
+        assert_eq!(policy.check(other, ip("10.0.0.1"), Some("reth/v2.0.0")), Ok(()));
+        assert_eq!(policy.check(id, ip("10.0.0.1"), None), Err(PolicyViolation::DeniedNode));
+        assert_eq!(
+            policy.check(other, ip("10.0.0.1"), Some("Geth/v1.14")),
+            Err(PolicyViolation::DeniedClient)
+        );
+        assert_eq!(policy.check(other, ip("192.168.0.1"), None), Err(PolicyViolation::NotAllowed));
+    }
+
+    #[test]
+    fn defers_client_allow_list_until_handshake() {
+        let policy = policy("[allow]\nclient_versions = [\"^reth/\"]\n");
+        let id = PeerId::repeat_byte(1);
+        let addr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+        assert_eq!(policy.check(id, addr, None), Ok(()));
+        assert_eq!(policy.check(id, addr, Some("reth/v2.0.0")), Ok(()));
+        assert_eq!(policy.check(id, addr, Some("Geth/v1.14")), Err(PolicyViolation::NotAllowed));
+    }
+
+    #[test]
+    fn permits_everything_by_default() {
+        let policy = PeerPolicy::default();
+        assert_eq!(policy.check(PeerId::ZERO, ip("1.2.3.4"), Some("anything")), Ok(()));
+    }
+
+    #[test]
+    fn restricts_peers_config() {
+        let id = PeerId::repeat_byte(1);
+        let policy = policy(&format!(
+            "[allow]\ncidrs = [\"10.0.0.0/8\"]\n\n[deny]\nnode_ids = [\"{id}\"]\ncidrs = [\"10.0.0.1\", \"10.1.0.0/16\"]\n"
+        ));
+        let mut config = PeersConfig::default();
+        policy.restrict_peers_config(&mut config);
+        assert!(config.ban_list.is_banned_peer(&id));
+        assert!(config.ban_list.is_banned_ip(&ip("10.0.0.1")));
+        // Only single addresses can be banned, ranges are checked later.
+        assert!(!config.ban_list.is_banned_ip(&ip("10.1.0.1")));
+        assert!(config.ip_filter.is_allowed(&ip("10.2.0.1")));
+        assert!(!config.ip_filter.is_allowed(&ip("192.168.0.1")));
+    }
+
+    #[test]
+    fn keeps_ip_filter_for_mixed_allow_list() {
+        let id = PeerId::repeat_byte(1);
+        let policy = policy(&format!("[allow]\nnode_ids = [\"{id}\"]\ncidrs = [\"10.0.0.0/8\"]\n"));
+        let mut config = PeersConfig::default();
+        policy.restrict_peers_config(&mut config);
+        // The node may connect from outside the allowed ranges.
+        assert!(config.ip_filter.is_allowed(&ip("192.168.0.1")));
+    }
+
+    #[test]
+    fn rejects_invalid_policy() {
+        let config: PeerPolicyConfig =
+            toml::from_str("[deny]\nclient_versions = [\"(\"]\n").unwrap();
+        assert!(PeerPolicy::new(config).is_err());
+        assert!(PeerPolicy::new(toml::from_str("[allow]\n").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,469 @@
+//! Peers file watched at runtime.
+//!
+//! The peers file lists the enodes the node should stay connected to, optionally marked as
+//! trusted or static:
+//!
+//! ```toml
+//! [[peers]]
+//! enode = "enode://…@10.0.0.1:30303"
+//! trusted = true
+//!
+//! [[peers]]
+//! enode = "enode://…@10.0.0.2:30303"
+//! static = true
+//! ```
+//!
+//! The file is polled while the node runs: added peers are dialed and removed peers are
+//! disconnected through the [`NetworkHandle`](reth_network::NetworkHandle). Peers rejected by the
+//! peer policy, see [`crate::peer_policy`], are not dialed. While the file is
+//! absent, the bootnodes of the node are used instead: the ones of `--bootnodes` if set, the
+//! built-in bootnodes of the chain otherwise.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::peer_policy::{PeerPolicy, PeerPolicyHandle};
+use eyre::{WrapErr, ensure};
+use reth_network_api::{PeerKind, Peers};
+use reth_network_peers::{NodeRecord, PeerId};
+use reth_tasks::TaskExecutor;
+use serde::Deserialize;
+use tracing::{info, warn};
+
+/// How often the peers file is checked for changes.
+pub const PEERS_FILE_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Contents of a peers file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PeersFile {
+    #[serde(default)]
+    peers: Vec<PeerEntry>,
+}
+
+/// A peer of the peers file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PeerEntry {
+    enode: String,
+    #[serde(default)]
+    trusted: bool,
+    #[serde(default, rename = "static")]
+    is_static: bool,
+}
+
+/// A peer the node keeps connected to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConfiguredPeer {
+    /// The node record of the peer.
+    pub record: NodeRecord,
+    /// The kind the peer is added as.
+    pub kind: PeerKind,
+}
+
+impl ConfiguredPeer {
+    /// Returns a basic peer for a bootnode.
+    pub const fn basic(record: NodeRecord) -> Self {
+        Self { record, kind: PeerKind::Basic }
+    }
+}
+
+/// Parses the peers of a peers file.
+pub fn parse_peers_file(contents: &str) -> eyre::Result<Vec<ConfiguredPeer>> {
+    let file: PeersFile = toml::from_str(contents)?;
+    file.peers
+        .into_iter()
+        .map(|entry| {
+            ensure!(
+                !(entry.trusted && entry.is_static),
+                "peer {} can't be both trusted and static",
+                entry.enode
+            );
+            let record =
+                entry.enode.parse().wrap_err_with(|| format!("invalid enode {}", entry.enode))?;
+            let kind = if entry.trusted {
+                PeerKind::Trusted
+            } else if entry.is_static {
+                PeerKind::Static
+            } else {
+                PeerKind::Basic
+            };
+            Ok(ConfiguredPeer { record, kind })
+        })
+        .collect()
+}
+
+/// The operations on the peer set of the network the peers file is synced with.
+///
+/// Implemented by every [`Peers`] handle.
+pub trait PeerSet {
+    /// Removes the peer from the peer set.
+    fn remove_peer(&self, peer_id: PeerId, kind: PeerKind);
+
+    /// Disconnects the peer.
+    fn disconnect_peer(&self, peer_id: PeerId);
+
+    /// Adds the peer to the peer set and dials it.
+    fn connect_peer_kind(
+        &self,
+        peer_id: PeerId,
+        kind: PeerKind,
+        tcp_addr: SocketAddr,
+        udp_addr: Option<SocketAddr>,
+    );
+}
+
+impl<N: Peers> PeerSet for N {
+    fn remove_peer(&self, peer_id: PeerId, kind: PeerKind) {
+        Peers::remove_peer(self, peer_id, kind);
+    }
+
+    fn disconnect_peer(&self, peer_id: PeerId) {
+        Peers::disconnect_peer(self, peer_id);
+    }
+
+    fn connect_peer_kind(
+        &self,
+        peer_id: PeerId,
+        kind: PeerKind,
+        tcp_addr: SocketAddr,
+        udp_addr: Option<SocketAddr>,
+    ) {
+        Peers::connect_peer_kind(self, peer_id, kind, tcp_addr, udp_addr);
+    }
+}
+
+/// Changes between two versions of the configured peers.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PeersDiff {
+    /// Peers to dial.
+    pub added: Vec<ConfiguredPeer>,
+    /// Peers to disconnect.
+    pub removed: Vec<ConfiguredPeer>,
+}
+
+impl PeersDiff {
+    /// Returns `true` if no peer changed.
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Drops the added peers rejected by `policy`, returning how many were dropped.
+    pub fn retain_allowed(&mut self, policy: &PeerPolicy) -> usize {
+        let before = self.added.len();
+        self.added.retain(|peer| policy.check(peer.record.id, peer.record.address, None).is_ok());
+        before - self.added.len()
+    }
+
+    /// Applies the changes to the network: removed peers are dropped from the peer set and
+    /// disconnected, added peers are dialed.
+    pub fn apply<N: PeerSet>(&self, network: &N) {
+        for peer in &self.removed {
+            PeerSet::remove_peer(network, peer.record.id, peer.kind);
+            PeerSet::disconnect_peer(network, peer.record.id);
+        }
+        for peer in &self.added {
+            PeerSet::connect_peer_kind(
+                network,
+                peer.record.id,
+                peer.kind,
+                peer.record.tcp_addr(),
+                Some(peer.record.udp_addr()),
+            );
+        }
+    }
+}
+
+/// Tracks the peers of a peers file, falling back to a fixed list while the file is absent.
+#[derive(Debug)]
+pub struct PeersFileWatcher {
+    path: PathBuf,
+    fallback: Vec<ConfiguredPeer>,
+    contents: Option<String>,
+    peers: HashMap<PeerId, ConfiguredPeer>,
+}
+
+impl PeersFileWatcher {
+    /// Loads the peers file at `path`, or the `fallback` nodes if it doesn't exist.
+    ///
+    /// Fails if the file exists but is malformed.
+    pub fn new(path: impl Into<PathBuf>, fallback: Vec<NodeRecord>) -> eyre::Result<Self> {
+        let mut watcher = Self {
+            path: path.into(),
+            fallback: fallback.into_iter().map(ConfiguredPeer::basic).collect(),
+            contents: None,
+            peers: HashMap::new(),
+        };
+        let contents = watcher.read()?;
+        let peers = watcher.resolve(contents.as_deref())?;
+        watcher.contents = contents;
+        watcher.peers = peers.into_iter().map(|peer| (peer.record.id, peer)).collect();
+        Ok(watcher)
+    }
+
+    /// Returns the path of the peers file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the peers currently configured.
+    pub fn peers(&self) -> Vec<ConfiguredPeer> {
+        self.peers.values().copied().collect()
+    }
+
+    /// Returns `true` if the peers file exists, i.e. the fallback is not used.
+    pub const fn is_loaded(&self) -> bool {
+        self.contents.is_some()
+    }
+
+    /// Re-reads the peers file and returns the changes since the last call.
+    ///
+    /// If the file is malformed the previous peers are kept.
+    pub fn poll(&mut self) -> eyre::Result<PeersDiff> {
+        let contents = self.read()?;
+        if contents == self.contents {
+            return Ok(PeersDiff::default());
+        }
+        // Remember the contents even if they are malformed, so they are only reported once.
+        let peers = self.resolve(contents.as_deref());
+        self.contents = contents;
+        let peers: HashMap<_, _> = peers?.into_iter().map(|peer| (peer.record.id, peer)).collect();
+
+        let mut diff = PeersDiff::default();
+        for (id, peer) in &self.peers {
+            if peers.get(id) != Some(peer) {
+                diff.removed.push(*peer);
+            }
+        }
+        for (id, peer) in &peers {
+            if self.peers.get(id) != Some(peer) {
+                diff.added.push(*peer);
+            }
+        }
+
+        self.peers = peers;
+        Ok(diff)
+    }
+
+    /// Reads the peers file, returning `None` if it doesn't exist.
+    fn read(&self) -> eyre::Result<Option<String>> {
+        match std::fs::read_to_string(&self.path) {
+            Ok(contents) => Ok(Some(contents)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err)
+                .wrap_err_with(|| format!("failed to read peers file {}", self.path.display())),
+        }
+    }
+
+    /// Returns the peers of the file contents, or the fallback if there are none.
+    fn resolve(&self, contents: Option<&str>) -> eyre::Result<Vec<ConfiguredPeer>> {
+        match contents {
+            Some(contents) => parse_peers_file(contents)
+                .wrap_err_with(|| format!("invalid peers file {}", self.path.display())),
+            None => Ok(self.fallback.clone()),
+        }
+    }
+}
+
+/// Spawns a task adding the peers of `watcher` to the network and keeping them in sync with the
+/// peers file, skipping the peers rejected by `policy`.
+pub fn spawn_peers_file_watcher<N>(
+    executor: &TaskExecutor,
+    network: N,
+    mut watcher: PeersFileWatcher,
+    policy: PeerPolicyHandle,
+) where
+    N: Peers + Send + 'static,
+{
+    executor.spawn_critical_task("fraxtal-peers-file", async move {
+        let mut initial = PeersDiff { added: watcher.peers(), removed: Vec::new() };
+        let rejected = initial.retain_allowed(&policy.current());
+        initial.apply(&network);
+        info!(
+            target: "reth::cli",
+            path = %watcher.path().display(),
+            count = initial.added.len(),
+            rejected,
+            loaded = watcher.is_loaded(),
+            "Watching peers file",
+        );
+
+        loop {
+            tokio::time::sleep(PEERS_FILE_POLL_INTERVAL).await;
+            match watcher.poll() {
+                Ok(diff) if diff.is_empty() => {}
+                Ok(mut diff) => {
+                    let rejected = diff.retain_allowed(&policy.current());
+                    info!(
+                        target: "reth::cli",
+                        added = diff.added.len(),
+                        removed = diff.removed.len(),
+                        rejected,
+                        loaded = watcher.is_loaded(),
+                        "Peers file changed",
+                    );
+                    diff.apply(&network);
+                }
+                Err(err) => {
+                    warn!(target: "reth::cli", %err, "Ignoring peers file update");
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    const NODE_A: &str = "enode://6f8a80d14311c39f35f516fa664deaaaa13e85b2f7493f37f6144d86991ec012937307647bd3b9a82abe2974e1407241d54947bbb39763a4cac9f77166ad92a0@10.3.58.6:30303";
+    const NODE_B: &str = "enode://3f1d12044546b76342d59d4a05532c14b85aa669704bfe1f864fe079415aa2c02d743e03218e57a33fb94523adb54032871a6c51b2cc5514cb7c7e35b3ed0a99@10.3.58.7:30303";
+
+    fn record(enode: &str) -> NodeRecord {
+        enode.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_peer_kinds() {
+        let peers = parse_peers_file(&format!(
+            "[[peers]]\nenode = \"{NODE_A}\"\ntrusted = true\n\n[[peers]]\nenode = \"{NODE_B}\"\nstatic = true\n"
+        ))
+        .unwrap();
+        assert_eq!(
+            peers,
+            [
+                ConfiguredPeer { record: record(NODE_A), kind: PeerKind::Trusted },
+                ConfiguredPeer { record: record(NODE_B), kind: PeerKind::Static },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_peers() {
+        let err = parse_peers_file(&format!(
+            "[[peers]]\nenode = \"{NODE_A}\"\ntrusted = true\nstatic = true\n"
+        ))
+        .unwrap_err();
+        assert!(err.to_string().contains("both trusted and static"), "{err}");
+        assert!(parse_peers_file("[[peers]]\nenode = \"enode://nope\"\n").is_err());
+    }
+
+    /// Operation recorded by [`RecordingPeers`].
+    #[derive(Debug, PartialEq, Eq)]
+    enum PeerOp {
+        Remove(PeerId, PeerKind),
+        Disconnect(PeerId),
+        Connect(PeerId, PeerKind, SocketAddr, Option<SocketAddr>),
+    }
+
+    /// Peer set recording the operations applied to it.
+    #[derive(Debug, Default)]
+    struct RecordingPeers {
+        ops: Mutex<Vec<PeerOp>>,
+    }
+
+    impl PeerSet for RecordingPeers {
+        fn remove_peer(&self, peer_id: PeerId, kind: PeerKind) {
+            self.ops.lock().unwrap().push(PeerOp::Remove(peer_id, kind));
+        }
+
+        fn disconnect_peer(&self, peer_id: PeerId) {
+            self.ops.lock().unwrap().push(PeerOp::Disconnect(peer_id));
+        }
+
+        fn connect_peer_kind(
+            &self,
+            peer_id: PeerId,
+            kind: PeerKind,
+            tcp_addr: SocketAddr,
+            udp_addr: Option<SocketAddr>,
+        ) {
+            self.ops.lock().unwrap().push(PeerOp::Connect(peer_id, kind, tcp_addr, udp_addr));
+        }
+    }
+
+    #[test]
+    fn applies_diff_to_network() {
+        let removed = ConfiguredPeer { record: record(NODE_A), kind: PeerKind::Trusted };
+        let added = ConfiguredPeer::basic(record(NODE_B));
+        let network = RecordingPeers::default();
+
+        PeersDiff { added: vec![added], removed: vec![removed] }.apply(&network);
+
+        assert_eq!(
+            network.ops.into_inner().unwrap(),
+            [
+                PeerOp::Remove(removed.record.id, PeerKind::Trusted),
+                PeerOp::Disconnect(removed.record.id),
+                PeerOp::Connect(
+                    added.record.id,
+                    PeerKind::Basic,
+                    added.record.tcp_addr(),
+                    Some(added.record.udp_addr()),
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_peers_rejected_by_policy() {
+        let peer_a = ConfiguredPeer::basic(record(NODE_A));
+        let peer_b = ConfiguredPeer::basic(record(NODE_B));
+        let policy =
+            PeerPolicy::new(toml::from_str("[deny]\ncidrs = [\"10.3.58.7\"]\n").unwrap()).unwrap();
+
+        let mut diff = PeersDiff { added: vec![peer_a, peer_b], removed: vec![peer_b] };
+        assert_eq!(diff.retain_allowed(&policy), 1);
+        // Removed peers are still disconnected.
+        assert_eq!(diff, PeersDiff { added: vec![peer_a], removed: vec![peer_b] });
+    }
+
+    #[test]
+    fn tracks_file_changes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("peers.toml");
+        let fallback = ConfiguredPeer::basic(record(NODE_B));
+
+        // Without a file the fallback is used.
+        let mut watcher = PeersFileWatcher::new(&path, vec![fallback.record]).unwrap();
+        assert!(!watcher.is_loaded());
+        assert_eq!(watcher.peers(), [fallback]);
+        assert!(watcher.poll().unwrap().is_empty());
+
+        // The file replaces the fallback.
+        std::fs::write(&path, format!("[[peers]]\nenode = \"{NODE_A}\"\n")).unwrap();
+        let peer_a = ConfiguredPeer::basic(record(NODE_A));
+        assert_eq!(
+            watcher.poll().unwrap(),
+            PeersDiff { added: vec![peer_a], removed: vec![fallback] }
+        );
+        assert!(watcher.is_loaded());
+
+        // A changed kind re-adds the peer.
+        std::fs::write(&path, format!("[[peers]]\nenode = \"{NODE_A}\"\ntrusted = true\n"))
+            .unwrap();
+        let trusted_a = ConfiguredPeer { kind: PeerKind::Trusted, ..peer_a };
+        assert_eq!(
+            watcher.poll().unwrap(),
+            PeersDiff { added: vec![trusted_a], removed: vec![peer_a] }
+        );
+
+        // A malformed file keeps the current peers.
+        std::fs::write(&path, "peers = 1").unwrap();
+        assert!(watcher.poll().is_err());
+        assert!(watcher.poll().unwrap().is_empty());
+        assert_eq!(watcher.peers(), [trusted_a]);
+
+        // Removing the file falls back to the bootnodes again.
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            watcher.poll().unwrap(),
+            PeersDiff { added: vec![fallback], removed: vec![trusted_a] }
+        );
+    }
+}
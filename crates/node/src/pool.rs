@@ -0,0 +1,238 @@
+//! Transaction pool builder applying the transaction type gossip policy.
+//!
+//! Mirrors `reth_optimism_node::node::OpPoolBuilder`, but wraps the OP transaction validator in a
+//! [`GossipTypeValidator`], which clears the `propagate` flag of the transactions whose type is
+//! not gossiped, see [`TxGossipTypes`]. The transactions manager only gossips pooled transactions
+//! with the flag set, so the other types stay in the local pool and are still included in blocks.
+
+use crate::tx_gossip::TxGossipTypes;
+use alloy_eips::Typed2718;
+use reth_evm::ConfigureEvm;
+use reth_node_api::NodeTypes;
+use reth_node_builder::{
+    BuilderContext,
+    components::{PoolBuilder, PoolBuilderConfigOverrides, TxPoolBuilder, create_blob_store},
+    node::FullNodeTypes,
+};
+use reth_optimism_forks::OpHardforks;
+use reth_optimism_primitives::OpPrimitives;
+use reth_optimism_txpool::{
+    OpPooledTransaction, OpTransactionValidator,
+    supervisor::{DEFAULT_SUPERVISOR_URL, SafetyLevel, SupervisorClient},
+};
+use reth_primitives_traits::SealedBlock;
+use reth_provider::CanonStateSubscriptions;
+use reth_transaction_pool::{
+    CoinbaseTipOrdering, Pool, TransactionOrigin, TransactionValidationOutcome,
+    TransactionValidationTaskExecutor, TransactionValidator, blobstore::DiskFileBlobStore,
+};
+use tracing::{debug, info};
+
+/// Transaction validator clearing the `propagate` flag of the transactions whose type is not
+/// gossiped.
+#[derive(Debug, Clone)]
+pub struct GossipTypeValidator<V> {
+    inner: V,
+    types: TxGossipTypes,
+}
+
+impl<V> GossipTypeValidator<V> {
+    /// Wraps `inner`, only gossiping the transactions of the given `types`.
+    pub const fn new(inner: V, types: TxGossipTypes) -> Self {
+        Self { inner, types }
+    }
+
+    /// Returns the wrapped validator.
+    pub const fn inner(&self) -> &V {
+        &self.inner
+    }
+}
+
+impl<V: TransactionValidator> GossipTypeValidator<V> {
+    /// Clears the `propagate` flag of a valid transaction whose type is not gossiped.
+    fn filter(
+        &self,
+        mut outcome: TransactionValidationOutcome<V::Transaction>,
+    ) -> TransactionValidationOutcome<V::Transaction> {
+        if let TransactionValidationOutcome::Valid { transaction, propagate, .. } = &mut outcome
+            && !self.types.contains(transaction.transaction().ty())
+        {
+            *propagate = false;
+        }
+        outcome
+    }
+}
+
+impl<V: TransactionValidator> TransactionValidator for GossipTypeValidator<V> {
+    type Transaction = V::Transaction;
+    type Block = V::Block;
+
+    async fn validate_transaction(
+        &self,
+        origin: TransactionOrigin,
+        transaction: Self::Transaction,
+    ) -> TransactionValidationOutcome<Self::Transaction> {
+        self.filter(self.inner.validate_transaction(origin, transaction).await)
+    }
+
+    async fn validate_transactions(
+        &self,
+        transactions: Vec<(TransactionOrigin, Self::Transaction)>,
+    ) -> Vec<TransactionValidationOutcome<Self::Transaction>> {
+        let outcomes = self.inner.validate_transactions(transactions).await;
+        outcomes.into_iter().map(|outcome| self.filter(outcome)).collect()
+    }
+
+    fn on_new_head_block(&self, new_tip_block: &SealedBlock<Self::Block>) {
+        self.inner.on_new_head_block(new_tip_block);
+    }
+}
+
+/// Transaction pool of a Fraxtal node.
+pub type FraxtalTransactionPool<Client, S, Evm, T = OpPooledTransaction> = Pool<
+    TransactionValidationTaskExecutor<GossipTypeValidator<OpTransactionValidator<Client, T, Evm>>>,
+    CoinbaseTipOrdering<T>,
+    S,
+>;
+
+/// Pool builder for Fraxtal nodes.
+#[derive(Debug, Clone)]
+pub struct FraxtalPoolBuilder {
+    /// Enforce the conditional of transactions sent with `eth_sendRawTransactionConditional`.
+    pub enable_tx_conditional: bool,
+    /// Supervisor client url.
+    pub supervisor_http: String,
+    /// Supervisor safety level.
+    pub supervisor_safety_level: SafetyLevel,
+    /// Transaction types pooled transactions are gossiped for.
+    pub gossip_types: TxGossipTypes,
+    /// Overrides of the pool configuration.
+    pub pool_config_overrides: PoolBuilderConfigOverrides,
+}
+
+impl Default for FraxtalPoolBuilder {
+    fn default() -> Self {
+        Self {
+            enable_tx_conditional: false,
+            supervisor_http: DEFAULT_SUPERVISOR_URL.to_string(),
+            supervisor_safety_level: SafetyLevel::CrossUnsafe,
+            gossip_types: TxGossipTypes::default(),
+            pool_config_overrides: PoolBuilderConfigOverrides::default(),
+        }
+    }
+}
+
+impl FraxtalPoolBuilder {
+    /// Sets whether the conditional of transactions is enforced.
+    pub const fn with_enable_tx_conditional(mut self, enable_tx_conditional: bool) -> Self {
+        self.enable_tx_conditional = enable_tx_conditional;
+        self
+    }
+
+    /// Sets the supervisor client url and safety level.
+    pub fn with_supervisor(
+        mut self,
+        supervisor_http: String,
+        supervisor_safety_level: SafetyLevel,
+    ) -> Self {
+        self.supervisor_http = supervisor_http;
+        self.supervisor_safety_level = supervisor_safety_level;
+        self
+    }
+
+    /// Sets the transaction types pooled transactions are gossiped for.
+    pub const fn with_gossip_types(mut self, gossip_types: TxGossipTypes) -> Self {
+        self.gossip_types = gossip_types;
+        self
+    }
+}
+
+impl<Node, Evm> PoolBuilder<Node, Evm> for FraxtalPoolBuilder
+where
+    Node: FullNodeTypes<Types: NodeTypes<ChainSpec: OpHardforks, Primitives = OpPrimitives>>,
+    Evm: ConfigureEvm<Primitives = OpPrimitives> + Clone + 'static,
+{
+    type Pool = FraxtalTransactionPool<Node::Provider, DiskFileBlobStore, Evm>;
+
+    async fn build_pool(
+        self,
+        ctx: &BuilderContext<Node>,
+        evm_config: Evm,
+    ) -> eyre::Result<Self::Pool> {
+        let Self {
+            enable_tx_conditional,
+            supervisor_http,
+            supervisor_safety_level,
+            gossip_types,
+            pool_config_overrides,
+        } = self;
+
+        let interop = ctx.chain_spec().is_interop_active_at_timestamp(ctx.head().timestamp);
+        if interop && supervisor_http == DEFAULT_SUPERVISOR_URL {
+            info!(target: "reth::cli", url = %DEFAULT_SUPERVISOR_URL, "Default supervisor url is used, consider changing --rollup.supervisor-http");
+        }
+        let supervisor_client = SupervisorClient::builder(supervisor_http)
+            .minimum_safety(supervisor_safety_level)
+            .build()
+            .await;
+
+        let blob_store = create_blob_store(ctx)?;
+        let validator =
+            TransactionValidationTaskExecutor::eth_builder(ctx.provider().clone(), evm_config)
+                .no_eip4844()
+                .with_head_timestamp(ctx.head().timestamp)
+                .with_max_tx_input_bytes(ctx.config().txpool.max_tx_input_bytes)
+                .kzg_settings(ctx.kzg_settings()?)
+                .set_tx_fee_cap(ctx.config().rpc.rpc_tx_fee_cap)
+                .with_max_tx_gas_limit(ctx.config().txpool.max_tx_gas_limit)
+                .with_minimum_priority_fee(ctx.config().txpool.minimum_priority_fee)
+                .with_additional_tasks(
+                    pool_config_overrides
+                        .additional_validation_tasks
+                        .unwrap_or_else(|| ctx.config().txpool.additional_validation_tasks),
+                )
+                .build_with_tasks(ctx.task_executor().clone(), blob_store.clone())
+                .map(|validator| {
+                    let validator = OpTransactionValidator::new(validator)
+                        // In --dev mode we can't require gas fees because we're unable to decode
+                        // the L1 block info.
+                        .require_l1_data_gas_fee(!ctx.config().dev.dev)
+                        .with_supervisor(supervisor_client.clone());
+                    GossipTypeValidator::new(validator, gossip_types)
+                });
+
+        let pool_config = pool_config_overrides.apply(ctx.pool_config());
+        let transaction_pool = TxPoolBuilder::new(ctx)
+            .with_validator(validator)
+            .build_and_spawn_maintenance_task(blob_store, pool_config)?;
+        info!(target: "reth::cli", ?gossip_types, "Transaction pool initialized");
+        debug!(target: "reth::cli", "Spawned txpool maintenance task");
+
+        if interop {
+            let chain_events = ctx.provider().canonical_state_stream();
+            ctx.task_executor().spawn_critical_task(
+                "fraxtal-txpool-interop-maintenance",
+                reth_optimism_txpool::maintain::maintain_transaction_pool_interop_future(
+                    transaction_pool.clone(),
+                    chain_events,
+                    supervisor_client,
+                ),
+            );
+            debug!(target: "reth::cli", "Spawned txpool interop maintenance task");
+        }
+
+        if enable_tx_conditional {
+            let chain_events = ctx.provider().canonical_state_stream();
+            ctx.task_executor().spawn_critical_task(
+                "fraxtal-txpool-conditional-maintenance",
+                reth_optimism_txpool::maintain::maintain_transaction_pool_conditional_future(
+                    transaction_pool.clone(),
+                    chain_events,
+                ),
+            );
+            debug!(target: "reth::cli", "Spawned txpool conditional maintenance task");
+        }
+
+        Ok(transaction_pool)
+    }
+}
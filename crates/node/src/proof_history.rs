@@ -1,7 +1,21 @@
 //! Node luncher with proof history support.
 
-use crate::node::FraxtalNode;
-use eyre::ErrReport;
+use crate::{
+    args::FraxtalArgs,
+    dev::DevPayloadAttributesHandle,
+    migrations::load_migration_plan,
+    node::{FraxtalNode, L1InfoReader, OpLocalPayloadAttributesBuilder},
+    peer_policy::{PeerPolicy, PeerPolicyHandle},
+    reports::{MIGRATION_REPORTS_DIR, MigrationReportStore, spawn_migration_reports},
+    reputation::{PeerScores, ReputationConfig},
+    rpc::{
+        FraxtalAdminApiServer, FraxtalAdminRpc, FraxtalApiServer, FraxtalDevApiServer,
+        FraxtalDevRpc, FraxtalRpc,
+    },
+};
+use alloy_consensus::{BlockHeader, Transaction};
+use eyre::{ErrReport, OptionExt};
+use fraxtal_chainspec::{FraxtalChainSpec, fraxtal_bootnodes, overrides::applied_overrides};
 use futures_util::FutureExt;
 use reth_db::DatabaseEnv;
 use reth_db_api::database_metrics::DatabaseMetrics;
@@ -14,6 +28,8 @@ use reth_optimism_rpc::{
     eth::proofs::{EthApiExt, EthApiOverrideServer},
 };
 use reth_optimism_trie::{OpProofsStorage, db::MdbxProofsStorage};
+use reth_provider::{BlockNumReader, HeaderProvider, TransactionsProvider};
+use reth_rpc_server_types::RethRpcModule;
 use reth_tasks::TaskExecutor;
 use std::{sync::Arc, time::Duration};
 use tokio::time::sleep;
@@ -24,8 +40,12 @@ use tracing::info;
 /// - MDBX proofs storage.
 pub async fn launch_node_with_proof_history(
     builder: WithLaunchContext<NodeBuilder<DatabaseEnv, OpChainSpec>>,
-    args: RollupArgs,
+    fraxtal_args: FraxtalArgs,
 ) -> eyre::Result<(), ErrReport> {
+    let tx_gossip = fraxtal_args.tx_gossip_policy()?;
+    let dev_attributes = DevPayloadAttributesHandle::new(fraxtal_args.dev.attributes())
+        .map_err(|err| err.wrap_err("invalid dev payload attributes"))?;
+    let FraxtalArgs { rollup: args, migration_plan, peers_file, peer_policy, .. } = fraxtal_args;
     let RollupArgs {
         proofs_history,
         proofs_history_window,
@@ -34,8 +54,82 @@ pub async fn launch_node_with_proof_history(
         ..
     } = args;
 
+    // Refuse to start if the chain claims the id of a built-in chain but not its pinned genesis.
+    FraxtalChainSpec::verify(&builder.config().chain)?;
+
     // Start from a plain FraxtalNode builder
-    let mut node_builder = builder.node(FraxtalNode::new(args.clone()));
+    let mut node = FraxtalNode::new(args.clone());
+    node = node.with_tx_gossip(tx_gossip);
+    let migrations = match migration_plan {
+        Some(path) => {
+            info!(target: "reth::cli", path = %path.display(), "Loading fork migration plan");
+            Some(Arc::new(load_migration_plan(&path)?))
+        }
+        None => None,
+    };
+    if let Some(migrations) = &migrations {
+        node = node.with_migrations(migrations.clone());
+    }
+    let migration_reports = MigrationReportStore::open(
+        builder.config().datadir().data_dir().join(MIGRATION_REPORTS_DIR),
+    )?;
+    let (rpc_migrations, rpc_migration_reports) = (migrations.clone(), migration_reports.clone());
+    if let Some(path) = peers_file {
+        node = node.with_peers_file(path);
+    }
+    let peer_policy = match peer_policy {
+        Some(path) => {
+            info!(target: "reth::cli", path = %path.display(), "Loading peer policy");
+            PeerPolicyHandle::new(PeerPolicy::load(&path)?)
+        }
+        None => PeerPolicyHandle::default(),
+    };
+    node = node.with_peer_policy(peer_policy.clone());
+    // The bootnodes and trusted peers are the Fraxtal infrastructure peers.
+    let infra_peers = fraxtal_bootnodes(&*builder.config().chain)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|node| node.id)
+        .chain(builder.config().network.trusted_peers.iter().map(|peer| peer.id));
+    let peer_scores = PeerScores::new(ReputationConfig::default().with_infra_peers(infra_peers));
+    node = node.with_peer_scores(peer_scores.clone());
+    let overrides = applied_overrides(&*builder.config().chain)?;
+    let dev = builder.config().dev.dev;
+    let l1_info = L1InfoReader::default();
+    let dev_attributes_builder = OpLocalPayloadAttributesBuilder::new(
+        builder.config().chain.clone(),
+        dev_attributes.clone(),
+        l1_info.clone(),
+    );
+    let mut node_builder = builder.node(node);
+
+    // The component initialized hook is set once, so it serves both the chain spec overrides and
+    // the dev blocks.
+    node_builder = node_builder.on_component_initialized(move |node| {
+        let provider = node.provider();
+        // Chain spec overrides may only move forks that are not active at the local head.
+        if let Some(overrides) = overrides {
+            let head = provider.best_block_number()?;
+            let header =
+                provider.header_by_number(head)?.ok_or_eyre("missing header of the local head")?;
+            overrides.validate(header.timestamp())?;
+            info!(target: "reth::cli", head, "Applied chain spec overrides");
+        }
+        // Dev blocks continue the simulated L1 chain from the deposits of their parents.
+        if dev {
+            let provider = provider.clone();
+            l1_info.set(move |hash| {
+                let transactions = provider.transactions_by_block(hash.into()).ok()??;
+                transactions.first().map(|tx| tx.input().clone())
+            });
+        }
+        Ok(())
+    });
+
+    // The RPC extension and node started hooks are set once, so each of them serves both the
+    // Fraxtal extensions and proofs history.
+    let mut proofs_rpc_storage = None;
+    let mut proofs_db_metrics = None;
 
     if proofs_history {
         let path = args
@@ -51,44 +145,74 @@ pub async fn launch_node_with_proof_history(
         let storage: OpProofsStorage<Arc<MdbxProofsStorage>> = mdbx.clone().into();
 
         let storage_exec = storage.clone();
+        proofs_rpc_storage = Some(storage);
+        proofs_db_metrics = Some(mdbx);
 
-        node_builder = node_builder
-            .on_node_started(move |node| {
-                spawn_proofs_db_metrics(
-                    node.task_executor,
-                    mdbx,
-                    node.config.metrics.push_gateway_interval,
-                );
-                Ok(())
-            })
-            .install_exex("proofs-history", async move |exex_context| {
-                Ok(OpProofsExEx::builder(exex_context, storage_exec)
-                    .with_proofs_history_window(proofs_history_window)
-                    .with_proofs_history_prune_interval(proofs_history_prune_interval)
-                    .with_verification_interval(proofs_history_verification_interval)
-                    .build()
-                    .run()
-                    .boxed())
-            })
-            .extend_rpc_modules(move |ctx| {
-                info!(target: "reth::cli", "Installing proofs-history RPC overrides (eth_getProof, debug_executePayload)");
-                let api_ext = EthApiExt::new(ctx.registry.eth_api().clone(), storage.clone());
-                let debug_ext = DebugApiExt::new(
-                    ctx.node().provider().clone(),
-                    ctx.registry.eth_api().clone(),
-                    storage,
-                    ctx.node().task_executor().clone(),
-                    ctx.node().evm_config().clone(),
-                );
-                let eth_replaced = ctx.modules.replace_configured(api_ext.into_rpc())?;
-                let debug_replaced = ctx.modules.replace_configured(debug_ext.into_rpc())?;
-                info!(target: "reth::cli", eth_replaced, debug_replaced, "Proofs-history RPC overrides installed");
-                Ok(())
-            });
+        node_builder = node_builder.install_exex("proofs-history", async move |exex_context| {
+            Ok(OpProofsExEx::builder(exex_context, storage_exec)
+                .with_proofs_history_window(proofs_history_window)
+                .with_proofs_history_prune_interval(proofs_history_prune_interval)
+                .with_verification_interval(proofs_history_verification_interval)
+                .build()
+                .run()
+                .boxed())
+        });
     }
 
+    // Migration reports are recorded from the blocks that become canonical, off the executor.
+    node_builder = node_builder.on_node_started(move |node| {
+        info!(target: "reth::cli", dir = %migration_reports.dir().display(), "Recording fork migration reports");
+        spawn_migration_reports(
+            &node.task_executor,
+            node.provider.clone(),
+            migrations,
+            migration_reports,
+        );
+        if let Some(mdbx) = proofs_db_metrics {
+            spawn_proofs_db_metrics(
+                node.task_executor,
+                mdbx,
+                node.config.metrics.push_gateway_interval,
+            );
+        }
+        Ok(())
+    });
+
+    node_builder = node_builder.extend_rpc_modules(move |ctx| {
+        ctx.modules.merge_configured(
+            FraxtalRpc::new(ctx.node().provider().clone(), rpc_migrations, rpc_migration_reports)
+                .into_rpc(),
+        )?;
+        ctx.modules.merge_if_module_configured(
+            RethRpcModule::Admin,
+            FraxtalAdminRpc::new(peer_policy, peer_scores).into_rpc(),
+        )?;
+        if dev {
+            ctx.modules.merge_configured(FraxtalDevRpc::new(dev_attributes).into_rpc())?;
+        }
+
+        if let Some(storage) = proofs_rpc_storage {
+            info!(target: "reth::cli", "Installing proofs-history RPC overrides (eth_getProof, debug_executePayload)");
+            let api_ext = EthApiExt::new(ctx.registry.eth_api().clone(), storage.clone());
+            let debug_ext = DebugApiExt::new(
+                ctx.node().provider().clone(),
+                ctx.registry.eth_api().clone(),
+                storage,
+                ctx.node().task_executor().clone(),
+                ctx.node().evm_config().clone(),
+            );
+            let eth_replaced = ctx.modules.replace_configured(api_ext.into_rpc())?;
+            let debug_replaced = ctx.modules.replace_configured(debug_ext.into_rpc())?;
+            info!(target: "reth::cli", eth_replaced, debug_replaced, "Proofs-history RPC overrides installed");
+        }
+        Ok(())
+    });
+
     // In all cases (with or without proofs), launch the node.
-    let handle = node_builder.launch_with_debug_capabilities().await?;
+    let handle = node_builder
+        .launch_with_debug_capabilities()
+        .with_payload_attributes_builder(dev_attributes_builder)
+        .await?;
     handle.node_exit_future.await
 }
 /// Spawns a task that periodically reports metrics for the proofs DB.
@@ -0,0 +1,295 @@
+//! Persistence of the fork migration reports of canonical blocks.
+//!
+//! Reports are recorded off the execution path: once a fork activation block becomes canonical,
+//! its migrations are applied again to the state of its parent. They run before any transaction
+//! of the block, so this yields the same diff as the execution of the block did. Blocks that never
+//! went through the canonical state stream, such as the ones synced by the pipeline, are backfilled
+//! the same way when their reports are first requested.
+
+use alloy_consensus::BlockHeader;
+use alloy_primitives::B256;
+use fraxtal_op_evm::block::{MigrationPlan, MigrationReport, apply_with_diff};
+use futures_util::StreamExt;
+use reth_chainspec::EthChainSpec;
+use reth_optimism_forks::OpHardforks;
+use reth_optimism_primitives::OpPrimitives;
+use reth_provider::{
+    CanonStateSubscriptions, ChainSpecProvider, HeaderProvider, StateProviderFactory,
+};
+use reth_revm::database::StateProviderDatabase;
+use reth_tasks::TaskExecutor;
+use revm::database::State;
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+use tracing::{debug, warn};
+
+/// Name of the directory within the node data directory holding the migration reports.
+pub const MIGRATION_REPORTS_DIR: &str = "migration-reports";
+
+/// Stores the migration reports of canonical blocks as one JSON file per block.
+///
+/// Reports are keyed by block hash, so the reports of a block that is reorged out never replace
+/// the ones of the canonical block at the same height.
+#[derive(Debug, Clone)]
+pub struct MigrationReportStore {
+    dir: PathBuf,
+}
+
+impl MigrationReportStore {
+    /// Opens the store in the given directory, creating it if it does not exist.
+    pub fn open(dir: impl Into<PathBuf>) -> io::Result<Self> {
+        let dir = dir.into();
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    /// Returns the directory of the store.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the reports of the fork migrations applied by the block with the given hash.
+    ///
+    /// Blocks that applied no migration have no reports.
+    pub fn get(&self, block_hash: B256) -> eyre::Result<Vec<MigrationReport>> {
+        Ok(self.load(block_hash)?.unwrap_or_default())
+    }
+
+    /// Returns the stored reports of the block with the given hash, `None` if the store has no
+    /// entry for it.
+    pub fn load(&self, block_hash: B256) -> eyre::Result<Option<Vec<MigrationReport>>> {
+        match fs::read(self.path(block_hash)) {
+            Ok(contents) => Ok(Some(serde_json::from_slice(&contents)?)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Stores the reports of the block with the given hash, replacing any previous ones.
+    pub fn insert(&self, block_hash: B256, reports: &[MigrationReport]) -> eyre::Result<()> {
+        let path = self.path(block_hash);
+        // Write to a temporary file first so a crash never leaves a truncated report behind.
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(reports)?)?;
+        fs::rename(&tmp, &path)?;
+        Ok(())
+    }
+
+    fn path(&self, block_hash: B256) -> PathBuf {
+        self.dir.join(format!("{block_hash}.json"))
+    }
+}
+
+/// Spawns a task storing the migration reports of the fork activation blocks that become
+/// canonical.
+///
+/// `migrations` overrides the built-in plan of the chain, as for the executor.
+pub fn spawn_migration_reports<P>(
+    executor: &TaskExecutor,
+    provider: P,
+    migrations: Option<Arc<MigrationPlan>>,
+    store: MigrationReportStore,
+) where
+    P: CanonStateSubscriptions<Primitives = OpPrimitives>
+        + StateProviderFactory
+        + HeaderProvider<Header = alloy_consensus::Header>
+        + ChainSpecProvider<ChainSpec: OpHardforks + EthChainSpec>
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    executor.spawn_critical_task("fraxtal-migration-reports", async move {
+        let mut notifications = provider.canonical_state_stream();
+        while let Some(notification) = notifications.next().await {
+            for block in notification.committed().blocks_iter() {
+                let (block_hash, header) = (block.hash(), block.header().clone());
+                let (provider, migrations, store) =
+                    (provider.clone(), migrations.clone(), store.clone());
+                // Reading the parent state and writing the report block, keep them off the
+                // notification loop.
+                tokio::task::spawn_blocking(move || {
+                    let block_number = header.number();
+                    match record_block(&provider, migrations.as_deref(), &store, block_hash, &header)
+                    {
+                        Ok(0) => {}
+                        Ok(count) => {
+                            debug!(target: "reth::cli", block_number, %block_hash, count, "Stored fork migration reports");
+                        }
+                        Err(err) => {
+                            warn!(target: "reth::cli", block_number, %block_hash, %err, "Failed to store fork migration reports");
+                        }
+                    }
+                });
+            }
+        }
+    });
+}
+
+/// Stores the reports of the migrations activated by the given canonical block, returning their
+/// number.
+fn record_block<P>(
+    provider: &P,
+    migrations: Option<&MigrationPlan>,
+    store: &MigrationReportStore,
+    block_hash: B256,
+    header: &alloy_consensus::Header,
+) -> eyre::Result<usize>
+where
+    P: StateProviderFactory
+        + HeaderProvider<Header = alloy_consensus::Header>
+        + ChainSpecProvider<ChainSpec: OpHardforks + EthChainSpec>,
+{
+    let reports = migration_reports(provider, migrations, header)?;
+    if !reports.is_empty() {
+        store.insert(block_hash, &reports)?;
+    }
+    Ok(reports.len())
+}
+
+/// Returns the reports of the block with the given hash from the store, computing and storing
+/// them if the store has no entry for it.
+///
+/// Unknown blocks have no reports.
+pub fn backfill_block<P>(
+    provider: &P,
+    migrations: Option<&MigrationPlan>,
+    store: &MigrationReportStore,
+    block_hash: B256,
+) -> eyre::Result<Vec<MigrationReport>>
+where
+    P: StateProviderFactory
+        + HeaderProvider<Header = alloy_consensus::Header>
+        + ChainSpecProvider<ChainSpec: OpHardforks + EthChainSpec>,
+{
+    if let Some(reports) = store.load(block_hash)? {
+        return Ok(reports);
+    }
+    let Some(header) = provider.header(block_hash)? else {
+        return Ok(Vec::new());
+    };
+    let reports = migration_reports(provider, migrations, &header)?;
+    if !reports.is_empty() {
+        store.insert(block_hash, &reports)?;
+        debug!(target: "reth::cli", block_number = header.number(), %block_hash, count = reports.len(), "Backfilled fork migration reports");
+    }
+    Ok(reports)
+}
+
+/// Applies the migrations activated by the given block to the state of its parent, returning
+/// their reports.
+fn migration_reports<P>(
+    provider: &P,
+    migrations: Option<&MigrationPlan>,
+    header: &alloy_consensus::Header,
+) -> eyre::Result<Vec<MigrationReport>>
+where
+    P: StateProviderFactory
+        + HeaderProvider<Header = alloy_consensus::Header>
+        + ChainSpecProvider<ChainSpec: OpHardforks + EthChainSpec>,
+{
+    let chain_spec = provider.chain_spec();
+    let chain_id = chain_spec.chain().id();
+    let plan = migrations.unwrap_or_else(|| MigrationPlan::builtin(chain_id));
+    let Some(parent) = provider.header(header.parent_hash())? else {
+        eyre::bail!("missing parent header {}", header.parent_hash());
+    };
+    let activated: Vec<_> = MigrationPlan::op_stack()
+        .activated(&*chain_spec, header.timestamp(), parent.timestamp())
+        .chain(plan.activated(&*chain_spec, header.timestamp(), parent.timestamp()))
+        .collect();
+    if activated.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let state = provider.history_by_block_hash(header.parent_hash())?;
+    let mut db = State::builder().with_database(StateProviderDatabase::new(state)).build();
+    activated
+        .into_iter()
+        .map(|migration| {
+            let diff = apply_with_diff(&migration.transitions, &mut db).map_err(|err| {
+                eyre::eyre!("failed to apply {} migrations: {err}", migration.fork)
+            })?;
+            Ok(MigrationReport {
+                fork: migration.fork,
+                chain_id,
+                block_number: header.number(),
+                accounts: diff.accounts,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{address, b256};
+    use fraxtal_op_evm::block::AccountDiff;
+    use reth_optimism_forks::OpHardfork;
+
+    const BLOCK_HASH: B256 =
+        b256!("0101010101010101010101010101010101010101010101010101010101010101");
+
+    fn report(block_number: u64) -> MigrationReport {
+        MigrationReport {
+            fork: OpHardfork::Granite,
+            chain_id: 252,
+            block_number,
+            accounts: vec![AccountDiff {
+                address: address!("fc00000000000000000000000000000000000001"),
+                code_hash_before: Some(b256!(
+                    "1111111111111111111111111111111111111111111111111111111111111111"
+                )),
+                code_hash_after: Some(b256!(
+                    "2222222222222222222222222222222222222222222222222222222222222222"
+                )),
+                storage: Vec::new(),
+            }],
+        }
+    }
+
+    #[test]
+    fn store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MigrationReportStore::open(dir.path().join(MIGRATION_REPORTS_DIR)).unwrap();
+
+        assert!(store.get(BLOCK_HASH).unwrap().is_empty());
+        assert_eq!(store.load(BLOCK_HASH).unwrap(), None);
+
+        store.insert(BLOCK_HASH, &[report(10)]).unwrap();
+        assert_eq!(store.get(BLOCK_HASH).unwrap(), vec![report(10)]);
+        assert_eq!(store.load(BLOCK_HASH).unwrap(), Some(vec![report(10)]));
+        assert!(store.get(B256::ZERO).unwrap().is_empty());
+    }
+
+    #[test]
+    fn insert_replaces_previous_reports() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MigrationReportStore::open(dir.path()).unwrap();
+
+        let mut first = report(10);
+        first.fork = OpHardfork::Holocene;
+        store.insert(BLOCK_HASH, &[first, report(10)]).unwrap();
+        store.insert(BLOCK_HASH, &[report(10)]).unwrap();
+
+        assert_eq!(store.get(BLOCK_HASH).unwrap(), vec![report(10)]);
+    }
+
+    #[test]
+    fn keys_reports_by_block_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = MigrationReportStore::open(dir.path()).unwrap();
+
+        // A reorged out block at the same height does not replace the canonical reports.
+        let mut reorged = report(10);
+        reorged.accounts.clear();
+        store.insert(BLOCK_HASH, &[report(10)]).unwrap();
+        store.insert(B256::ZERO, &[reorged.clone()]).unwrap();
+
+        assert_eq!(store.get(BLOCK_HASH).unwrap(), vec![report(10)]);
+        assert_eq!(store.get(B256::ZERO).unwrap(), vec![reorged]);
+    }
+}
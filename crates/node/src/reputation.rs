@@ -0,0 +1,372 @@
+//! Peer reputation tuned for the Fraxtal sequencer/RPC topology.
+//!
+//! Upstream reth scores peers with generic weights. Fraxtal sentries mostly gossip transactions,
+//! so [`ReputationConfig`] penalizes bad transactions and invalid blocks harder, and penalizes
+//! peers whose advertised head lags far behind the local head.
+//!
+//! Discovered peers advertising an incompatible fork id are counted too. The network never adds
+//! them to the peer set, so there is no reputation to penalize. Peers rejected in the eth
+//! handshake for an incompatible fork id, genesis or chain id are removed from the peer set and
+//! banned by the network, for [`ReputationConfig::ban_duration`].
+//!
+//! Known Fraxtal infrastructure peers, by default the bootnodes of the chain, are boosted when
+//! they connect and are never left with a negative reputation.
+//!
+//! [`PeerScores`] keeps a snapshot of the connected peers with their reputation and the Fraxtal
+//! penalties they received, served by `admin_peerScores`. The penalties of at most
+//! [`ReputationConfig::max_penalized_peers`] peers are kept, the least recently penalized ones are
+//! forgotten first.
+
+use std::{
+    collections::{HashMap, HashSet},
+    net::SocketAddr,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use futures_util::StreamExt;
+use reth_metrics::{
+    Metrics,
+    metrics::{Counter, Gauge},
+};
+use reth_network::{NetworkHandle, NetworkPrimitives};
+use reth_network_api::{NetworkEvent, Peers, ReputationChangeKind};
+use reth_network_peers::PeerId;
+use reth_network_types::ReputationChangeWeights;
+use reth_tasks::TaskExecutor;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+/// Reputation change of a single penalty unit, the same unit as upstream reth.
+pub const REPUTATION_UNIT: i32 = -1024;
+
+/// How often the reputation of the connected peers is refreshed.
+pub const PEER_SCORES_REFRESH_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Fraxtal reputation weights and penalties.
+#[derive(Debug, Clone)]
+pub struct ReputationConfig {
+    /// Weights of the reputation changes applied by the network itself.
+    pub weights: ReputationChangeWeights,
+    /// How long the network bans peers, notably the ones rejected in the eth handshake for not
+    /// following the Fraxtal chain.
+    pub ban_duration: Duration,
+    /// Penalty of a peer whose head lags more than `stale_head_threshold` blocks behind.
+    pub stale_head_penalty: i32,
+    /// Number of blocks a peer's head may lag behind the local head.
+    pub stale_head_threshold: u64,
+    /// Maximum number of peers whose penalties are kept.
+    pub max_penalized_peers: usize,
+    /// Boost of an infrastructure peer when it connects.
+    pub infra_boost: i32,
+    /// Known Fraxtal infrastructure peers.
+    pub infra_peers: HashSet<PeerId>,
+}
+
+impl Default for ReputationConfig {
+    fn default() -> Self {
+        Self {
+            weights: ReputationChangeWeights {
+                // Invalid blocks and transactions get a peer banned quickly.
+                bad_block: 50 * REPUTATION_UNIT,
+                bad_transactions: 32 * REPUTATION_UNIT,
+                ..Default::default()
+            },
+            // Peers of the other OP Stack chains sharing the discovery bootstraps are found again
+            // and again, keep them out longer than upstream's 12 hours.
+            ban_duration: Duration::from_secs(7 * 24 * 60 * 60),
+            stale_head_penalty: 8 * REPUTATION_UNIT,
+            stale_head_threshold: 1800,
+            max_penalized_peers: 4096,
+            infra_boost: -25 * REPUTATION_UNIT,
+            infra_peers: HashSet::new(),
+        }
+    }
+}
+
+impl ReputationConfig {
+    /// Sets the known Fraxtal infrastructure peers.
+    pub fn with_infra_peers(mut self, peers: impl IntoIterator<Item = PeerId>) -> Self {
+        self.infra_peers = peers.into_iter().collect();
+        self
+    }
+}
+
+/// Fraxtal reputation metrics.
+#[derive(Metrics, Clone, Debug)]
+#[metrics(scope = "fraxtal.network.reputation")]
+struct ReputationMetrics {
+    /// Penalties applied to peers with a stale head.
+    stale_head_penalties: Counter,
+    /// Discovered peers advertising an incompatible fork id.
+    fork_id_mismatches: Counter,
+    /// Boosts applied to infrastructure peers.
+    infra_boosts: Counter,
+    /// Connected peers with a negative reputation.
+    negative_peers: Gauge,
+}
+
+/// Score of a peer, as served by `admin_peerScores`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeerScore {
+    /// The node id of the peer.
+    pub peer_id: PeerId,
+    /// The address of the peer, if connected.
+    pub remote_addr: Option<SocketAddr>,
+    /// The client version of the peer, if connected.
+    pub client_version: Option<String>,
+    /// Whether the peer is a known Fraxtal infrastructure peer.
+    pub infra: bool,
+    /// The reputation of the peer in the peer set, if it is in the peer set.
+    pub reputation: Option<i32>,
+    /// Number of stale head penalties.
+    pub stale_heads: u64,
+    /// Number of times the peer was discovered with an incompatible fork id.
+    pub fork_id_mismatches: u64,
+}
+
+/// Fraxtal penalties received by a peer.
+#[derive(Debug, Clone, Copy, Default)]
+struct Penalties {
+    stale_heads: u64,
+    fork_id_mismatches: u64,
+    /// Order of the last penalty, used to forget the least recently penalized peers.
+    last_penalty: u64,
+}
+
+#[derive(Debug, Default)]
+struct PeerScoresInner {
+    config: ReputationConfig,
+    penalties: HashMap<PeerId, Penalties>,
+    penalty_count: u64,
+    connected: Vec<PeerScore>,
+}
+
+impl PeerScoresInner {
+    /// Returns the penalties of a peer about to be penalized, forgetting the least recently
+    /// penalized peer if too many are tracked.
+    fn penalize(&mut self, peer_id: PeerId) -> &mut Penalties {
+        if !self.penalties.contains_key(&peer_id)
+            && self.penalties.len() >= self.config.max_penalized_peers
+            && let Some(oldest) = self
+                .penalties
+                .iter()
+                .min_by_key(|(_, penalties)| penalties.last_penalty)
+                .map(|(id, _)| *id)
+        {
+            self.penalties.remove(&oldest);
+        }
+        self.penalty_count += 1;
+        let penalties = self.penalties.entry(peer_id).or_default();
+        penalties.last_penalty = self.penalty_count;
+        penalties
+    }
+}
+
+/// Shared table of the peer scores.
+#[derive(Debug, Clone, Default)]
+pub struct PeerScores {
+    inner: Arc<RwLock<PeerScoresInner>>,
+    metrics: Arc<ReputationMetrics>,
+}
+
+impl PeerScores {
+    /// Creates the table, penalizing and boosting peers according to `config`.
+    pub fn new(config: ReputationConfig) -> Self {
+        let scores = Self::default();
+        scores.write().config = config;
+        scores
+    }
+
+    /// Returns the reputation configuration.
+    pub fn config(&self) -> ReputationConfig {
+        self.read().config.clone()
+    }
+
+    /// Returns the scores of the connected peers, followed by the penalized peers that are not
+    /// connected.
+    pub fn snapshot(&self) -> Vec<PeerScore> {
+        let inner = self.read();
+        let mut scores = inner.connected.clone();
+        let connected: HashSet<_> = scores.iter().map(|score| score.peer_id).collect();
+        scores.extend(inner.penalties.iter().filter(|(id, _)| !connected.contains(id)).map(
+            |(&peer_id, penalties)| PeerScore {
+                peer_id,
+                remote_addr: None,
+                client_version: None,
+                infra: inner.config.infra_peers.contains(&peer_id),
+                reputation: None,
+                stale_heads: penalties.stale_heads,
+                fork_id_mismatches: penalties.fork_id_mismatches,
+            },
+        ));
+        scores
+    }
+
+    /// Records a discovered peer advertising an incompatible fork id.
+    pub fn record_fork_id_mismatch(&self, peer_id: PeerId) {
+        self.write().penalize(peer_id).fork_id_mismatches += 1;
+        self.metrics.fork_id_mismatches.increment(1);
+    }
+
+    /// Records the head of a peer, returning the penalty to apply if it lags too far behind
+    /// `local_head`.
+    fn record_head(&self, peer_id: PeerId, peer_head: u64, local_head: u64) -> Option<i32> {
+        let mut inner = self.write();
+        if local_head.saturating_sub(peer_head) <= inner.config.stale_head_threshold {
+            return None;
+        }
+        inner.penalize(peer_id).stale_heads += 1;
+        self.metrics.stale_head_penalties.increment(1);
+        Some(inner.config.stale_head_penalty)
+    }
+
+    fn is_infra(&self, peer_id: &PeerId) -> bool {
+        self.read().config.infra_peers.contains(peer_id)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, PeerScoresInner> {
+        self.inner.read().expect("peer scores lock poisoned")
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, PeerScoresInner> {
+        self.inner.write().expect("peer scores lock poisoned")
+    }
+}
+
+/// Spawns the tasks applying the Fraxtal penalties and boosts, and refreshing the peer scores.
+///
+/// `local_head` returns the number of the local head, used to detect peers with a stale head.
+pub fn spawn_peer_scoring<N: NetworkPrimitives>(
+    executor: &TaskExecutor,
+    network: NetworkHandle<N>,
+    scores: PeerScores,
+    local_head: impl Fn() -> Option<u64> + Send + 'static,
+) {
+    let mut events = network.event_listener();
+    let sessions = network.clone();
+    let session_scores = scores.clone();
+    executor.spawn_critical_task("fraxtal-peer-scoring", async move {
+        while let Some(event) = events.next().await {
+            let NetworkEvent::ActivePeerSession { info, .. } = event else { continue };
+            let peer_id = info.peer_id;
+            if session_scores.is_infra(&peer_id) {
+                let boost = session_scores.read().config.infra_boost;
+                sessions.reputation_change(peer_id, ReputationChangeKind::Other(boost));
+                session_scores.metrics.infra_boosts.increment(1);
+            } else if let (Some(peer_head), Some(local_head)) =
+                (info.status.latest_block, local_head()) &&
+                let Some(penalty) = session_scores.record_head(peer_id, peer_head, local_head)
+            {
+                debug!(target: "fraxtal::network", %peer_id, peer_head, local_head, "Penalized peer with a stale head");
+                sessions.reputation_change(peer_id, ReputationChangeKind::Other(penalty));
+            }
+        }
+    });
+
+    executor.spawn_critical_task("fraxtal-peer-scores", async move {
+        loop {
+            tokio::time::sleep(PEER_SCORES_REFRESH_INTERVAL).await;
+            let peers = match network.get_all_peers().await {
+                Ok(peers) => peers,
+                Err(err) => {
+                    warn!(target: "fraxtal::network", %err, "Failed to list peers for the peer scores");
+                    continue
+                }
+            };
+
+            let mut connected = Vec::with_capacity(peers.len());
+            let mut negative = 0;
+            for peer in peers {
+                let peer_id = peer.remote_id;
+                let mut reputation = network.reputation_by_id(peer_id).await.ok().flatten();
+                let infra = scores.is_infra(&peer_id);
+                // Infrastructure peers are never left with a negative reputation.
+                if infra && reputation.is_some_and(|reputation| reputation < 0) {
+                    network.reputation_change(peer_id, ReputationChangeKind::Reset);
+                    scores.metrics.infra_boosts.increment(1);
+                    reputation = Some(0);
+                }
+                if reputation.is_some_and(|reputation| reputation < 0) {
+                    negative += 1;
+                }
+                let penalties =
+                    scores.read().penalties.get(&peer_id).copied().unwrap_or_default();
+                connected.push(PeerScore {
+                    peer_id,
+                    remote_addr: Some(peer.remote_addr),
+                    client_version: Some(peer.client_version.to_string()),
+                    infra,
+                    reputation,
+                    stale_heads: penalties.stale_heads,
+                    fork_id_mismatches: penalties.fork_id_mismatches,
+                });
+            }
+            scores.metrics.negative_peers.set(negative as f64);
+            scores.write().connected = connected;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bans_peers_longer_than_upstream() {
+        let upstream = reth_network::PeersConfig::default();
+        assert!(ReputationConfig::default().ban_duration > upstream.ban_duration);
+    }
+
+    #[test]
+    fn penalizes_stale_heads_beyond_threshold() {
+        let scores = PeerScores::new(ReputationConfig::default());
+        let peer = PeerId::repeat_byte(1);
+
+        assert_eq!(scores.record_head(peer, 8_200, 10_000), None);
+        assert_eq!(scores.record_head(peer, 100, 10_000), Some(8 * REPUTATION_UNIT));
+
+        let snapshot = scores.snapshot();
+        assert_eq!(snapshot.len(), 1);
+        assert_eq!(snapshot[0].stale_heads, 1);
+        assert_eq!(snapshot[0].reputation, None);
+    }
+
+    #[test]
+    fn tracks_fork_id_mismatches() {
+        let infra = PeerId::repeat_byte(2);
+        let scores = PeerScores::new(ReputationConfig::default().with_infra_peers([infra]));
+        let peer = PeerId::repeat_byte(1);
+
+        scores.record_fork_id_mismatch(peer);
+        scores.record_fork_id_mismatch(peer);
+        scores.record_fork_id_mismatch(infra);
+
+        let mut snapshot = scores.snapshot();
+        snapshot.sort_by_key(|score| score.peer_id);
+        assert_eq!(
+            snapshot
+                .iter()
+                .map(|score| (score.fork_id_mismatches, score.infra))
+                .collect::<Vec<_>>(),
+            [(2, false), (1, true)]
+        );
+    }
+
+    #[test]
+    fn forgets_least_recently_penalized_peers() {
+        let scores =
+            PeerScores::new(ReputationConfig { max_penalized_peers: 2, ..Default::default() });
+        let (a, b, c) = (PeerId::repeat_byte(1), PeerId::repeat_byte(2), PeerId::repeat_byte(3));
+
+        scores.record_fork_id_mismatch(a);
+        scores.record_fork_id_mismatch(b);
+        scores.record_fork_id_mismatch(a);
+        scores.record_fork_id_mismatch(c);
+
+        let mut peers = scores.snapshot().iter().map(|score| score.peer_id).collect::<Vec<_>>();
+        peers.sort();
+        assert_eq!(peers, [a, c]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Fraxtal-specific RPC namespace, the Fraxtal extensions of the `admin_` namespace, and the
+//! dev-mode only `dev_` namespace.
+
+use crate::{
+    dev::{DevPayloadAttributes, DevPayloadAttributesHandle, DevPayloadAttributesPatch},
+    peer_policy::{PeerPolicy, PeerPolicyConfig, PeerPolicyHandle},
+    reports::{MigrationReportStore, backfill_block},
+    reputation::{PeerScore, PeerScores},
+};
+use alloy_primitives::U64;
+use fraxtal_op_evm::block::{MigrationPlan, MigrationReport};
+use jsonrpsee::{
+    core::RpcResult,
+    proc_macros::rpc,
+    types::{
+        ErrorObjectOwned,
+        error::{INTERNAL_ERROR_CODE, INVALID_PARAMS_CODE},
+    },
+};
+use reth_chainspec::EthChainSpec;
+use reth_optimism_forks::OpHardforks;
+use reth_provider::{BlockHashReader, ChainSpecProvider, HeaderProvider, StateProviderFactory};
+use std::sync::Arc;
+
+/// The `fraxtal_` RPC namespace.
+#[rpc(server, namespace = "fraxtal")]
+pub trait FraxtalApi {
+    /// Returns the reports of the fork migrations applied by the canonical block with the given
+    /// number.
+    ///
+    /// Blocks that applied no migration have no reports. The reports of blocks that were not
+    /// recorded as they became canonical are computed from the state of their parent on the first
+    /// request, which fails if that state was pruned.
+    #[method(name = "getMigrationReport")]
+    fn get_migration_report(&self, block_number: U64) -> RpcResult<Vec<MigrationReport>>;
+}
+
+/// Implementation of the `fraxtal_` RPC namespace.
+#[derive(Debug, Clone)]
+pub struct FraxtalRpc<P> {
+    provider: P,
+    migrations: Option<Arc<MigrationPlan>>,
+    reports: MigrationReportStore,
+}
+
+impl<P> FraxtalRpc<P> {
+    /// Creates the `fraxtal_` API serving migration reports from the given store, resolving
+    /// block numbers to canonical blocks with `provider`.
+    ///
+    /// Missing reports are backfilled with `migrations`, overriding the built-in plan of the
+    /// chain as for the executor.
+    pub const fn new(
+        provider: P,
+        migrations: Option<Arc<MigrationPlan>>,
+        reports: MigrationReportStore,
+    ) -> Self {
+        Self { provider, migrations, reports }
+    }
+}
+
+impl<P> FraxtalApiServer for FraxtalRpc<P>
+where
+    P: BlockHashReader
+        + StateProviderFactory
+        + HeaderProvider<Header = alloy_consensus::Header>
+        + ChainSpecProvider<ChainSpec: OpHardforks + EthChainSpec>
+        + Send
+        + Sync
+        + 'static,
+{
+    fn get_migration_report(&self, block_number: U64) -> RpcResult<Vec<MigrationReport>> {
+        let internal_error =
+            |err: String| ErrorObjectOwned::owned(INTERNAL_ERROR_CODE, err, None::<()>);
+        let Some(block_hash) = self
+            .provider
+            .block_hash(block_number.to())
+            .map_err(|err| internal_error(err.to_string()))?
+        else {
+            return Ok(Vec::new());
+        };
+        backfill_block(&self.provider, self.migrations.as_deref(), &self.reports, block_hash)
+            .map_err(|err| internal_error(err.to_string()))
+    }
+}
+
+/// Fraxtal extensions of the `admin_` RPC namespace.
+#[rpc(server, namespace = "admin")]
+pub trait FraxtalAdminApi {
+    /// Returns the allow/deny policy applied to the peers.
+    #[method(name = "peerPolicy")]
+    fn peer_policy(&self) -> RpcResult<PeerPolicyConfig>;
+
+    /// Replaces the allow/deny policy applied to the peers.
+    ///
+    /// Connected peers violating the new policy are disconnected.
+    #[method(name = "setPeerPolicy")]
+    fn set_peer_policy(&self, policy: PeerPolicyConfig) -> RpcResult<bool>;
+
+    /// Returns the reputation of the connected peers and the Fraxtal penalties they received,
+    /// followed by the penalized peers that are not connected.
+    #[method(name = "peerScores")]
+    fn peer_scores(&self) -> RpcResult<Vec<PeerScore>>;
+}
+
+/// Implementation of the Fraxtal extensions of the `admin_` RPC namespace.
+#[derive(Debug, Clone)]
+pub struct FraxtalAdminRpc {
+    peer_policy: PeerPolicyHandle,
+    peer_scores: PeerScores,
+}
+
+impl FraxtalAdminRpc {
+    /// Creates the `admin_` extensions managing the given peer policy and serving the given peer
+    /// scores.
+    pub const fn new(peer_policy: PeerPolicyHandle, peer_scores: PeerScores) -> Self {
+        Self { peer_policy, peer_scores }
+    }
+}
+
+impl FraxtalAdminApiServer for FraxtalAdminRpc {
+    fn peer_policy(&self) -> RpcResult<PeerPolicyConfig> {
+        Ok(self.peer_policy.current().config().clone())
+    }
+
+    fn set_peer_policy(&self, policy: PeerPolicyConfig) -> RpcResult<bool> {
+        let policy = PeerPolicy::new(policy).map_err(|err| {
+            ErrorObjectOwned::owned(INVALID_PARAMS_CODE, format!("{err:#}"), None::<()>)
+        })?;
+        self.peer_policy.set(policy);
+        Ok(true)
+    }
+
+    fn peer_scores(&self) -> RpcResult<Vec<PeerScore>> {
+        Ok(self.peer_scores.snapshot())
+    }
+}
+
+/// The `dev_` RPC namespace, only installed in dev mode.
+#[rpc(server, namespace = "dev")]
+pub trait FraxtalDevApi {
+    /// Returns the payload attributes of the dev blocks.
+    #[method(name = "payloadAttributes")]
+    fn payload_attributes(&self) -> RpcResult<DevPayloadAttributes>;
+
+    /// Changes the payload attributes of the dev blocks, from the next block on, returning the
+    /// updated attributes.
+    ///
+    /// Attributes left out keep their current value.
+    #[method(name = "setPayloadAttributes")]
+    fn set_payload_attributes(
+        &self,
+        patch: DevPayloadAttributesPatch,
+    ) -> RpcResult<DevPayloadAttributes>;
+}
+
+/// Implementation of the `dev_` RPC namespace.
+#[derive(Debug, Clone)]
+pub struct FraxtalDevRpc {
+    attributes: DevPayloadAttributesHandle,
+}
+
+impl FraxtalDevRpc {
+    /// Creates the `dev_` API managing the given dev payload attributes.
+    pub const fn new(attributes: DevPayloadAttributesHandle) -> Self {
+        Self { attributes }
+    }
+}
+
+impl FraxtalDevApiServer for FraxtalDevRpc {
+    fn payload_attributes(&self) -> RpcResult<DevPayloadAttributes> {
+        Ok(self.attributes.current())
+    }
+
+    fn set_payload_attributes(
+        &self,
+        patch: DevPayloadAttributesPatch,
+    ) -> RpcResult<DevPayloadAttributes> {
+        self.attributes.update(&patch).map_err(|err| {
+            ErrorObjectOwned::owned(INVALID_PARAMS_CODE, format!("{err:#}"), None::<()>)
+        })
+    }
+}
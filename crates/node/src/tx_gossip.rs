@@ -0,0 +1,193 @@
+//! Transaction gossip policy.
+//!
+//! Replaces the all-or-nothing `--rollup.disable-tx-pool-gossip` with a policy choosing which
+//! peers pooled transactions are gossiped to, whether they receive the full transactions or only
+//! their hashes, and which transaction types are gossiped. Private orderflow nodes can gossip to
+//! their trusted peers only, so their transactions don't reach the public mesh.
+//!
+//! The peers and mode are applied by the transactions manager. The types are applied by the
+//! transaction pool, see [`crate::pool`]: transactions of the other types are accepted in the pool
+//! and included in blocks, but never gossiped. Deposit transactions never enter the pool and the
+//! OP pool rejects blob transactions, so neither can be gossiped.
+
+use clap::ValueEnum;
+use reth_network::transactions::{
+    TransactionPropagationMode, TransactionsManagerConfig, config::TransactionPropagationKind,
+};
+
+/// Peers pooled transactions are gossiped to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TxGossipPeers {
+    /// Gossip to all peers.
+    #[default]
+    All,
+    /// Gossip to trusted peers only.
+    Trusted,
+    /// Don't gossip transactions.
+    None,
+}
+
+/// What the peers transactions are gossiped to receive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum TxGossipMode {
+    /// Send the full transactions to a subset of the peers and announce their hashes to the
+    /// others.
+    #[default]
+    Full,
+    /// Only announce the hashes, peers have to request the transactions they want.
+    Hashes,
+}
+
+/// Type of pooled transactions, as selected on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum TxGossipType {
+    /// Legacy transactions.
+    Legacy,
+    /// EIP-2930 access list transactions.
+    Eip2930,
+    /// EIP-1559 dynamic fee transactions.
+    Eip1559,
+    /// EIP-7702 set code transactions.
+    Eip7702,
+}
+
+impl TxGossipType {
+    /// Returns the EIP-2718 type id.
+    pub const fn ty(self) -> u8 {
+        match self {
+            Self::Legacy => 0,
+            Self::Eip2930 => 1,
+            Self::Eip1559 => 2,
+            Self::Eip7702 => 4,
+        }
+    }
+}
+
+/// Set of transaction types that are gossiped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxGossipTypes(u8);
+
+impl Default for TxGossipTypes {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl TxGossipTypes {
+    /// All the types a pooled transaction can have.
+    pub const ALL: Self = Self(
+        1 << TxGossipType::Legacy.ty()
+            | 1 << TxGossipType::Eip2930.ty()
+            | 1 << TxGossipType::Eip1559.ty()
+            | 1 << TxGossipType::Eip7702.ty(),
+    );
+
+    /// Creates the set of the given types.
+    pub fn new(types: impl IntoIterator<Item = TxGossipType>) -> Self {
+        Self(types.into_iter().fold(0, |set, ty| set | (1 << ty.ty())))
+    }
+
+    /// Returns `true` if transactions of the EIP-2718 type id `ty` are gossiped.
+    pub const fn contains(&self, ty: u8) -> bool {
+        ty < 8 && self.0 & (1 << ty) != 0
+    }
+}
+
+/// Policy for gossiping pooled transactions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxGossipPolicy {
+    /// Peers transactions are gossiped to.
+    pub peers: TxGossipPeers,
+    /// What the peers receive.
+    pub mode: TxGossipMode,
+    /// Transaction types that are gossiped.
+    pub types: TxGossipTypes,
+}
+
+impl TxGossipPolicy {
+    /// Creates a new policy gossiping all transaction types.
+    pub const fn new(peers: TxGossipPeers, mode: TxGossipMode) -> Self {
+        Self { peers, mode, types: TxGossipTypes::ALL }
+    }
+
+    /// Sets the transaction types that are gossiped.
+    pub const fn with_types(mut self, types: TxGossipTypes) -> Self {
+        self.types = types;
+        self
+    }
+
+    /// Returns a policy that doesn't gossip transactions.
+    pub const fn disabled() -> Self {
+        Self::new(TxGossipPeers::None, TxGossipMode::Full)
+    }
+
+    /// Returns the policy equivalent to `--rollup.disable-tx-pool-gossip`.
+    pub const fn from_disable_txpool_gossip(disable_txpool_gossip: bool) -> Self {
+        if disable_txpool_gossip {
+            Self::disabled()
+        } else {
+            Self::new(TxGossipPeers::All, TxGossipMode::Full)
+        }
+    }
+
+    /// Returns `true` if transactions aren't gossiped at all.
+    pub const fn is_disabled(&self) -> bool {
+        matches!(self.peers, TxGossipPeers::None)
+    }
+
+    /// Returns the propagation policy of the transactions manager.
+    pub const fn propagation_kind(&self) -> TransactionPropagationKind {
+        match self.peers {
+            TxGossipPeers::All => TransactionPropagationKind::All,
+            TxGossipPeers::Trusted => TransactionPropagationKind::Trusted,
+            TxGossipPeers::None => TransactionPropagationKind::None,
+        }
+    }
+
+    /// Applies the policy to the transactions manager `config`.
+    pub const fn transactions_manager_config(
+        &self,
+        mut config: TransactionsManagerConfig,
+    ) -> TransactionsManagerConfig {
+        if matches!(self.mode, TxGossipMode::Hashes) {
+            // No peer receives the full transactions, all of them get the hashes.
+            config.propagation_mode = TransactionPropagationMode::Max(0);
+        }
+        config
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_to_transactions_manager() {
+        let policy = TxGossipPolicy::from_disable_txpool_gossip(false);
+        assert!(!policy.is_disabled());
+        assert_eq!(policy.propagation_kind(), TransactionPropagationKind::All);
+        let config = policy.transactions_manager_config(TransactionsManagerConfig::default());
+        assert_eq!(config.propagation_mode, TransactionPropagationMode::Sqrt);
+
+        let policy = TxGossipPolicy::new(TxGossipPeers::Trusted, TxGossipMode::Hashes);
+        assert_eq!(policy.propagation_kind(), TransactionPropagationKind::Trusted);
+        let config = policy.transactions_manager_config(TransactionsManagerConfig::default());
+        assert_eq!(config.propagation_mode, TransactionPropagationMode::Max(0));
+
+        assert!(TxGossipPolicy::from_disable_txpool_gossip(true).is_disabled());
+    }
+
+    #[test]
+    fn contains_selected_types() {
+        let all = TxGossipTypes::default();
+        assert_eq!(all, TxGossipTypes::new(TxGossipType::value_variants().iter().copied()));
+
+        let types = TxGossipTypes::new([TxGossipType::Eip1559, TxGossipType::Eip7702]);
+        assert!(types.contains(2));
+        assert!(types.contains(4));
+        assert!(!types.contains(0));
+        // Blob and deposit transactions are never gossiped.
+        assert!(!all.contains(3));
+        assert!(!all.contains(0x7e));
+    }
+}
@@ -1,7 +1,6 @@
-use alloy_evm::Database;
-use alloy_op_hardforks::OpHardforks;
 use alloy_primitives::{Address, B256, Bytes, address, b256, hex};
-use revm::{DatabaseCommit, primitives::HashMap, state::Bytecode};
+
+use super::transition::{CodeHashCheck, ForceDeploy, IrregularStateTransition};
 
 /// The address of the create2 deployer
 const CREATE_2_DEPLOYER_ADDR: Address = address!("0x13b0D85CcB8bf860b6b79AF3029fCA081AE9beF2");
@@ -18,35 +17,14 @@ const CREATE_2_DEPLOYER_BYTECODE: [u8; 1584] = hex!(
 /// The Canyon hardfork issues an irregular state transition that force-deploys the create2
 /// deployer contract. This is done by directly setting the code of the create2 deployer account
 /// prior to executing any transactions on the timestamp activation of the fork.
-pub(crate) fn ensure_create2_deployer<DB>(
-    chain_spec: impl OpHardforks,
-    timestamp: u64,
-    db: &mut DB,
-) -> Result<(), DB::Error>
-where
-    DB: Database + DatabaseCommit,
-{
-    // If the canyon hardfork is active at the current timestamp, and it was not active at the
-    // previous block timestamp (heuristically, block time is not perfectly constant at 2s), and the
-    // chain is an optimism chain, then we need to force-deploy the create2 deployer contract.
-    if chain_spec.is_canyon_active_at_timestamp(timestamp)
-        && !chain_spec.is_canyon_active_at_timestamp(timestamp.saturating_sub(2))
-    {
-        // Load the create2 deployer account from the cache.
-        let mut acc_info = db.basic(CREATE_2_DEPLOYER_ADDR)?.unwrap_or_default();
-
-        // Update the account info with the create2 deployer codehash and bytecode.
-        acc_info.code_hash = CREATE_2_DEPLOYER_CODEHASH;
-        acc_info.code = Some(Bytecode::new_raw(Bytes::from_static(&CREATE_2_DEPLOYER_BYTECODE)));
-
-        // Convert the cache account back into a revm account and mark it as touched.
-        let mut revm_acc: revm::state::Account = acc_info.into();
-        revm_acc.mark_touch();
-
-        // Commit the create2 deployer account to the database.
-        db.commit(HashMap::from_iter([(CREATE_2_DEPLOYER_ADDR, revm_acc)]));
-        return Ok(());
-    }
-
-    Ok(())
+pub(super) fn transitions() -> Vec<IrregularStateTransition> {
+    vec![
+        ForceDeploy {
+            address: CREATE_2_DEPLOYER_ADDR,
+            code_hash: CREATE_2_DEPLOYER_CODEHASH,
+            code: Bytes::from_static(&CREATE_2_DEPLOYER_BYTECODE),
+            expected: CodeHashCheck { pre: None, post: Some(CREATE_2_DEPLOYER_CODEHASH) },
+        }
+        .into(),
+    ]
 }
@@ -0,0 +1,238 @@
+//! State diffs produced by irregular state transitions.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use alloy_evm::Database;
+use alloy_primitives::{Address, B256, U256};
+use revm::DatabaseCommit;
+
+use super::transition::{IrregularStateTransition, TransitionError};
+
+/// The change of a single storage slot.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StorageSlotDiff {
+    /// The storage slot.
+    pub slot: B256,
+    /// The slot value before the transitions.
+    pub before: U256,
+    /// The slot value after the transitions.
+    pub after: U256,
+}
+
+/// The change of a single account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountDiff {
+    /// The account address.
+    pub address: Address,
+    /// The code hash before the transitions, `None` if the account did not exist.
+    pub code_hash_before: Option<B256>,
+    /// The code hash after the transitions, `None` if the account does not exist.
+    pub code_hash_after: Option<B256>,
+    /// The written storage slots.
+    pub storage: Vec<StorageSlotDiff>,
+}
+
+impl AccountDiff {
+    /// Returns `true` if the code of the account changed.
+    pub fn code_changed(&self) -> bool {
+        self.code_hash_before != self.code_hash_after
+    }
+}
+
+/// The accounts and storage slots written by a list of transitions, ordered by address.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateDiff {
+    /// The written accounts.
+    pub accounts: Vec<AccountDiff>,
+}
+
+impl StateDiff {
+    /// Returns `true` if no account was written.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+}
+
+/// Applies the transitions in order and returns the resulting diff of every written account and
+/// storage slot.
+pub fn apply_with_diff<'a, DB>(
+    transitions: impl IntoIterator<Item = &'a IrregularStateTransition>,
+    db: &mut DB,
+) -> Result<StateDiff, TransitionError<DB::Error>>
+where
+    DB: Database + DatabaseCommit,
+{
+    let transitions: Vec<_> = transitions.into_iter().collect();
+
+    let mut written: BTreeMap<Address, BTreeSet<B256>> = BTreeMap::new();
+    for transition in &transitions {
+        for address in transition.touched_accounts() {
+            written.entry(address).or_default();
+        }
+        for (address, slot) in transition.written_slots() {
+            written.entry(address).or_default().insert(slot);
+        }
+    }
+
+    let before = read_state(&written, db).map_err(TransitionError::Database)?;
+    for transition in transitions {
+        transition.apply(db)?;
+    }
+    let after = read_state(&written, db).map_err(TransitionError::Database)?;
+
+    let accounts = written
+        .into_iter()
+        .zip(before.into_iter().zip(after))
+        .map(
+            |((address, _), ((code_hash_before, slots_before), (code_hash_after, slots_after)))| {
+                AccountDiff {
+                    address,
+                    code_hash_before,
+                    code_hash_after,
+                    storage: slots_before
+                        .into_iter()
+                        .zip(slots_after)
+                        .map(|((slot, before), (_, after))| StorageSlotDiff { slot, before, after })
+                        .collect(),
+                }
+            },
+        )
+        .collect();
+
+    Ok(StateDiff { accounts })
+}
+
+/// Code hash and slot values of every written account, in the iteration order of `written`.
+type AccountState = (Option<B256>, Vec<(B256, U256)>);
+
+fn read_state<DB: Database>(
+    written: &BTreeMap<Address, BTreeSet<B256>>,
+    db: &mut DB,
+) -> Result<Vec<AccountState>, DB::Error> {
+    written
+        .iter()
+        .map(|(&address, slots)| {
+            let code_hash = db.basic(address)?.map(|info| info.code_hash);
+            let slots = slots
+                .iter()
+                .map(|&slot| Ok((slot, db.storage(address, slot.into())?)))
+                .collect::<Result<_, _>>()?;
+            Ok((code_hash, slots))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::transition::{
+        BytecodePatch, CodeHashCheck, PROXY_ADDR, PROXY_ADMIN_ADDR, PROXY_ADMIN_SLOT,
+        PROXY_IMPLEMENTATION_SLOT, ProxyUpgrade, StorageWrite, implementation_address,
+    };
+    use alloy_primitives::{Bytes, address, b256, bytes, keccak256};
+    use revm::{
+        database::{CacheDB, EmptyDB},
+        state::{AccountInfo, Bytecode},
+    };
+
+    const TOKEN: Address = address!("fc00000000000000000000000000000000000001");
+    const SLOT: B256 = b256!("0000000000000000000000000000000000000000000000000000000000000003");
+
+    fn db_with_code(accounts: &[(Address, Bytes)]) -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        for (address, code) in accounts {
+            db.insert_account_info(
+                *address,
+                AccountInfo::from_bytecode(Bytecode::new_raw(code.clone())),
+            );
+        }
+        db
+    }
+
+    #[test]
+    fn diffs_written_accounts_and_slots() {
+        let mut db = db_with_code(&[(TOKEN, bytes!("00000000")), (PROXY_ADDR, bytes!("60006000"))]);
+        db.insert_account_storage(TOKEN, SLOT.into(), U256::from(1)).unwrap();
+        let transitions = [
+            IrregularStateTransition::from(ProxyUpgrade::fraxtal(TOKEN)),
+            IrregularStateTransition::from(StorageWrite {
+                address: TOKEN,
+                slot: SLOT,
+                value: B256::with_last_byte(2),
+            }),
+        ];
+
+        let diff = apply_with_diff(&transitions, &mut db).unwrap();
+
+        let implementation = implementation_address(TOKEN);
+        assert_eq!(diff.accounts.len(), 2);
+        // Accounts and slots are ordered.
+        assert_eq!(diff.accounts[0].address, TOKEN);
+        assert_eq!(diff.accounts[1].address, implementation);
+
+        let token = &diff.accounts[0];
+        assert_eq!(token.code_hash_before, Some(keccak256(bytes!("00000000"))));
+        assert_eq!(token.code_hash_after, Some(keccak256(bytes!("60006000"))));
+        assert!(token.code_changed());
+        assert_eq!(
+            token.storage,
+            vec![
+                StorageSlotDiff { slot: SLOT, before: U256::from(1), after: U256::from(2) },
+                StorageSlotDiff {
+                    slot: PROXY_IMPLEMENTATION_SLOT,
+                    before: U256::ZERO,
+                    after: U256::from_be_slice(implementation.as_slice()),
+                },
+                StorageSlotDiff {
+                    slot: PROXY_ADMIN_SLOT,
+                    before: U256::ZERO,
+                    after: U256::from_be_slice(PROXY_ADMIN_ADDR.as_slice()),
+                },
+            ]
+        );
+
+        let implementation = &diff.accounts[1];
+        assert_eq!(implementation.code_hash_before, None);
+        assert_eq!(implementation.code_hash_after, Some(keccak256(bytes!("00000000"))));
+        assert!(implementation.storage.is_empty());
+    }
+
+    #[test]
+    fn diffs_unchanged_code() {
+        let mut db = db_with_code(&[(TOKEN, bytes!("00ff"))]);
+        let patch = IrregularStateTransition::from(BytecodePatch {
+            address: TOKEN,
+            offset: 1,
+            value: bytes!("ff"),
+            expected: CodeHashCheck::NONE,
+        });
+
+        let diff = apply_with_diff([&patch], &mut db).unwrap();
+
+        assert_eq!(diff.accounts.len(), 1);
+        assert!(!diff.accounts[0].code_changed());
+        assert!(diff.accounts[0].storage.is_empty());
+    }
+
+    #[test]
+    fn empty_transitions_produce_empty_diff() {
+        let mut db = db_with_code(&[(TOKEN, bytes!("00"))]);
+        assert!(apply_with_diff([], &mut db).unwrap().is_empty());
+    }
+
+    #[test]
+    fn propagates_transition_errors() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let patch = IrregularStateTransition::from(BytecodePatch {
+            address: TOKEN,
+            offset: 0,
+            value: bytes!("ff"),
+            expected: CodeHashCheck::NONE,
+        });
+
+        assert!(apply_with_diff([&patch], &mut db).is_err());
+    }
+}
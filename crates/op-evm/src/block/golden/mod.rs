@@ -0,0 +1,158 @@
+//! Golden state-root regression tests of the fork activation blocks.
+//!
+//! Every fixture in `testdata/fork-transitions` holds a canonical block together with the
+//! execution witness of its pre-state. The block is re-executed offline through
+//! [`FraxtalBlockExecutorFactory`], the resulting changes are applied to the parent state trie,
+//! revealed from the witness by reth's stateless sparse trie, and the post-state root is compared
+//! against the recorded one. Any divergence of the irregular state transitions from what the
+//! network executed shows up as a root mismatch.
+//!
+//! See `testdata/fork-transitions/README.md` for capturing new fixtures.
+
+mod witness;
+
+use std::path::{Path, PathBuf};
+
+use alloy_consensus::{Block, Header, transaction::SignerRecoverable};
+use alloy_evm::{
+    EvmFactory,
+    block::{BlockExecutor, BlockExecutorFactory},
+};
+use alloy_op_evm::{OpBlockExecutionCtx, block::OpAlloyReceiptBuilder, evm_env_for_op_block};
+use alloy_primitives::{B256, Bytes};
+use alloy_rlp::Decodable;
+use alloy_rpc_types_debug::ExecutionWitness;
+use eyre::{OptionExt, WrapErr, bail, ensure};
+use op_alloy_consensus::OpTxEnvelope;
+use reth_chainspec::EthChainSpec;
+use reth_trie_common::{HashedPostState, KeccakKeyHasher};
+use revm::database::{State, states::bundle_state::BundleRetention};
+use serde::Deserialize;
+
+use self::witness::WitnessDatabase;
+use super::{FraxtalBlockExecutionCtx, FraxtalBlockExecutorFactory};
+use crate::FraxtalEvmFactory;
+
+/// Directory holding the fixtures, relative to the crate root.
+const FIXTURES_DIR: &str = "testdata/fork-transitions";
+
+/// A canonical block with the witness of its pre-state.
+#[derive(Debug, Deserialize)]
+struct Fixture {
+    /// Name of the chain, as accepted by `--chain`.
+    chain: String,
+    /// RLP encoded block, as returned by `debug_getRawBlock`.
+    block: Bytes,
+    /// Witness of the pre-state, as returned by `debug_executionWitness`.
+    witness: ExecutionWitness,
+    /// State root after executing the block.
+    expected_state_root: B256,
+}
+
+impl Fixture {
+    fn load(path: &Path) -> eyre::Result<Self> {
+        let contents = std::fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    /// Executes the block on top of the witness and returns the resulting state root.
+    fn post_state_root(&self) -> eyre::Result<B256> {
+        let spec = fraxtal_chainspec::chain_value_parser(&self.chain)?;
+        let block =
+            Block::<OpTxEnvelope>::decode(&mut self.block.as_ref()).wrap_err("invalid block")?;
+        let headers = self
+            .witness
+            .headers
+            .iter()
+            .map(|header| Header::decode(&mut header.as_ref()))
+            .collect::<Result<Vec<_>, _>>()
+            .wrap_err("invalid witness header")?;
+        let parent = headers
+            .iter()
+            .find(|header| header.hash_slow() == block.header.parent_hash)
+            .ok_or_eyre("witness is missing the parent header")?;
+
+        let mut state = State::builder()
+            .with_database(WitnessDatabase::new(parent.state_root, &self.witness, &headers)?)
+            .with_bundle_update()
+            .build();
+
+        let factory = FraxtalBlockExecutorFactory::new(
+            OpAlloyReceiptBuilder::default(),
+            spec.clone(),
+            FraxtalEvmFactory::default(),
+        );
+        let evm_env = evm_env_for_op_block(&block.header, &spec, spec.chain().id());
+        let evm = factory.evm_factory().create_evm(&mut state, evm_env);
+        let ctx = FraxtalBlockExecutionCtx::new(
+            OpBlockExecutionCtx {
+                parent_hash: block.header.parent_hash,
+                parent_beacon_block_root: block.header.parent_beacon_block_root,
+                extra_data: block.header.extra_data.clone(),
+            },
+            Some(parent.timestamp),
+        );
+        let mut executor = factory.create_executor(evm, ctx);
+
+        executor.apply_pre_execution_changes()?;
+        for tx in &block.body.transactions {
+            let tx = tx.clone().try_into_recovered().wrap_err("invalid signature")?;
+            executor.execute_transaction(tx.as_recovered_ref())?;
+        }
+        let (_, result) = executor.finish()?;
+        ensure!(
+            result.gas_used == block.header.gas_used,
+            "gas used mismatch: expected {}, got {}",
+            block.header.gas_used,
+            result.gas_used
+        );
+
+        state.merge_transitions(BundleRetention::PlainState);
+        let bundle = state.take_bundle();
+        let hashed = HashedPostState::from_bundle_state::<KeccakKeyHasher>(bundle.state());
+        let root = state.database.state_root(hashed)?;
+        ensure!(
+            root == block.header.state_root,
+            "state root {root} does not match the block header {}",
+            block.header.state_root
+        );
+        Ok(root)
+    }
+}
+
+/// Returns the paths of all fixtures.
+fn fixtures() -> eyre::Result<Vec<PathBuf>> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURES_DIR);
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(&dir).wrap_err_with(|| format!("reading {}", dir.display()))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+#[test]
+#[ignore = "needs the fork activation fixtures in testdata/fork-transitions"]
+fn fork_transition_state_roots() {
+    let paths = fixtures().unwrap();
+    assert!(!paths.is_empty(), "no fixtures in {FIXTURES_DIR}");
+
+    let mut failures = Vec::new();
+    for path in paths {
+        let name = path.file_stem().unwrap().to_string_lossy().into_owned();
+        let result = Fixture::load(&path).and_then(|fixture| {
+            let root = fixture.post_state_root()?;
+            if root != fixture.expected_state_root {
+                bail!("expected state root {}, got {root}", fixture.expected_state_root);
+            }
+            Ok(())
+        });
+        if let Err(err) = result {
+            failures.push(format!("{name}: {err:#}"));
+        }
+    }
+    assert!(failures.is_empty(), "golden state roots diverged:\n{}", failures.join("\n"));
+}
@@ -0,0 +1,103 @@
+//! [`revm::Database`] serving the pre-state of a block from an execution witness.
+
+use std::collections::HashMap;
+
+use alloy_consensus::Header;
+use alloy_primitives::{Address, B256, U256, map::B256Map};
+use alloy_rpc_types_debug::ExecutionWitness;
+use alloy_trie::KECCAK_EMPTY;
+use reth_stateless::{StatelessSparseTrie, StatelessTrie};
+use reth_trie_common::HashedPostState;
+use revm::{
+    Database,
+    database_interface::DBErrorMarker,
+    state::{AccountInfo, Bytecode},
+};
+
+/// Errors of the [`WitnessDatabase`].
+#[derive(Debug, thiserror::Error)]
+pub(crate) enum WitnessError {
+    /// The witness does not hold the trie nodes of the accessed state.
+    #[error("invalid witness: {0}")]
+    Witness(String),
+    /// The code of an accessed contract is not part of the witness.
+    #[error("witness is missing code {0}")]
+    MissingCode(B256),
+    /// The hash of an accessed block is not part of the witness.
+    #[error("witness is missing the header of block {0}")]
+    MissingHeader(u64),
+}
+
+impl DBErrorMarker for WitnessError {}
+
+/// Pre-state of a block, resolved from an [`ExecutionWitness`].
+#[derive(Debug)]
+pub(crate) struct WitnessDatabase {
+    trie: StatelessSparseTrie,
+    codes: B256Map<Bytecode>,
+    block_hashes: HashMap<u64, B256>,
+}
+
+impl WitnessDatabase {
+    /// Creates the database for the state with the given root, revealing the witness nodes.
+    pub(crate) fn new(
+        state_root: B256,
+        witness: &ExecutionWitness,
+        headers: &[Header],
+    ) -> Result<Self, WitnessError> {
+        let (trie, codes) = StatelessSparseTrie::new(witness, state_root)
+            .map_err(|err| WitnessError::Witness(err.to_string()))?;
+        Ok(Self {
+            trie,
+            codes,
+            block_hashes: headers
+                .iter()
+                .map(|header| (header.number, header.hash_slow()))
+                .collect(),
+        })
+    }
+
+    /// Applies `state` to the pre-state trie and returns the resulting state root.
+    pub(crate) fn state_root(&mut self, state: HashedPostState) -> Result<B256, WitnessError> {
+        self.trie.calculate_state_root(state).map_err(|err| WitnessError::Witness(err.to_string()))
+    }
+}
+
+impl Database for WitnessDatabase {
+    type Error = WitnessError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let Some(account) =
+            self.trie.account(address).map_err(|err| WitnessError::Witness(err.to_string()))?
+        else {
+            return Ok(None);
+        };
+        let code = if account.code_hash == KECCAK_EMPTY {
+            None
+        } else {
+            Some(self.code_by_hash(account.code_hash)?)
+        };
+        Ok(Some(AccountInfo {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code,
+            ..Default::default()
+        }))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == KECCAK_EMPTY {
+            return Ok(Bytecode::default());
+        }
+        self.codes.get(&code_hash).cloned().ok_or(WitnessError::MissingCode(code_hash))
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        self.trie.storage(address, index).map_err(|err| WitnessError::Witness(err.to_string()))
+    }
+
+    fn block_hash(&mut self, number: u64) -> Result<B256, Self::Error> {
+        self.block_hashes.get(&number).copied().ok_or(WitnessError::MissingHeader(number))
+    }
+}
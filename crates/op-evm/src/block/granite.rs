@@ -1,28 +1,16 @@
-use std::collections::HashMap;
+use alloy_primitives::{Address, B256, address, b256};
 
-use alloy_evm::Database;
-use alloy_op_hardforks::OpHardforks;
-use alloy_primitives::{Address, B256, KECCAK256_EMPTY, U256, address, b256};
-use reth_chainspec::EthChainSpec;
-use revm::{
-    DatabaseCommit,
-    state::{Account, Bytecode, EvmStorageSlot},
-};
-use tracing::info;
+use super::transition::{IrregularStateTransition, ProxyUpgrade, StorageWrite};
 
 const FRAX_ADDR: Address = address!("Fc00000000000000000000000000000000000001");
-const FRAX_IMPL_ADDR: Address = address!("fcc0d30000000000000000000000000000000001");
 const SFRAX_ADDR: Address = address!("Fc00000000000000000000000000000000000008");
-const SFRAX_IMPL_ADDR: Address = address!("fcc0d30000000000000000000000000000000008");
-const PROXY_ADDR: Address = address!("fc0000000000000000000000000000000000000a");
-const PROXY_ADMIN_ADDR: Address = address!("fc0000000000000000000000000000000000000a");
 const FRXUSD_L1_ADDR: Address = address!("CAcd6fd266aF91b8AeD52aCCc382b4e165586E29");
 const SFRXUSD_L1_ADDR: Address = address!("cf62F905562626CfcDD2261162a51fd02Fc9c5b6");
 
-const PROXY_ADMIN_SLOT: B256 =
-    b256!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
-const PROXY_IMPLEMENTATION_SLOT: B256 =
-    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+const NAME_STORAGE_SLOT: B256 =
+    b256!("0000000000000000000000000000000000000000000000000000000000000003");
+const SYMBOL_STORAGE_SLOT: B256 =
+    b256!("0000000000000000000000000000000000000000000000000000000000000004");
 const FRXUSD_NAME_STORAGE_VALUE: B256 =
     b256!("4672617820555344000000000000000000000000000000000000000000000010");
 const FRXUSD_SYMBOL_STORAGE_VALUE: B256 =
@@ -42,166 +30,64 @@ const DEVNET_SFRAX_L1_REPLACEMENTS_INDEXES: &[usize] = &[693, 1303];
 
 /// The Granite hardfork issues an irregular state transition that upgrades the frax/sfrax
 /// contracts code to be upgradable proxies.
-pub(super) fn migrate_frxusd<DB>(
-    chain_spec: impl OpHardforks + EthChainSpec,
-    timestamp: u64,
-    db: &mut DB,
-) -> Result<(), DB::Error>
-where
-    DB: Database + DatabaseCommit,
-{
-    if chain_spec.is_granite_active_at_timestamp(timestamp)
-        && !chain_spec.is_granite_active_at_timestamp(timestamp.saturating_sub(2))
-    {
-        info!(target: "evm", "Forcing frxusd upgrade on Granite transition");
+pub(super) fn mainnet_transitions() -> Vec<IrregularStateTransition> {
+    [
+        frxusd_transitions(MAINNET_FRAX_L1_REPLACEMENTS_INDEXES),
+        sfrxusd_transitions(MAINNET_SFRAX_L1_REPLACEMENTS_INDEXES),
+    ]
+    .concat()
+}
 
-        match chain_spec.chain().id() {
-            2521 => {
-                migrate(
-                    FRAX_ADDR,
-                    FRAX_IMPL_ADDR,
-                    PROXY_ADDR,
-                    FRXUSD_L1_ADDR,
-                    DEVNET_FRAX_L1_REPLACEMENTS_INDEXES,
-                    PROXY_ADMIN_ADDR,
-                    FRXUSD_NAME_STORAGE_VALUE,
-                    FRXUSD_SYMBOL_STORAGE_VALUE,
-                    db,
-                )?;
-                migrate(
-                    SFRAX_ADDR,
-                    SFRAX_IMPL_ADDR,
-                    PROXY_ADDR,
-                    SFRXUSD_L1_ADDR,
-                    DEVNET_SFRAX_L1_REPLACEMENTS_INDEXES,
-                    PROXY_ADMIN_ADDR,
-                    SFRXUSD_NAME_STORAGE_VALUE,
-                    SFRXUSD_SYMBOL_STORAGE_VALUE,
-                    db,
-                )?;
-            }
-            2522 => {
-                migrate(
-                    FRAX_ADDR,
-                    FRAX_IMPL_ADDR,
-                    PROXY_ADDR,
-                    FRXUSD_L1_ADDR,
-                    TESTNET_FRAX_L1_REPLACEMENTS_INDEXES,
-                    PROXY_ADMIN_ADDR,
-                    FRXUSD_NAME_STORAGE_VALUE,
-                    FRXUSD_SYMBOL_STORAGE_VALUE,
-                    db,
-                )?;
-            }
-            _ => {
-                migrate(
-                    FRAX_ADDR,
-                    FRAX_IMPL_ADDR,
-                    PROXY_ADDR,
-                    FRXUSD_L1_ADDR,
-                    MAINNET_FRAX_L1_REPLACEMENTS_INDEXES,
-                    PROXY_ADMIN_ADDR,
-                    FRXUSD_NAME_STORAGE_VALUE,
-                    FRXUSD_SYMBOL_STORAGE_VALUE,
-                    db,
-                )?;
-                migrate(
-                    SFRAX_ADDR,
-                    SFRAX_IMPL_ADDR,
-                    PROXY_ADDR,
-                    SFRXUSD_L1_ADDR,
-                    MAINNET_SFRAX_L1_REPLACEMENTS_INDEXES,
-                    PROXY_ADMIN_ADDR,
-                    SFRXUSD_NAME_STORAGE_VALUE,
-                    SFRXUSD_SYMBOL_STORAGE_VALUE,
-                    db,
-                )?;
-            }
-        }
+/// Granite migration of the Fraxtal testnet, which only upgrades frax to frxUSD.
+pub(super) fn testnet_transitions() -> Vec<IrregularStateTransition> {
+    frxusd_transitions(TESTNET_FRAX_L1_REPLACEMENTS_INDEXES)
+}
 
-        return Ok(());
-    }
+/// Granite migration of the Fraxtal devnet.
+pub(super) fn devnet_transitions() -> Vec<IrregularStateTransition> {
+    [
+        frxusd_transitions(DEVNET_FRAX_L1_REPLACEMENTS_INDEXES),
+        sfrxusd_transitions(DEVNET_SFRAX_L1_REPLACEMENTS_INDEXES),
+    ]
+    .concat()
+}
 
-    Ok(())
+/// Migrates frax to frxUSD, pointing the implementation at the L1 frxUSD token.
+fn frxusd_transitions(l1_bytecode_replacements: &[usize]) -> Vec<IrregularStateTransition> {
+    token_transitions(
+        FRAX_ADDR,
+        FRXUSD_L1_ADDR,
+        l1_bytecode_replacements,
+        FRXUSD_NAME_STORAGE_VALUE,
+        FRXUSD_SYMBOL_STORAGE_VALUE,
+    )
 }
 
-#[allow(clippy::too_many_arguments)]
-fn migrate<DB>(
+/// Migrates sfrax to sfrxUSD, pointing the implementation at the L1 sfrxUSD token.
+fn sfrxusd_transitions(l1_bytecode_replacements: &[usize]) -> Vec<IrregularStateTransition> {
+    token_transitions(
+        SFRAX_ADDR,
+        SFRXUSD_L1_ADDR,
+        l1_bytecode_replacements,
+        SFRXUSD_NAME_STORAGE_VALUE,
+        SFRXUSD_SYMBOL_STORAGE_VALUE,
+    )
+}
+
+fn token_transitions(
     contract_addr: Address,
-    implementation_addr: Address,
-    proxy_source_addr: Address,
     l1_token: Address,
-    l1_bytecode_replacmenets: &[usize],
-    owner: Address,
+    l1_bytecode_replacements: &[usize],
     name_storage: B256,
     symbol_storage: B256,
-    db: &mut DB,
-) -> Result<(), DB::Error>
-where
-    DB: Database + DatabaseCommit,
-{
-    info!(target: "evm", "Setting implementation from {} to {}", contract_addr, implementation_addr);
-    let l1_token_bytes = l1_token.as_slice();
-    let mut current_contract_acc = db.basic(contract_addr)?.unwrap_or_default();
-
-    let mut new_implementation_code = current_contract_acc
-        .code
-        .clone()
-        .unwrap_or_else(|| db.code_by_hash(current_contract_acc.code_hash).unwrap_or_default())
-        .original_byte_slice()
-        .to_owned();
-
-    for i in l1_bytecode_replacmenets {
-        new_implementation_code[*i..*i + l1_token_bytes.len()].copy_from_slice(l1_token_bytes);
-    }
-
-    let mut implementation_acc = db.basic(implementation_addr)?.unwrap_or_default();
-    implementation_acc.code = Some(Bytecode::new_raw(new_implementation_code.into()));
-    implementation_acc.code_hash =
-        implementation_acc.code.as_ref().map_or(KECCAK256_EMPTY, |c| c.hash_slow());
-    let mut implementation_revm_account: Account = implementation_acc.into();
-    implementation_revm_account.mark_touch();
-
-    info!(target: "evm", "Setting proxy from {} to {}", proxy_source_addr, contract_addr);
-    let proxy_acc = db.basic(proxy_source_addr)?.unwrap_or_default();
-    current_contract_acc.code = proxy_acc.code.clone();
-    current_contract_acc.code_hash = proxy_acc.code_hash;
-
-    let mut current_contract_revm_account: Account = current_contract_acc.into();
-    current_contract_revm_account.mark_touch();
-    info!(target: "evm", "Setting proxy {} admin to {}", proxy_source_addr, owner);
-    current_contract_revm_account.storage.insert(
-        U256::from_be_bytes(PROXY_ADMIN_SLOT.into()),
-        EvmStorageSlot::new_changed(
-            U256::default(),
-            U256::from_be_bytes(B256::left_padding_from(owner.as_slice()).into()),
-            0,
-        ),
-    );
-    info!(target: "evm", "Setting proxy {} implementation to {}", proxy_source_addr, implementation_addr);
-    current_contract_revm_account.storage.insert(
-        U256::from_be_bytes(PROXY_IMPLEMENTATION_SLOT.into()),
-        EvmStorageSlot::new_changed(
-            U256::default(),
-            U256::from_be_bytes(B256::left_padding_from(implementation_addr.as_slice()).into()),
-            0,
-        ),
-    );
-
-    info!(target: "evm", "Setting proxy {} name and symbol", proxy_source_addr);
-    current_contract_revm_account.storage.insert(
-        U256::from(3),
-        EvmStorageSlot::new_changed(U256::default(), U256::from_be_bytes(name_storage.into()), 0),
-    );
-    current_contract_revm_account.storage.insert(
-        U256::from(4),
-        EvmStorageSlot::new_changed(U256::default(), U256::from_be_bytes(symbol_storage.into()), 0),
-    );
-
-    db.commit(HashMap::from_iter([
-        (implementation_addr, implementation_revm_account),
-        (contract_addr, current_contract_revm_account),
-    ]));
-
-    Ok(())
+) -> Vec<IrregularStateTransition> {
+    vec![
+        ProxyUpgrade::fraxtal(contract_addr)
+            .with_implementation_patches(l1_bytecode_replacements, l1_token.to_vec())
+            .into(),
+        StorageWrite { address: contract_addr, slot: NAME_STORAGE_SLOT, value: name_storage }
+            .into(),
+        StorageWrite { address: contract_addr, slot: SYMBOL_STORAGE_SLOT, value: symbol_storage }
+            .into(),
+    ]
 }
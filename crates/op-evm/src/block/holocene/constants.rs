@@ -1,4 +1,6 @@
-use alloy_primitives::{Address, B256, Bytes, address, b256, bytes};
+use alloy_primitives::{Address, address, b256, bytes};
+
+use crate::block::transition::{BytecodePatch, CodeHashCheck, StorageWrite};
 
 pub(super) const MAINNET_TOKENS_ADDRESSES: &[Address] = &[
     address!("fc00000000000000000000000000000000000002"), // FXS
@@ -30,542 +32,538 @@ pub(super) const DEVNET_TOKENS_ADDRESSES: &[Address] = &[
     address!("fc00000000000000000000000000000000000007"), // frxBTC
 ];
 
-pub(super) const PROXY_ADDR: Address = address!("fc0000000000000000000000000000000000000a");
-pub(super) const PROXY_ADMIN_ADDR: Address = address!("fc0000000000000000000000000000000000000a");
-pub(super) const PROXY_ADMIN_SLOT: B256 =
-    b256!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
-pub(super) const PROXY_IMPLEMENTATION_SLOT: B256 =
-    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
-
-pub(super) struct ByteCodeChange {
-    pub address: Address,
-    pub offset: usize,
-    pub value: Bytes,
-}
-
-pub(super) const MAINNET_NAMING_BYTECODE_CHANGES: [ByteCodeChange; 15] = [
-    ByteCodeChange {
+pub(super) const MAINNET_NAMING_BYTECODE_CHANGES: [BytecodePatch; 15] = [
+    BytecodePatch {
         address: address!("63Eb7846642630456707C3efBb50A03c79B89D81"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000d6372765553442f66727855534400000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("385540FdA649A114Ffeb943fD73ae82cE7908Da3"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000b6672785553442f73444149000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("1e199DbE1f7aa237282fE941d5Bcc3b167c8Ce48"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000b6672785553442f55534465000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("8b4E5263E8D6cc0bbF31EDF14491fc6077B88229"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000c6672785553442f73555344650000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("eE454138083b9B9714cac3c7cF12560248d76D6B"),
         offset: 23761,
         value: bytes!(
             "0x00000000000000000000000000000000000000000000000000000000000000116672785553444658423230323931323331000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("4Cfc391d75c43Cf1Bdb368e8bF680AEd1228df39"),
         offset: 23761,
         value: bytes!(
             "0x00000000000000000000000000000000000000000000000000000000000000116672785553444658423230353531323331000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("63d64A76C2D616676CBAC3068D3C6548F8485314"),
         offset: 23761,
         value: bytes!(
             "0x00000000000000000000000000000000000000000000000000000000000000116672785553444658423230323531323331000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("9CA648D2f51098941688Db9a0beb1DadC2D1B357"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000b6672785553442f64555344000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("bC3705b2bfD42d38e8FA2c8EFDC3Fdda645C3b2a"),
         offset: 23761,
         value: bytes!(
             "0x00000000000000000000000000000000000000000000000000000000000000116672785553444658423230323631323331000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("cAEF324bea3Ff5c7a08710081294F3344fFAdC54"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000b6672785553442f55534454000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("a8eB7B41fe8dF7Ea3de982397AD183721784D987"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000b6672785553442f55534443000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("86d7FA03a70c428D3E28092F4509a16f16eC5b7C"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000a6672785553442f44414900000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("35c6c59277f8fA9Ae98dea29b0546D79957ACDd8"),
         offset: 23761,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000c6672785553442f50595553440000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("Da73dC70D5ca3F51b0000C308abcd358b5F3FEFe"),
         offset: 18067,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000b564348462f667278555344000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
-    ByteCodeChange {
+    BytecodePatch {
         address: address!("77146B0a1d08B6844376dF6d9da99bA7F1b19e71"),
         offset: 18067,
         value: bytes!(
             "0x000000000000000000000000000000000000000000000000000000000000000b564555522f667278555344000000000000000000000000000000000000000000"
         ),
+        expected: CodeHashCheck::NONE,
     },
 ];
 
-pub(super) struct StorageChange {
-    pub address: Address,
-    pub storage_slot: B256,
-    pub value: B256,
-}
-
-pub(super) const MAINNET_NAMING_STORAGE_CHANGES: [StorageChange; 82] = [
-    StorageChange {
+pub(super) const MAINNET_NAMING_STORAGE_CHANGES: [StorageWrite; 82] = [
+    StorageWrite {
         address: address!("98a6C35546be6F7c545c99EfB48F4673eD2c99fc"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000023"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("98a6C35546be6F7c545c99EfB48F4673eD2c99fc"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e666920637276555344667278555344204761756765204465706f"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("98a6C35546be6F7c545c99EfB48F4673eD2c99fc"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("7369740000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("cdBfCBf18D504F5BD6b29Bac5d91405F9f59980F"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000021"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("cdBfCBf18D504F5BD6b29Bac5d91405F9f59980F"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e66692066727855534473444149204761756765204465706f7369"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("cdBfCBf18D504F5BD6b29Bac5d91405F9f59980F"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("7400000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("86A79cEE2734E5357FF6B5495c4a30915921223a"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000021"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("86A79cEE2734E5357FF6B5495c4a30915921223a"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e66692066727855534455534465204761756765204465706f7369"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("86A79cEE2734E5357FF6B5495c4a30915921223a"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("7400000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("59005665c5060D3abEE291A21DE0375cf1BBed03"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000022"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("59005665c5060D3abEE291A21DE0375cf1BBed03"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e6669206672785553447355534465204761756765204465706f73"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("59005665c5060D3abEE291A21DE0375cf1BBed03"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("6974000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("aa1bDFfAb337c11c34AfB56eee1Da95cc3D09A65"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000020"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("aa1bDFfAb337c11c34AfB56eee1Da95cc3D09A65"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e666920465842323631323331204761756765204465706f736974"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("7bA1e40dAe22847E6c521bA297d40fF1c5fD97dd"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000020"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("7bA1e40dAe22847E6c521bA297d40fF1c5fD97dd"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e666920465842353531323331204761756765204465706f736974"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("aA45cD0b2d22df00832Ae70D9DB8EBadAB176a60"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000020"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("aA45cD0b2d22df00832Ae70D9DB8EBadAB176a60"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e666920465842323531323331204761756765204465706f736974"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("48100238C4a3CF44cFbF6C7693eD5C216881fAcE"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000020"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("48100238C4a3CF44cFbF6C7693eD5C216881fAcE"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e666920465842323931323331204761756765204465706f736974"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("E049AA6fbd64210bDEddC15F5a861084E6eE8b57"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000021"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("E049AA6fbd64210bDEddC15F5a861084E6eE8b57"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e66692066727855534455534454204761756765204465706f7369"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("E049AA6fbd64210bDEddC15F5a861084E6eE8b57"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("7400000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("AA6dDbA00DE48D41E41543eF0a78AB31aae4dE8d"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000021"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("AA6dDbA00DE48D41E41543eF0a78AB31aae4dE8d"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e66692066727855534455534443204761756765204465706f7369"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("AA6dDbA00DE48D41E41543eF0a78AB31aae4dE8d"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("7400000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("64100c172D5DC8007E3ee701ECD99468619B6583"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000020"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("64100c172D5DC8007E3ee701ECD99468619B6583"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e666920667278555344444149204761756765204465706f736974"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("72Bd89F2E3470fA5d188cF04eda17E8145eb4BF1"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000003"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000022"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("72Bd89F2E3470fA5d188cF04eda17E8145eb4BF1"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000004"),
         value: b256!("43757276652e6669206672785553445059555344204761756765204465706f73"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("72Bd89F2E3470fA5d188cF04eda17E8145eb4BF1"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("6974000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("05600c37D54a4F3cDc76E0867aF1530BeCC332ca"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000021"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("05600c37D54a4F3cDc76E0867aF1530BeCC332ca"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000006"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000006"),
         value: b256!("43757276652e66692066727855534464555344204761756765204465706f7369"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("05600c37D54a4F3cDc76E0867aF1530BeCC332ca"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000007"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000007"),
         value: b256!("7400000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("C87e5bCf1aF32E5231d708c27271EA2474464a61"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000021"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("C87e5bCf1aF32E5231d708c27271EA2474464a61"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000006"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000006"),
         value: b256!("43757276652e66692056434846667278555344204761756765204465706f7369"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("C87e5bCf1aF32E5231d708c27271EA2474464a61"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000007"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000007"),
         value: b256!("7400000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("D3bAA7b2dcA8F6b272416312CC5f413F9bd0e624"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000005"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000021"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("D3bAA7b2dcA8F6b272416312CC5f413F9bd0e624"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000006"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000006"),
         value: b256!("43757276652e66692056455552667278555344204761756765204465706f7369"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("D3bAA7b2dcA8F6b272416312CC5f413F9bd0e624"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000007"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000007"),
         value: b256!("7400000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("032578d99b1070682a5E171012BE1756a50a17d4"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
         value: b256!("00000000000000000000000000000000000000000000000000000000000000d7"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("032578d99b1070682a5E171012BE1756a50a17d4"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
         value: b256!("467261786c656e6420496e7465726573742042656172696e6720667278555344"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("032578d99b1070682a5E171012BE1756a50a17d4"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
         value: b256!("20284658425f345f44454333313230323629202d203100000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("032578d99b1070682a5E171012BE1756a50a17d4"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("032578d99b1070682a5E171012BE1756a50a17d4"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("032578d99b1070682a5E171012BE1756a50a17d4"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
         value: b256!("66667278555344284658425f3230323631323331292d3100000000000000002e"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("1b48c9595385F1780d7Be1aB57f8eAcFeA3A5cE5"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
         value: b256!("00000000000000000000000000000000000000000000000000000000000000cb"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("1b48c9595385F1780d7Be1aB57f8eAcFeA3A5cE5"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
         value: b256!("467261786c656e6420496e7465726573742042656172696e6720667278555344"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("1b48c9595385F1780d7Be1aB57f8eAcFeA3A5cE5"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
         value: b256!("2028465842323032353132333129202d20313200000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("1b48c9595385F1780d7Be1aB57f8eAcFeA3A5cE5"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("1b48c9595385F1780d7Be1aB57f8eAcFeA3A5cE5"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("1b48c9595385F1780d7Be1aB57f8eAcFeA3A5cE5"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
         value: b256!("66667278555344284658423230323531323331292d313200000000000000002e"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("3e92765eE2B009b104A8A7baf3759B159c19AbA1"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
         value: b256!("00000000000000000000000000000000000000000000000000000000000000cb"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("3e92765eE2B009b104A8A7baf3759B159c19AbA1"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
         value: b256!("467261786c656e6420496e7465726573742042656172696e6720667278555344"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("3e92765eE2B009b104A8A7baf3759B159c19AbA1"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
         value: b256!("2028465842323032393132333129202d20313100000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("3e92765eE2B009b104A8A7baf3759B159c19AbA1"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("3e92765eE2B009b104A8A7baf3759B159c19AbA1"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("3e92765eE2B009b104A8A7baf3759B159c19AbA1"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
         value: b256!("66667278555344284658423230323931323331292d313100000000000000002e"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("4F968317721B9c300afBff3FD37365637318271D"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
         value: b256!("00000000000000000000000000000000000000000000000000000000000000df"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("4F968317721B9c300afBff3FD37365637318271D"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
         value: b256!("467261786c656e6420496e7465726573742042656172696e6720667278555344"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("4F968317721B9c300afBff3FD37365637318271D"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
         value: b256!("20285374616b6564204672617820457468657229202d20380000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("4F968317721B9c300afBff3FD37365637318271D"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("4F968317721B9c300afBff3FD37365637318271D"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("4F968317721B9c300afBff3FD37365637318271D"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
         value: b256!("666672785553442873667278455448292d380000000000000000000000000024"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("8EdA613EC96992D3C42BCd9aC2Ae58a92929Ceb2"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
         value: b256!("00000000000000000000000000000000000000000000000000000000000000c3"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("8EdA613EC96992D3C42BCd9aC2Ae58a92929Ceb2"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
         value: b256!("467261786c656e6420496e7465726573742042656172696e6720667278555344"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("8EdA613EC96992D3C42BCd9aC2Ae58a92929Ceb2"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
         value: b256!("20284672617820536861726529202d2039000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("8EdA613EC96992D3C42BCd9aC2Ae58a92929Ceb2"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("8EdA613EC96992D3C42BCd9aC2Ae58a92929Ceb2"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("8EdA613EC96992D3C42BCd9aC2Ae58a92929Ceb2"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
         value: b256!("6666727855534428465853292d3900000000000000000000000000000000001c"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("b2d53dF70181FbE783f84B74F58E38cC1Ca8528d"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
         value: b256!("00000000000000000000000000000000000000000000000000000000000000df"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("b2d53dF70181FbE783f84B74F58E38cC1Ca8528d"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
         value: b256!("467261786c656e6420496e7465726573742042656172696e6720667278555344"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("b2d53dF70181FbE783f84B74F58E38cC1Ca8528d"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
         value: b256!("20284672617820507269636520496e64657829202d2031360000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("b2d53dF70181FbE783f84B74F58E38cC1Ca8528d"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("b2d53dF70181FbE783f84B74F58E38cC1Ca8528d"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("b2d53dF70181FbE783f84B74F58E38cC1Ca8528d"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
         value: b256!("6666727855534428465049292d3136000000000000000000000000000000001e"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("B71E4829e81f72f7F36A0d858e58109f5948A713"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000015"),
         value: b256!("00000000000000000000000000000000000000000000000000000000000000db"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("B71E4829e81f72f7F36A0d858e58109f5948A713"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec475"),
         value: b256!("467261786c656e6420496e7465726573742042656172696e6720736672784554"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("B71E4829e81f72f7F36A0d858e58109f5948A713"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec476"),
         value: b256!("4820285374616b656420467261782055534429202d2031300000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("B71E4829e81f72f7F36A0d858e58109f5948A713"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec477"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("B71E4829e81f72f7F36A0d858e58109f5948A713"),
-        storage_slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
+        slot: b256!("55f448fdea98c4d29eb340757ef0a66cd03dbb9538908a6a81d96026b71ec478"),
         value: b256!("0000000000000000000000000000000000000000000000000000000000000000"),
     },
-    StorageChange {
+    StorageWrite {
         address: address!("B71E4829e81f72f7F36A0d858e58109f5948A713"),
-        storage_slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
+        slot: b256!("0000000000000000000000000000000000000000000000000000000000000016"),
         value: b256!("66736672784554482873667278555344292d3130000000000000000000000028"),
     },
 ];
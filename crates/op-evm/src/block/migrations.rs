@@ -0,0 +1,228 @@
+//! Registry of the irregular state transitions scheduled at fork boundaries.
+
+use std::sync::LazyLock;
+
+use alloy_op_hardforks::{OpHardfork, OpHardforks};
+use alloy_primitives::Address;
+
+use super::{
+    canyon, granite, holocene, isthmus,
+    transition::{CodeHashCheck, IrregularStateTransition},
+};
+
+/// Chain id of Fraxtal mainnet.
+pub const FRAXTAL_MAINNET_CHAIN_ID: u64 = 252;
+/// Chain id of the Fraxtal testnet.
+pub const FRAXTAL_TESTNET_CHAIN_ID: u64 = 2522;
+/// Chain id of the Fraxtal Hoodi testnet, which replays the mainnet Granite and Holocene
+/// migrations.
+pub const FRAXTAL_HOODI_TESTNET_CHAIN_ID: u64 = 2523;
+/// Chain id of the Fraxtal devnet.
+pub const FRAXTAL_DEVNET_CHAIN_ID: u64 = 2521;
+
+/// The irregular state transitions applied on the activation block of a single fork.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct ForkMigration {
+    /// The fork whose activation triggers the migration.
+    #[cfg_attr(feature = "serde", serde(with = "serde_fork"))]
+    pub fork: OpHardfork,
+    /// The transitions, applied in order.
+    pub transitions: Vec<IrregularStateTransition>,
+}
+
+/// The fork migrations of a chain, ordered by fork activation.
+///
+/// Chains other than the Fraxtal networks have no built-in migrations; their plan can be supplied
+/// from a file instead, e.g. in TOML:
+///
+/// ```toml
+/// [[forks]]
+/// fork = "granite"
+///
+/// [[forks.transitions]]
+/// type = "proxy_upgrade"
+/// address = "0xfc00000000000000000000000000000000000001"
+/// implementation_patches = [{ offset = 693, value = "0xcacd6fd266af91b8aed52accc382b4e165586e29" }]
+///
+/// [[forks.transitions]]
+/// type = "storage_write"
+/// address = "0xfc00000000000000000000000000000000000001"
+/// slot = "0x0000000000000000000000000000000000000000000000000000000000000003"
+/// value = "0x4672617820555344000000000000000000000000000000000000000000000010"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct MigrationPlan {
+    #[cfg_attr(feature = "serde", serde(default))]
+    forks: Vec<ForkMigration>,
+}
+
+impl MigrationPlan {
+    /// Creates an empty plan.
+    pub const fn new() -> Self {
+        Self { forks: Vec::new() }
+    }
+
+    /// Schedules `transitions` on the activation of `fork`.
+    pub fn with_fork(
+        mut self,
+        fork: OpHardfork,
+        transitions: impl IntoIterator<Item = IrregularStateTransition>,
+    ) -> Self {
+        self.forks.push(ForkMigration { fork, transitions: transitions.into_iter().collect() });
+        self
+    }
+
+    /// Returns the scheduled fork migrations.
+    pub fn forks(&self) -> &[ForkMigration] {
+        &self.forks
+    }
+
+    /// Returns an iterator over the scheduled fork migrations.
+    pub fn iter(&self) -> core::slice::Iter<'_, ForkMigration> {
+        self.forks.iter()
+    }
+
+    /// Sets the code hashes checked by the transitions of the plan, from a table of the fork, the
+    /// address of the transition and the check.
+    ///
+    /// Transitions without an entry keep their checks.
+    pub fn with_code_hashes(
+        mut self,
+        code_hashes: &[(OpHardfork, Address, CodeHashCheck)],
+    ) -> Self {
+        for migration in &mut self.forks {
+            for transition in &mut migration.transitions {
+                let address = transition.address();
+                let check = code_hashes
+                    .iter()
+                    .find(|(fork, addr, _)| *fork == migration.fork && *addr == address);
+                if let (Some((_, _, check)), Some(expected)) = (check, transition.expected_mut()) {
+                    *expected = *check;
+                }
+            }
+        }
+        self
+    }
+
+    /// Returns the transitions scheduled on the activation of `fork`.
+    pub fn transitions(&self, fork: OpHardfork) -> impl Iterator<Item = &IrregularStateTransition> {
+        self.forks.iter().filter(move |m| m.fork == fork).flat_map(|m| &m.transitions)
+    }
+
+    /// Returns the fork migrations activated by a block with the given timestamp whose parent
+    /// has `parent_timestamp`.
+    pub fn activated<'a>(
+        &'a self,
+        spec: &'a impl OpHardforks,
+        timestamp: u64,
+        parent_timestamp: u64,
+    ) -> impl Iterator<Item = &'a ForkMigration> {
+        self.forks.iter().filter(move |m| {
+            spec.op_fork_activation(m.fork).transitions_at_timestamp(timestamp, parent_timestamp)
+        })
+    }
+
+    /// Returns `true` if the forks of the plan are unique and ordered by activation.
+    pub fn is_ordered(&self) -> bool {
+        self.forks.windows(2).all(|pair| pair[0].fork < pair[1].fork)
+    }
+
+    /// Returns `true` if no transitions are scheduled.
+    pub fn is_empty(&self) -> bool {
+        self.forks.iter().all(|m| m.transitions.is_empty())
+    }
+
+    /// Returns the OP Stack protocol transitions, which apply to every chain.
+    pub fn op_stack() -> &'static Self {
+        &OP_STACK_MIGRATIONS
+    }
+
+    /// Returns the built-in Fraxtal migrations for the given chain id.
+    ///
+    /// Unknown chains have no built-in migrations.
+    pub fn builtin(chain_id: u64) -> &'static Self {
+        static EMPTY: MigrationPlan = MigrationPlan::new();
+
+        match chain_id {
+            FRAXTAL_MAINNET_CHAIN_ID => &MAINNET_MIGRATIONS,
+            FRAXTAL_HOODI_TESTNET_CHAIN_ID => &HOODI_TESTNET_MIGRATIONS,
+            FRAXTAL_TESTNET_CHAIN_ID => &TESTNET_MIGRATIONS,
+            FRAXTAL_DEVNET_CHAIN_ID => &DEVNET_MIGRATIONS,
+            _ => &EMPTY,
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a MigrationPlan {
+    type Item = &'a ForkMigration;
+    type IntoIter = core::slice::Iter<'a, ForkMigration>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+static OP_STACK_MIGRATIONS: LazyLock<MigrationPlan> =
+    LazyLock::new(|| MigrationPlan::new().with_fork(OpHardfork::Canyon, canyon::transitions()));
+
+/// Code hashes around the code rewriting transitions of the Fraxtal mainnet migrations, by fork
+/// and address of the rewritten contract.
+///
+/// For a proxy upgrade, `pre` is the code hash of the upgraded contract before the migration and
+/// `post` the one of its implementation after it. For a bytecode patch, both are code hashes of
+/// the patched contract. They are read from the `fraxtal-op-reth migrations preview` output of the
+/// fork activation blocks on a synced mainnet node, and have not been captured yet.
+const MAINNET_CODE_HASHES: &[(OpHardfork, Address, CodeHashCheck)] = &[];
+
+static MAINNET_MIGRATIONS: LazyLock<MigrationPlan> = LazyLock::new(|| {
+    MigrationPlan::new()
+        .with_fork(OpHardfork::Granite, granite::mainnet_transitions())
+        .with_fork(OpHardfork::Holocene, holocene::mainnet_transitions())
+        .with_fork(OpHardfork::Isthmus, isthmus::mainnet_transitions())
+        .with_code_hashes(MAINNET_CODE_HASHES)
+});
+
+static HOODI_TESTNET_MIGRATIONS: LazyLock<MigrationPlan> = LazyLock::new(|| {
+    MigrationPlan::new()
+        .with_fork(OpHardfork::Granite, granite::mainnet_transitions())
+        .with_fork(OpHardfork::Holocene, holocene::mainnet_transitions())
+});
+
+static TESTNET_MIGRATIONS: LazyLock<MigrationPlan> = LazyLock::new(|| {
+    MigrationPlan::new()
+        .with_fork(OpHardfork::Granite, granite::testnet_transitions())
+        .with_fork(OpHardfork::Holocene, holocene::testnet_transitions())
+});
+
+static DEVNET_MIGRATIONS: LazyLock<MigrationPlan> = LazyLock::new(|| {
+    MigrationPlan::new()
+        .with_fork(OpHardfork::Granite, granite::devnet_transitions())
+        .with_fork(OpHardfork::Holocene, holocene::devnet_transitions())
+});
+
+#[cfg(feature = "serde")]
+pub(super) mod serde_fork {
+    //! (De)serializes an [`OpHardfork`] by its case-insensitive name, e.g. `"granite"`.
+
+    use alloc::string::String;
+
+    use alloy_op_hardforks::OpHardfork;
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+
+    pub(super) fn serialize<S: Serializer>(
+        fork: &OpHardfork,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&fork.name().to_lowercase())
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<OpHardfork, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(D::Error::custom)
+    }
+}
@@ -1,8 +1,10 @@
 //! Block executor for Optimism.
 
 use crate::FraxtalEvmFactory;
-use alloc::{borrow::Cow, boxed::Box, vec::Vec};
-use alloy_consensus::{Eip658Value, Header, Transaction, TransactionEnvelope, TxReceipt};
+use alloc::{borrow::Cow, boxed::Box, sync::Arc, vec::Vec};
+use alloy_consensus::{
+    BlockHeader, Eip658Value, Header, Transaction, TransactionEnvelope, TxReceipt,
+};
 use alloy_eips::{Encodable2718, Typed2718};
 use alloy_evm::{
     Database, Evm, EvmFactory, FromRecoveredTx, FromTxWithEncoded, RecoveredTx,
@@ -18,9 +20,8 @@ use alloy_op_evm::{
     OpBlockExecutionCtx,
     block::{OpAlloyReceiptBuilder, OpTxEnv, receipt_builder::OpReceiptBuilder},
 };
-use alloy_op_hardforks::{OpChainHardforks, OpHardforks};
-use alloy_primitives::Address;
-use canyon::ensure_create2_deployer;
+use alloy_op_hardforks::{OpChainHardforks, OpHardfork, OpHardforks};
+use alloy_primitives::{Address, B256};
 use op_alloy_consensus::OpDepositReceipt;
 use op_revm::{
     L1BlockInfo, constants::L1_BLOCK_CONTRACT, estimate_tx_compressed_size,
@@ -32,13 +33,28 @@ use revm::{
     context::{Block, result::ResultAndState},
     database::DatabaseCommitExt,
 };
+use tracing::{info, warn};
 
 mod canyon;
+pub mod diff;
 mod granite;
 mod holocene;
 mod isthmus;
+pub mod migrations;
+pub mod report;
+pub mod transition;
 mod utils;
 
+#[cfg(test)]
+mod golden;
+#[cfg(test)]
+mod test_utils;
+
+pub use diff::{AccountDiff, StateDiff, StorageSlotDiff, apply_with_diff};
+pub use migrations::{ForkMigration, MigrationPlan};
+pub use report::MigrationReport;
+pub use transition::{CodeHashCheck, IrregularStateTransition, TransitionError};
+
 /// The result of executing a Fraxtal OP transaction.
 #[derive(Debug)]
 pub struct FraxtalTxResult<H, T> {
@@ -62,6 +78,32 @@ impl<H, T> TxResult for FraxtalTxResult<H, T> {
     }
 }
 
+/// Block time assumed to tell whether a fork activates at a block whose parent block timestamp is
+/// unknown.
+pub const FALLBACK_BLOCK_TIME: u64 = 2;
+
+/// Context for Fraxtal block execution.
+///
+/// Extends [`OpBlockExecutionCtx`] with the parent block timestamp, which determines whether a
+/// fork activates at the executed block.
+#[derive(Debug, Clone)]
+pub struct FraxtalBlockExecutionCtx {
+    /// The OP block execution context.
+    pub inner: OpBlockExecutionCtx,
+    /// Timestamp of the parent block, `None` if the parent header is unknown.
+    ///
+    /// Without it, fork activation falls back to assuming a [`FALLBACK_BLOCK_TIME`] seconds
+    /// block time.
+    pub parent_timestamp: Option<u64>,
+}
+
+impl FraxtalBlockExecutionCtx {
+    /// Creates a new [`FraxtalBlockExecutionCtx`].
+    pub const fn new(inner: OpBlockExecutionCtx, parent_timestamp: Option<u64>) -> Self {
+        Self { inner, parent_timestamp }
+    }
+}
+
 /// Block executor for Optimism.
 #[derive(Debug)]
 pub struct FraxtalBlockExecutor<Evm, R: OpReceiptBuilder, Spec> {
@@ -70,7 +112,7 @@ pub struct FraxtalBlockExecutor<Evm, R: OpReceiptBuilder, Spec> {
     /// Receipt builder.
     pub receipt_builder: R,
     /// Context for block execution.
-    pub ctx: OpBlockExecutionCtx,
+    pub ctx: FraxtalBlockExecutionCtx,
     /// The EVM used by executor.
     pub evm: Evm,
     /// Receipts of executed transactions.
@@ -86,6 +128,10 @@ pub struct FraxtalBlockExecutor<Evm, R: OpReceiptBuilder, Spec> {
     pub is_regolith: bool,
     /// Utility to call system smart contracts.
     pub system_caller: SystemCaller<Spec>,
+    /// Fork migrations overriding the built-in plan of the chain.
+    pub migrations: Option<Arc<MigrationPlan>>,
+    /// Reports of the fork migrations applied by the block.
+    pub migration_reports: Vec<MigrationReport>,
 }
 
 impl<E, R, Spec> FraxtalBlockExecutor<E, R, Spec>
@@ -95,7 +141,7 @@ where
     Spec: OpHardforks + Clone,
 {
     /// Creates a new [`FraxtalBlockExecutor`].
-    pub fn new(evm: E, ctx: OpBlockExecutionCtx, spec: Spec, receipt_builder: R) -> Self {
+    pub fn new(evm: E, ctx: FraxtalBlockExecutionCtx, spec: Spec, receipt_builder: R) -> Self {
         Self {
             is_regolith: spec
                 .is_regolith_active_at_timestamp(evm.block().timestamp().saturating_to()),
@@ -107,8 +153,21 @@ where
             gas_used: 0,
             da_footprint_used: 0,
             ctx,
+            migrations: None,
+            migration_reports: Vec::new(),
         }
     }
+
+    /// Overrides the built-in fork migrations of the chain with the given plan.
+    pub fn with_migrations(mut self, migrations: Option<Arc<MigrationPlan>>) -> Self {
+        self.migrations = migrations;
+        self
+    }
+
+    /// Returns the reports of the fork migrations applied by the block.
+    pub fn migration_reports(&self) -> &[MigrationReport] {
+        &self.migration_reports
+    }
 }
 
 /// Custom errors that can occur during OP block execution.
@@ -132,6 +191,46 @@ pub enum OpBlockExecutionError {
         /// The available block DA footprint.
         available_block_da_footprint: u64,
     },
+
+    /// Account code does not match the code hash expected before a migration step.
+    #[error("unexpected code hash of {address} before migration: expected {expected}, got {got}")]
+    MigrationPreStateMismatch {
+        /// The migrated account.
+        address: Address,
+        /// The expected code hash.
+        expected: B256,
+        /// The actual code hash.
+        got: B256,
+    },
+
+    /// Migrated code does not match the code hash expected after a migration step.
+    #[error("unexpected code hash of {address} after migration: expected {expected}, got {got}")]
+    MigrationPostStateMismatch {
+        /// The migrated account.
+        address: Address,
+        /// The expected code hash.
+        expected: B256,
+        /// The actual code hash.
+        got: B256,
+    },
+
+    /// A code patch of a migration step does not fit into the code of the migrated account.
+    #[error("code of {address} is too short to write {len} bytes at offset {offset}")]
+    MigrationCodeTooShort {
+        /// The migrated account.
+        address: Address,
+        /// Byte offset of the patch.
+        offset: usize,
+        /// Length of the patch.
+        len: usize,
+    },
+
+    /// The account whose code is patched by a migration step does not exist.
+    #[error("migrated account {address} does not exist")]
+    MigrationAccountMissing {
+        /// The missing account.
+        address: Address,
+    },
 }
 
 impl<E, R, Spec> FraxtalBlockExecutor<E, R, Spec>
@@ -167,6 +266,29 @@ where
 
         Ok(encoded.saturating_mul(da_footprint_gas_scalar))
     }
+
+    /// Returns `true` if `fork` activates at the block with the given timestamp, i.e. it is
+    /// active at `timestamp` but was not active at the parent block timestamp.
+    ///
+    /// If the parent block timestamp is unknown, e.g. for a block on a side chain the timestamp
+    /// lookup can't see, the parent is assumed to be [`FALLBACK_BLOCK_TIME`] seconds older.
+    fn is_fork_transition(&self, fork: OpHardfork, timestamp: u64) -> bool {
+        let activation = self.spec.op_fork_activation(fork);
+        if let Some(parent_timestamp) = self.ctx.parent_timestamp {
+            return activation.transitions_at_timestamp(timestamp, parent_timestamp);
+        }
+        let transitions = activation
+            .transitions_at_timestamp(timestamp, timestamp.saturating_sub(FALLBACK_BLOCK_TIME));
+        if transitions {
+            warn!(
+                target: "evm",
+                %fork,
+                timestamp,
+                "Unknown parent block timestamp, assuming the fork activates at the block"
+            );
+        }
+        transitions
+    }
 }
 
 impl<E, R, Spec> BlockExecutor for FraxtalBlockExecutor<E, R, Spec>
@@ -184,30 +306,36 @@ where
     type Result = FraxtalTxResult<E::HaltReason, <R::Transaction as TransactionEnvelope>::TxType>;
 
     fn apply_pre_execution_changes(&mut self) -> Result<(), BlockExecutionError> {
-        self.system_caller.apply_blockhashes_contract_call(self.ctx.parent_hash, &mut self.evm)?;
         self.system_caller
-            .apply_beacon_root_contract_call(self.ctx.parent_beacon_block_root, &mut self.evm)?;
+            .apply_blockhashes_contract_call(self.ctx.inner.parent_hash, &mut self.evm)?;
+        self.system_caller.apply_beacon_root_contract_call(
+            self.ctx.inner.parent_beacon_block_root,
+            &mut self.evm,
+        )?;
 
         let timestamp: u64 = self.evm.block().timestamp().saturating_to();
+        let block_number: u64 = self.evm.block().number().saturating_to();
+        let chain_id = self.spec.chain().id();
+        let plan = self.migrations.as_deref().unwrap_or_else(|| MigrationPlan::builtin(chain_id));
+
+        // Apply the irregular state transitions of every fork activated by this block, e.g. the
+        // create2deployer force-deployment at the canyon transition. Optimism blocks will always
+        // have at least a single transaction in them (the L1 info transaction), so we can safely
+        // assume that this will always be triggered upon the transition.
+        for migration in MigrationPlan::op_stack().iter().chain(plan) {
+            if !self.is_fork_transition(migration.fork, timestamp) {
+                continue;
+            }
 
-        // Ensure that the create2deployer is force-deployed at the canyon transition. Optimism
-        // blocks will always have at least a single transaction in them (the L1 info transaction),
-        // so we can safely assume that this will always be triggered upon the transition and that
-        // the above check for empty blocks will never be hit on OP chains.
-        ensure_create2_deployer(&self.spec, timestamp, self.evm.db_mut())
-            .map_err(BlockExecutionError::other)?;
-
-        // Ensure that during the granite hard fork we migrate frax to frxUSD and sfrax to sfrxUSD
-        granite::migrate_frxusd(&self.spec, timestamp, self.evm.db_mut())
-            .map_err(BlockExecutionError::other)?;
-
-        // Ensure that during the holocene hard fork we run the frax holocene migration
-        holocene::migrate_frax_holocene(&self.spec, timestamp, self.evm.db_mut())
-            .map_err(BlockExecutionError::other)?;
-
-        // Ensure that during the isthmus hard fork we run the frax isthmus migration
-        isthmus::migrate_frax_isthmus(&self.spec, timestamp, self.evm.db_mut())
-            .map_err(BlockExecutionError::other)?;
+            info!(target: "evm", fork = %migration.fork, "Applying irregular state transitions");
+            let diff = apply_with_diff(&migration.transitions, self.evm.db_mut())?;
+            self.migration_reports.push(MigrationReport {
+                fork: migration.fork,
+                chain_id,
+                block_number,
+                accounts: diff.accounts,
+            });
+        }
 
         Ok(())
     }
@@ -394,7 +522,7 @@ where
 }
 
 /// Ethereum block executor factory.
-#[derive(Debug, Clone, Default, Copy)]
+#[derive(Debug, Clone, Default)]
 pub struct FraxtalBlockExecutorFactory<
     R = OpAlloyReceiptBuilder,
     Spec = OpChainHardforks,
@@ -406,13 +534,21 @@ pub struct FraxtalBlockExecutorFactory<
     spec: Spec,
     /// EVM factory.
     evm_factory: EvmFactory,
+    /// Fork migrations overriding the built-in plan of the chain.
+    migrations: Option<Arc<MigrationPlan>>,
 }
 
 impl<R, Spec, EvmFactory> FraxtalBlockExecutorFactory<R, Spec, EvmFactory> {
     /// Creates a new [`FraxtalBlockExecutorFactory`] with the given spec, [`EvmFactory`], and
     /// [`OpReceiptBuilder`].
     pub const fn new(receipt_builder: R, spec: Spec, evm_factory: EvmFactory) -> Self {
-        Self { receipt_builder, spec, evm_factory }
+        Self { receipt_builder, spec, evm_factory, migrations: None }
+    }
+
+    /// Overrides the built-in fork migrations of the chain with the given plan.
+    pub fn with_migrations(mut self, migrations: impl Into<Arc<MigrationPlan>>) -> Self {
+        self.migrations = Some(migrations.into());
+        self
     }
 
     /// Exposes the receipt builder.
@@ -429,6 +565,11 @@ impl<R, Spec, EvmFactory> FraxtalBlockExecutorFactory<R, Spec, EvmFactory> {
     pub const fn evm_factory(&self) -> &EvmFactory {
         &self.evm_factory
     }
+
+    /// Exposes the fork migrations overriding the built-in plan of the chain, if any.
+    pub fn migrations(&self) -> Option<&MigrationPlan> {
+        self.migrations.as_deref()
+    }
 }
 
 impl<R, Spec, EvmF> BlockExecutorFactory for FraxtalBlockExecutorFactory<R, Spec, EvmF>
@@ -441,7 +582,7 @@ where
     Self: 'static,
 {
     type EvmFactory = EvmF;
-    type ExecutionCtx<'a> = OpBlockExecutionCtx;
+    type ExecutionCtx<'a> = FraxtalBlockExecutionCtx;
     type Transaction = R::Transaction;
     type Receipt = R::Receipt;
 
@@ -459,5 +600,308 @@ where
         I: Inspector<EvmF::Context<DB>> + 'a,
     {
         FraxtalBlockExecutor::new(evm, ctx, &self.spec, &self.receipt_builder)
+            .with_migrations(self.migrations.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::migrations::{
+        FRAXTAL_DEVNET_CHAIN_ID, FRAXTAL_HOODI_TESTNET_CHAIN_ID, FRAXTAL_MAINNET_CHAIN_ID,
+        FRAXTAL_TESTNET_CHAIN_ID,
+    };
+    use crate::block::test_utils::{
+        BLOCK_GAS_LIMIT, ChainSpecFixture, ExpectedState, TestBlock, TestHarness, transfer,
+    };
+    use crate::block::transition::ProxyUpgrade;
+    use alloy_primitives::{U256, address};
+    use revm::state::AccountInfo;
+
+    const SENDER: Address = address!("1000000000000000000000000000000000000001");
+    const RECIPIENT: Address = address!("2000000000000000000000000000000000000002");
+
+    fn funded_harness(fork: OpHardfork) -> TestHarness {
+        let spec = ChainSpecFixture::new(FRAXTAL_MAINNET_CHAIN_ID).active_through(fork).build();
+        let mut harness = TestHarness::new(spec);
+        harness.insert_account(
+            SENDER,
+            AccountInfo { balance: U256::from(10).pow(U256::from(18)), ..Default::default() },
+        );
+        harness
+    }
+
+    /// Executes the fork activation block of `fork` on `chain_id` and checks that exactly the
+    /// scheduled migrations were applied, once.
+    fn assert_fork_migration(chain_id: u64, fork: OpHardfork) {
+        let transitions: Vec<_> = MigrationPlan::op_stack()
+            .transitions(fork)
+            .chain(MigrationPlan::builtin(chain_id).transitions(fork))
+            .cloned()
+            .collect();
+        assert!(!transitions.is_empty(), "no {fork} migration on chain {chain_id}");
+
+        let spec = ChainSpecFixture::new(chain_id).with_fork_at(fork, 10).build();
+        let mut harness = TestHarness::new(spec);
+        let mut expected = ExpectedState::seed(&mut harness, &transitions);
+
+        // The block before the fork leaves the state untouched.
+        harness.execute(&TestBlock::new(1, 8)).unwrap();
+        expected.assert_matches(&mut harness);
+        assert!(harness.reports.take().is_empty());
+
+        harness.execute(&TestBlock::new(2, 10)).unwrap();
+        expected.apply(&transitions);
+        expected.assert_matches(&mut harness);
+
+        let reports = harness.reports.take();
+        assert_eq!(reports.len(), 1);
+        assert_eq!(reports[0].fork, fork);
+        assert_eq!(reports[0].chain_id, chain_id);
+        assert_eq!(reports[0].block_number, 2);
+        assert!(!reports[0].accounts.is_empty());
+
+        // The migration is not applied again after the activation block.
+        harness.execute(&TestBlock::new(3, 12)).unwrap();
+        expected.assert_matches(&mut harness);
+        assert!(harness.reports.take().is_empty());
+    }
+
+    #[test]
+    fn executes_l1_info_deposit() {
+        let mut harness = funded_harness(OpHardfork::Isthmus);
+
+        let result = harness.execute(&TestBlock::new(1, 2)).unwrap();
+
+        assert_eq!(result.receipts.len(), 1);
+        assert!(result.receipts[0].is_deposit());
+        assert!(result.receipts[0].status());
+        assert_eq!(result.gas_used, result.receipts[0].cumulative_gas_used());
+        assert_eq!(result.blob_gas_used, 0);
+    }
+
+    #[test]
+    fn executes_user_transaction() {
+        let mut harness = funded_harness(OpHardfork::Isthmus);
+        let chain_id = harness.chain_id();
+
+        let block = TestBlock::new(1, 2).with_transaction(transfer(
+            chain_id,
+            SENDER,
+            0,
+            RECIPIENT,
+            U256::from(1_000),
+            21_000,
+        ));
+        let result = harness.execute(&block).unwrap();
+
+        assert_eq!(result.receipts.len(), 2);
+        assert!(result.receipts[1].status());
+        assert_eq!(
+            result.receipts[1].cumulative_gas_used() - result.receipts[0].cumulative_gas_used(),
+            21_000
+        );
+        assert_eq!(result.gas_used, result.receipts[1].cumulative_gas_used());
+        // The DA footprint is only tracked from Jovian on.
+        assert_eq!(result.blob_gas_used, 0);
+        assert_eq!(harness.account(RECIPIENT).unwrap().balance, U256::from(1_000));
+        assert_eq!(harness.account(SENDER).unwrap().nonce, 1);
+    }
+
+    #[test]
+    fn tracks_jovian_da_footprint() {
+        let mut harness = funded_harness(OpHardfork::Jovian);
+        let chain_id = harness.chain_id();
+        let tx = transfer(chain_id, SENDER, 0, RECIPIENT, U256::from(1_000), 21_000);
+        let da_size = estimate_tx_compressed_size(tx.encoded_2718().as_ref()) / 1_000_000;
+
+        let result = harness.execute(&TestBlock::new(1, 2).with_transaction(tx)).unwrap();
+
+        let scalar: u64 =
+            L1BlockInfo::fetch_da_footprint_gas_scalar(&mut harness.state).unwrap().into();
+        // Deposits do not count towards the DA footprint.
+        assert_eq!(result.blob_gas_used, da_size * scalar);
+    }
+
+    #[test]
+    fn rejects_transaction_above_block_gas_limit() {
+        let mut harness = funded_harness(OpHardfork::Isthmus);
+        let chain_id = harness.chain_id();
+
+        let block = TestBlock::new(1, 2).with_transaction(transfer(
+            chain_id,
+            SENDER,
+            0,
+            RECIPIENT,
+            U256::ZERO,
+            BLOCK_GAS_LIMIT + 1,
+        ));
+        let err = harness.execute(&block).unwrap_err();
+
+        assert!(matches!(
+            err,
+            BlockExecutionError::Validation(
+                BlockValidationError::TransactionGasLimitMoreThanAvailableBlockGas { .. }
+            )
+        ));
+    }
+
+    #[test]
+    fn canyon_migration() {
+        assert_fork_migration(FRAXTAL_MAINNET_CHAIN_ID, OpHardfork::Canyon);
+    }
+
+    #[test]
+    fn granite_migrations() {
+        assert_fork_migration(FRAXTAL_MAINNET_CHAIN_ID, OpHardfork::Granite);
+        assert_fork_migration(FRAXTAL_TESTNET_CHAIN_ID, OpHardfork::Granite);
+        assert_fork_migration(FRAXTAL_HOODI_TESTNET_CHAIN_ID, OpHardfork::Granite);
+        assert_fork_migration(FRAXTAL_DEVNET_CHAIN_ID, OpHardfork::Granite);
+    }
+
+    #[test]
+    fn holocene_migrations() {
+        assert_fork_migration(FRAXTAL_MAINNET_CHAIN_ID, OpHardfork::Holocene);
+        assert_fork_migration(FRAXTAL_TESTNET_CHAIN_ID, OpHardfork::Holocene);
+        assert_fork_migration(FRAXTAL_HOODI_TESTNET_CHAIN_ID, OpHardfork::Holocene);
+        assert_fork_migration(FRAXTAL_DEVNET_CHAIN_ID, OpHardfork::Holocene);
+    }
+
+    #[test]
+    fn isthmus_migration() {
+        assert_fork_migration(FRAXTAL_MAINNET_CHAIN_ID, OpHardfork::Isthmus);
+        assert!(
+            MigrationPlan::builtin(FRAXTAL_HOODI_TESTNET_CHAIN_ID)
+                .transitions(OpHardfork::Isthmus)
+                .next()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn sets_code_hashes_of_plan() {
+        let token = address!("fc00000000000000000000000000000000000002");
+        let check = CodeHashCheck::new(B256::with_last_byte(1), B256::with_last_byte(2));
+        let plan = MigrationPlan::new()
+            .with_fork(OpHardfork::Granite, [ProxyUpgrade::fraxtal(token).into()])
+            .with_fork(OpHardfork::Holocene, [ProxyUpgrade::fraxtal(token).into()])
+            .with_code_hashes(&[(OpHardfork::Holocene, token, check)]);
+
+        let expected = |fork| plan.transitions(fork).next().unwrap().expected().copied();
+        assert_eq!(expected(OpHardfork::Granite), Some(CodeHashCheck::NONE));
+        assert_eq!(expected(OpHardfork::Holocene), Some(check));
+    }
+
+    #[test]
+    #[ignore = "the mainnet code hashes are not captured yet, see `MAINNET_CODE_HASHES`"]
+    fn builtin_mainnet_plan_checks_code_hashes() {
+        let unchecked: Vec<_> = MigrationPlan::builtin(FRAXTAL_MAINNET_CHAIN_ID)
+            .iter()
+            .flat_map(|migration| {
+                migration
+                    .transitions
+                    .iter()
+                    .filter(|transition| {
+                        transition
+                            .expected()
+                            .is_some_and(|check| check.pre.is_none() || check.post.is_none())
+                    })
+                    .map(move |transition| (migration.fork, transition.address()))
+            })
+            .collect();
+        assert!(unchecked.is_empty(), "mainnet transitions without code hashes: {unchecked:?}");
+    }
+
+    #[test]
+    fn detects_fork_transition_against_parent_timestamp() {
+        let spec = ChainSpecFixture::new(FRAXTAL_MAINNET_CHAIN_ID)
+            .with_fork_at(OpHardfork::Granite, 10)
+            .build();
+        let transitions: Vec<_> = MigrationPlan::builtin(FRAXTAL_MAINNET_CHAIN_ID)
+            .transitions(OpHardfork::Granite)
+            .cloned()
+            .collect();
+        let mut harness = TestHarness::new(spec);
+        let mut expected = ExpectedState::seed(&mut harness, &transitions);
+
+        // A block 7 seconds after its parent still activates the fork.
+        harness.execute(&TestBlock::new(1, 3).with_parent_timestamp(Some(1))).unwrap();
+        harness.execute(&TestBlock::new(2, 10).with_parent_timestamp(Some(3))).unwrap();
+        expected.apply(&transitions);
+        expected.assert_matches(&mut harness);
+        assert_eq!(harness.reports.take().len(), 1);
+
+        // Its child does not, even though it follows after a shorter block time.
+        harness.execute(&TestBlock::new(3, 11).with_parent_timestamp(Some(10))).unwrap();
+        assert!(harness.reports.take().is_empty());
+    }
+
+    #[test]
+    fn plan_activated_matches_executed_migrations() {
+        let spec = ChainSpecFixture::new(FRAXTAL_MAINNET_CHAIN_ID)
+            .with_fork_at(OpHardfork::Granite, 10)
+            .build();
+        let plan = MigrationPlan::builtin(FRAXTAL_MAINNET_CHAIN_ID);
+
+        let forks: Vec<_> = plan.activated(&*spec, 10, 3).map(|m| m.fork).collect();
+        assert_eq!(forks, [OpHardfork::Granite]);
+        assert_eq!(plan.activated(&*spec, 11, 10).count(), 0);
+        assert_eq!(plan.activated(&*spec, 3, 1).count(), 0);
+    }
+
+    #[test]
+    fn unknown_parent_timestamp_falls_back_to_block_time() {
+        let spec = ChainSpecFixture::new(FRAXTAL_MAINNET_CHAIN_ID)
+            .with_fork_at(OpHardfork::Granite, 10)
+            .build();
+        let transitions: Vec<_> = MigrationPlan::builtin(FRAXTAL_MAINNET_CHAIN_ID)
+            .transitions(OpHardfork::Granite)
+            .cloned()
+            .collect();
+        let mut harness = TestHarness::new(spec);
+        let mut expected = ExpectedState::seed(&mut harness, &transitions);
+
+        harness.execute(&TestBlock::new(1, 8).with_parent_timestamp(None)).unwrap();
+        assert!(harness.reports.take().is_empty());
+
+        // The parent is assumed to be `FALLBACK_BLOCK_TIME` seconds older.
+        harness.execute(&TestBlock::new(2, 10).with_parent_timestamp(None)).unwrap();
+        expected.apply(&transitions);
+        expected.assert_matches(&mut harness);
+        assert_eq!(harness.reports.take().len(), 1);
+
+        harness.execute(&TestBlock::new(3, 12).with_parent_timestamp(None)).unwrap();
+        assert!(harness.reports.take().is_empty());
+    }
+
+    #[test]
+    fn custom_plan_replaces_builtin_migrations() {
+        let spec = ChainSpecFixture::new(FRAXTAL_MAINNET_CHAIN_ID)
+            .with_fork_at(OpHardfork::Granite, 10)
+            .build();
+        let mut harness = TestHarness::new(spec).with_migrations(MigrationPlan::new());
+        let transitions: Vec<_> = MigrationPlan::builtin(FRAXTAL_MAINNET_CHAIN_ID)
+            .transitions(OpHardfork::Granite)
+            .cloned()
+            .collect();
+        let expected = ExpectedState::seed(&mut harness, &transitions);
+
+        harness.execute(&TestBlock::new(1, 10)).unwrap();
+
+        expected.assert_matches(&mut harness);
+        assert!(harness.reports.take().is_empty());
+    }
+
+    #[test]
+    fn migration_on_truncated_code_fails_block() {
+        let spec = ChainSpecFixture::new(FRAXTAL_DEVNET_CHAIN_ID)
+            .with_fork_at(OpHardfork::Granite, 10)
+            .build();
+        let mut harness = TestHarness::new(spec);
+        harness.insert_code(address!("fc00000000000000000000000000000000000001"), [0u8; 32]);
+
+        let err = harness.execute(&TestBlock::new(1, 10)).unwrap_err();
+
+        assert!(err.to_string().contains("too short"), "{err}");
     }
 }
@@ -0,0 +1,25 @@
+//! Machine-readable records of the fork migrations applied by executed blocks.
+//!
+//! The executor keeps the reports of the block it executes, see
+//! [`FraxtalBlockExecutor::migration_reports`](super::FraxtalBlockExecutor::migration_reports).
+
+use alloc::vec::Vec;
+
+use alloy_op_hardforks::OpHardfork;
+
+use super::diff::AccountDiff;
+
+/// The record of a fork migration applied on the activation block of the fork.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MigrationReport {
+    /// The fork whose activation triggered the migration.
+    #[cfg_attr(feature = "serde", serde(with = "super::migrations::serde_fork"))]
+    pub fork: OpHardfork,
+    /// The chain id.
+    pub chain_id: u64,
+    /// The number of the fork activation block.
+    pub block_number: u64,
+    /// The accounts and storage slots written by the migration, ordered by address.
+    pub accounts: Vec<AccountDiff>,
+}
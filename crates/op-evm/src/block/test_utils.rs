@@ -0,0 +1,403 @@
+//! Harness executing blocks with a [`FraxtalBlockExecutor`] on an in-memory state.
+
+use std::{collections::HashMap, sync::Arc};
+
+use alloy_consensus::{Header, SignableTransaction, TxEip1559, transaction::Recovered};
+use alloy_eips::Decodable2718;
+use alloy_evm::{
+    EvmFactory,
+    block::{BlockExecutionError, BlockExecutionResult, BlockExecutor},
+};
+use alloy_genesis::Genesis;
+use alloy_op_evm::{OpBlockExecutionCtx, block::OpAlloyReceiptBuilder, evm_env_for_op_block};
+use alloy_op_hardforks::OpHardfork;
+use alloy_primitives::{Address, B256, Bytes, Signature, TxKind, U256, hex, keccak256};
+use op_alloy_consensus::{OpReceiptEnvelope, OpTxEnvelope};
+use reth_chainspec::{Chain, EthChainSpec, EthereumHardfork, ForkCondition};
+use reth_optimism_chainspec::{OpChainSpec, OpChainSpecBuilder};
+use revm::{
+    Database as _,
+    database::{CacheDB, EmptyDB, State},
+    state::{AccountInfo, Bytecode},
+};
+
+use super::{
+    FraxtalBlockExecutionCtx, FraxtalBlockExecutor, FraxtalBlockExecutorFactory, MigrationPlan,
+    MigrationReport,
+    transition::{IrregularStateTransition, PROXY_ADMIN_SLOT, PROXY_IMPLEMENTATION_SLOT},
+};
+use crate::FraxtalEvmFactory;
+
+/// L1 info deposit of OP Mainnet block 124665056, in the Ecotone encoding.
+const L1_INFO_DEPOSIT: [u8; 251] = hex!(
+    "7ef8f8a0683079df94aa5b9cf86687d739a60a9b4f0835e520ec4d664e2e415dca17a6df94deaddeaddeaddeaddeaddeaddeaddeaddead00019442000000000000000000000000000000000000158080830f424080b8a4440a5e200000146b000f79c500000000000000040000000066d052e700000000013ad8a3000000000000000000000000000000000000000000000000000000003ef1278700000000000000000000000000000000000000000000000000000000000000012fdf87b89884a61e74b322bbcf60386f543bfae7827725efaaf0ab1de2294a590000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f32985"
+);
+
+/// Gas limit of the test blocks.
+pub(crate) const BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// Fork activation timestamps of a test chain.
+///
+/// Bedrock and Regolith are active at genesis, every other fork is inactive unless configured.
+#[derive(Debug, Clone)]
+pub(crate) struct ChainSpecFixture {
+    chain_id: u64,
+    forks: Vec<(OpHardfork, u64)>,
+}
+
+impl ChainSpecFixture {
+    /// Creates a chain with the given chain id.
+    pub(crate) fn new(chain_id: u64) -> Self {
+        Self { chain_id, forks: vec![(OpHardfork::Regolith, 0)] }
+    }
+
+    /// Activates `fork` at `timestamp` and every earlier fork at genesis.
+    pub(crate) fn with_fork_at(mut self, fork: OpHardfork, timestamp: u64) -> Self {
+        self.forks = OpHardfork::VARIANTS
+            .iter()
+            .copied()
+            .skip(1)
+            .take_while(|f| *f <= fork)
+            .map(|f| (f, if f == fork { timestamp } else { 0 }))
+            .collect();
+        self
+    }
+
+    /// Activates every fork up to and including `fork` at genesis.
+    pub(crate) fn active_through(self, fork: OpHardfork) -> Self {
+        self.with_fork_at(fork, 0)
+    }
+
+    /// Builds the chain spec.
+    pub(crate) fn build(self) -> Arc<OpChainSpec> {
+        let mut builder = OpChainSpecBuilder::default()
+            .chain(Chain::from_id(self.chain_id))
+            .genesis(Genesis::default())
+            .bedrock_activated();
+
+        for (fork, timestamp) in self.forks {
+            let condition = ForkCondition::Timestamp(timestamp);
+            builder = builder.with_fork(fork, condition);
+            // OP Stack forks activating an Ethereum fork at the same time.
+            let ethereum_fork = match fork {
+                OpHardfork::Canyon => Some(EthereumHardfork::Shanghai),
+                OpHardfork::Ecotone => Some(EthereumHardfork::Cancun),
+                OpHardfork::Isthmus => Some(EthereumHardfork::Prague),
+                _ => None,
+            };
+            if let Some(ethereum_fork) = ethereum_fork {
+                builder = builder.with_fork(ethereum_fork, condition);
+            }
+        }
+
+        Arc::new(builder.build())
+    }
+}
+
+/// A block executed by [`TestHarness::execute`].
+#[derive(Debug, Clone)]
+pub(crate) struct TestBlock {
+    /// The block number.
+    pub(crate) number: u64,
+    /// The block timestamp.
+    pub(crate) timestamp: u64,
+    /// The timestamp of the parent block, `None` if unknown.
+    pub(crate) parent_timestamp: Option<u64>,
+    /// The block gas limit.
+    pub(crate) gas_limit: u64,
+    /// The block transactions, starting with the L1 info deposit.
+    pub(crate) transactions: Vec<Recovered<OpTxEnvelope>>,
+}
+
+impl TestBlock {
+    /// Creates a block holding only the L1 info deposit, two seconds after its parent.
+    pub(crate) fn new(number: u64, timestamp: u64) -> Self {
+        Self {
+            number,
+            timestamp,
+            parent_timestamp: Some(timestamp.saturating_sub(2)),
+            gas_limit: BLOCK_GAS_LIMIT,
+            transactions: vec![l1_info_deposit()],
+        }
+    }
+
+    /// Sets the timestamp of the parent block, `None` if unknown.
+    pub(crate) const fn with_parent_timestamp(mut self, parent_timestamp: Option<u64>) -> Self {
+        self.parent_timestamp = parent_timestamp;
+        self
+    }
+
+    /// Appends a transaction to the block.
+    pub(crate) fn with_transaction(mut self, tx: Recovered<OpTxEnvelope>) -> Self {
+        self.transactions.push(tx);
+        self
+    }
+
+    fn header(&self) -> Header {
+        Header {
+            number: self.number,
+            timestamp: self.timestamp,
+            gas_limit: self.gas_limit,
+            base_fee_per_gas: Some(0),
+            blob_gas_used: Some(0),
+            excess_blob_gas: Some(0),
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        }
+    }
+
+    fn ctx(&self) -> FraxtalBlockExecutionCtx {
+        FraxtalBlockExecutionCtx::new(
+            OpBlockExecutionCtx {
+                parent_hash: B256::ZERO,
+                parent_beacon_block_root: Some(B256::ZERO),
+                extra_data: Bytes::new(),
+            },
+            self.parent_timestamp,
+        )
+    }
+}
+
+/// Returns the canned L1 info deposit.
+pub(crate) fn l1_info_deposit() -> Recovered<OpTxEnvelope> {
+    let tx = OpTxEnvelope::decode_2718(&mut L1_INFO_DEPOSIT.as_slice()).unwrap();
+    let from = tx.as_deposit().unwrap().from;
+    Recovered::new_unchecked(tx, from)
+}
+
+/// Returns an EIP-1559 transfer of `value` from `sender` to `to`, without a valid signature.
+pub(crate) fn transfer(
+    chain_id: u64,
+    sender: Address,
+    nonce: u64,
+    to: Address,
+    value: U256,
+    gas_limit: u64,
+) -> Recovered<OpTxEnvelope> {
+    let tx = TxEip1559 {
+        chain_id,
+        nonce,
+        gas_limit,
+        max_fee_per_gas: 1,
+        max_priority_fee_per_gas: 0,
+        to: TxKind::Call(to),
+        value,
+        access_list: Default::default(),
+        input: Bytes::new(),
+    };
+    Recovered::new_unchecked(tx.into_signed(Signature::test_signature()).into(), sender)
+}
+
+/// Collects the migration reports of the executed blocks.
+#[derive(Debug, Default)]
+pub(crate) struct RecordedReports(Vec<MigrationReport>);
+
+impl RecordedReports {
+    /// Takes the reports recorded so far.
+    pub(crate) fn take(&mut self) -> Vec<MigrationReport> {
+        core::mem::take(&mut self.0)
+    }
+}
+
+/// Executes blocks with a [`FraxtalBlockExecutor`] configured like the ones created by a
+/// [`FraxtalBlockExecutorFactory`], on an in-memory state.
+#[derive(Debug)]
+pub(crate) struct TestHarness {
+    /// The chain spec.
+    pub(crate) spec: Arc<OpChainSpec>,
+    /// The state blocks are executed on.
+    pub(crate) state: State<CacheDB<EmptyDB>>,
+    /// The migration reports of executed blocks.
+    pub(crate) reports: RecordedReports,
+    factory: FraxtalBlockExecutorFactory<OpAlloyReceiptBuilder, Arc<OpChainSpec>>,
+}
+
+impl TestHarness {
+    /// Creates a harness with an empty state.
+    pub(crate) fn new(spec: Arc<OpChainSpec>) -> Self {
+        let factory = FraxtalBlockExecutorFactory::new(
+            OpAlloyReceiptBuilder::default(),
+            spec.clone(),
+            FraxtalEvmFactory::default(),
+        );
+        let state = State::builder()
+            .with_database(CacheDB::new(EmptyDB::default()))
+            .with_bundle_update()
+            .build();
+        Self { spec, state, reports: RecordedReports::default(), factory }
+    }
+
+    /// Overrides the built-in fork migrations of the chain.
+    pub(crate) fn with_migrations(mut self, plan: MigrationPlan) -> Self {
+        self.factory = self.factory.with_migrations(plan);
+        self
+    }
+
+    /// Returns the chain id.
+    pub(crate) fn chain_id(&self) -> u64 {
+        self.spec.chain().id()
+    }
+
+    /// Inserts an account into the underlying database.
+    pub(crate) fn insert_account(&mut self, address: Address, info: AccountInfo) {
+        self.state.database.insert_account_info(address, info);
+    }
+
+    /// Inserts a contract with the given code into the underlying database.
+    pub(crate) fn insert_code(&mut self, address: Address, code: impl Into<Bytes>) {
+        self.insert_account(address, AccountInfo::from_bytecode(Bytecode::new_raw(code.into())));
+    }
+
+    /// Executes the block.
+    pub(crate) fn execute(
+        &mut self,
+        block: &TestBlock,
+    ) -> Result<BlockExecutionResult<OpReceiptEnvelope>, BlockExecutionError> {
+        let evm_env = evm_env_for_op_block(&block.header(), &self.spec, self.chain_id());
+        let evm = self.factory.evm_factory().create_evm(&mut self.state, evm_env);
+        let mut executor = FraxtalBlockExecutor::new(
+            evm,
+            block.ctx(),
+            self.factory.spec(),
+            self.factory.receipt_builder(),
+        )
+        .with_migrations(self.factory.migrations().cloned().map(Arc::new));
+
+        executor.apply_pre_execution_changes()?;
+        self.reports.0.extend_from_slice(executor.migration_reports());
+        for tx in &block.transactions {
+            executor.execute_transaction(tx.as_recovered_ref())?;
+        }
+        let (_, result) = executor.finish()?;
+        Ok(result)
+    }
+
+    /// Returns the account at `address`, if it exists.
+    pub(crate) fn account(&mut self, address: Address) -> Option<AccountInfo> {
+        self.state.basic(address).unwrap()
+    }
+
+    /// Returns the code at `address`.
+    pub(crate) fn code(&mut self, address: Address) -> Bytes {
+        let Some(account) = self.account(address) else { return Bytes::new() };
+        let code = match account.code {
+            Some(code) => code,
+            None => self.state.code_by_hash(account.code_hash).unwrap(),
+        };
+        code.original_bytes()
+    }
+
+    /// Returns the value of `slot` at `address`.
+    pub(crate) fn storage(&mut self, address: Address, slot: B256) -> U256 {
+        self.state.storage(address, slot.into()).unwrap()
+    }
+}
+
+/// Independent model of the state produced by a list of [`IrregularStateTransition`]s.
+///
+/// The model is seeded with the same code as the harness, so the executed state can be compared
+/// against it account by account.
+#[derive(Debug, Default)]
+pub(crate) struct ExpectedState {
+    code: HashMap<Address, Vec<u8>>,
+    storage: HashMap<(Address, B256), B256>,
+}
+
+impl ExpectedState {
+    /// Seeds every account whose code the transitions read with code long enough for every
+    /// patch, in both the harness and the model.
+    pub(crate) fn seed(
+        harness: &mut TestHarness,
+        transitions: &[IrregularStateTransition],
+    ) -> Self {
+        let patch_end = transitions
+            .iter()
+            .flat_map(|transition| match transition {
+                IrregularStateTransition::ProxyUpgrade(upgrade) => upgrade
+                    .implementation_patches
+                    .iter()
+                    .map(|patch| patch.offset + patch.value.len())
+                    .collect(),
+                IrregularStateTransition::BytecodePatch(patch) => {
+                    vec![patch.offset + patch.value.len()]
+                }
+                _ => Vec::new(),
+            })
+            .max()
+            .unwrap_or_default();
+
+        let mut state = Self::default();
+        for transition in transitions {
+            let sources = match transition {
+                IrregularStateTransition::ProxyUpgrade(upgrade) => {
+                    vec![upgrade.address, upgrade.proxy_source]
+                }
+                IrregularStateTransition::BytecodePatch(patch) => vec![patch.address],
+                _ => Vec::new(),
+            };
+            for address in sources {
+                if state.code.contains_key(&address) {
+                    continue;
+                }
+                // Distinct code per account, so moved code can be told apart.
+                let code: Vec<u8> =
+                    keccak256(address).iter().copied().cycle().take(patch_end + 32).collect();
+                harness.insert_code(address, code.clone());
+                state.code.insert(address, code);
+            }
+        }
+        state
+    }
+
+    /// Applies the transitions to the model.
+    pub(crate) fn apply(&mut self, transitions: &[IrregularStateTransition]) {
+        for transition in transitions {
+            match transition {
+                IrregularStateTransition::ProxyUpgrade(upgrade) => {
+                    let mut implementation =
+                        self.code.get(&upgrade.address).cloned().unwrap_or_default();
+                    for patch in &upgrade.implementation_patches {
+                        implementation[patch.offset..patch.offset + patch.value.len()]
+                            .copy_from_slice(&patch.value);
+                    }
+                    let proxy = self.code.get(&upgrade.proxy_source).cloned().unwrap_or_default();
+                    self.code.insert(upgrade.implementation, implementation);
+                    self.code.insert(upgrade.address, proxy);
+                    self.storage
+                        .insert((upgrade.address, PROXY_ADMIN_SLOT), upgrade.admin.into_word());
+                    self.storage.insert(
+                        (upgrade.address, PROXY_IMPLEMENTATION_SLOT),
+                        upgrade.implementation.into_word(),
+                    );
+                }
+                IrregularStateTransition::BytecodePatch(patch) => {
+                    let code = self.code.entry(patch.address).or_default();
+                    code[patch.offset..patch.offset + patch.value.len()]
+                        .copy_from_slice(&patch.value);
+                }
+                IrregularStateTransition::StorageWrite(write) => {
+                    self.storage.insert((write.address, write.slot), write.value);
+                }
+                IrregularStateTransition::ForceDeploy(deploy) => {
+                    self.code.insert(deploy.address, deploy.code.to_vec());
+                }
+            }
+        }
+    }
+
+    /// Asserts that the code and storage of the harness match the model.
+    pub(crate) fn assert_matches(&self, harness: &mut TestHarness) {
+        for (address, code) in &self.code {
+            assert_eq!(harness.code(*address), Bytes::from(code.clone()), "code of {address}");
+            let code_hash = harness.account(*address).map(|account| account.code_hash);
+            assert_eq!(code_hash, Some(keccak256(code)), "code hash of {address}");
+        }
+        for ((address, slot), value) in &self.storage {
+            assert_eq!(
+                harness.storage(*address, *slot),
+                U256::from_be_bytes(value.0),
+                "slot {slot} of {address}"
+            );
+        }
+    }
+}
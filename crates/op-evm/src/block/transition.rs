@@ -0,0 +1,778 @@
+//! Irregular state transitions applied at Fraxtal fork boundaries.
+//!
+//! Every Fraxtal-specific fork migration is expressed as a list of
+//! [`IrregularStateTransition`]s, which are applied in order to the state prior to executing any
+//! transaction of the fork activation block.
+
+use std::collections::HashMap;
+
+use alloy_evm::{Database, block::BlockExecutionError};
+use alloy_primitives::{Address, B256, Bytes, KECCAK256_EMPTY, U256, address, b256, keccak256};
+use revm::{
+    DatabaseCommit,
+    state::{Account, AccountInfo, Bytecode, EvmStorageSlot},
+};
+use tracing::info;
+
+use super::{
+    OpBlockExecutionError,
+    utils::{get_contract_code, load_contract_account},
+};
+
+/// The account holding the proxy bytecode that upgraded contracts are switched to.
+pub const PROXY_ADDR: Address = address!("fc0000000000000000000000000000000000000a");
+/// The admin written to the proxy admin slot of upgraded contracts.
+pub const PROXY_ADMIN_ADDR: Address = address!("fc0000000000000000000000000000000000000a");
+/// EIP-1967 proxy admin slot.
+pub const PROXY_ADMIN_SLOT: B256 =
+    b256!("b53127684a568b3173ae13b9f8a6016e243e63b6e8ee1178d6a717850b5d6103");
+/// EIP-1967 proxy implementation slot.
+pub const PROXY_IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+/// Prefix of the address the original code of an upgraded contract is moved to.
+pub const IMPLEMENTATION_ADDR_PREFIX: [u8; 3] = [0xfc, 0xc0, 0xd3];
+
+/// Returns the implementation address for the given contract, i.e. the contract address with its
+/// first three bytes replaced by [`IMPLEMENTATION_ADDR_PREFIX`].
+pub fn implementation_address(address: Address) -> Address {
+    let mut implementation = address;
+    implementation[0..3].copy_from_slice(&IMPLEMENTATION_ADDR_PREFIX);
+    implementation
+}
+
+/// A single irregular state change issued at a fork boundary.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum IrregularStateTransition {
+    /// Turns a contract into an upgradable proxy.
+    ProxyUpgrade(ProxyUpgrade),
+    /// Overwrites a range of a contract bytecode.
+    BytecodePatch(BytecodePatch),
+    /// Writes a single storage slot.
+    StorageWrite(StorageWrite),
+    /// Force-deploys a fixed bytecode to an address.
+    ForceDeploy(ForceDeploy),
+}
+
+impl IrregularStateTransition {
+    /// Returns the account primarily affected by this transition.
+    pub const fn address(&self) -> Address {
+        match self {
+            Self::ProxyUpgrade(upgrade) => upgrade.address,
+            Self::BytecodePatch(patch) => patch.address,
+            Self::StorageWrite(write) => write.address,
+            Self::ForceDeploy(deploy) => deploy.address,
+        }
+    }
+
+    /// Returns the code hashes checked by this transition, `None` for storage writes.
+    pub const fn expected(&self) -> Option<&CodeHashCheck> {
+        match self {
+            Self::ProxyUpgrade(upgrade) => Some(&upgrade.expected),
+            Self::BytecodePatch(patch) => Some(&patch.expected),
+            Self::ForceDeploy(deploy) => Some(&deploy.expected),
+            Self::StorageWrite(_) => None,
+        }
+    }
+
+    /// Returns a mutable reference to the code hashes checked by this transition, `None` for
+    /// storage writes.
+    pub const fn expected_mut(&mut self) -> Option<&mut CodeHashCheck> {
+        match self {
+            Self::ProxyUpgrade(upgrade) => Some(&mut upgrade.expected),
+            Self::BytecodePatch(patch) => Some(&mut patch.expected),
+            Self::ForceDeploy(deploy) => Some(&mut deploy.expected),
+            Self::StorageWrite(_) => None,
+        }
+    }
+
+    /// Returns the accounts written by this transition.
+    pub fn touched_accounts(&self) -> Vec<Address> {
+        match self {
+            Self::ProxyUpgrade(upgrade) => vec![upgrade.address, upgrade.implementation],
+            _ => vec![self.address()],
+        }
+    }
+
+    /// Returns the storage slots written by this transition.
+    pub fn written_slots(&self) -> Vec<(Address, B256)> {
+        match self {
+            Self::ProxyUpgrade(upgrade) => vec![
+                (upgrade.address, PROXY_ADMIN_SLOT),
+                (upgrade.address, PROXY_IMPLEMENTATION_SLOT),
+            ],
+            Self::StorageWrite(write) => vec![(write.address, write.slot)],
+            Self::BytecodePatch(_) | Self::ForceDeploy(_) => Vec::new(),
+        }
+    }
+
+    /// Applies the transition to the given database, committing the changed accounts.
+    ///
+    /// Fails without committing anything if the state does not match the code hashes expected
+    /// by the transition.
+    pub fn apply<DB>(&self, db: &mut DB) -> Result<(), TransitionError<DB::Error>>
+    where
+        DB: Database + DatabaseCommit,
+    {
+        match self {
+            Self::ProxyUpgrade(upgrade) => upgrade.apply(db),
+            Self::BytecodePatch(patch) => patch.apply(db),
+            Self::StorageWrite(write) => write.apply(db),
+            Self::ForceDeploy(deploy) => deploy.apply(db),
+        }
+    }
+}
+
+/// Error applying an [`IrregularStateTransition`].
+#[derive(Debug, thiserror::Error)]
+pub enum TransitionError<E> {
+    /// Failed to read the state.
+    #[error(transparent)]
+    Database(E),
+    /// The state does not match the expectations of the transition.
+    #[error(transparent)]
+    Migration(#[from] OpBlockExecutionError),
+}
+
+impl<E> From<TransitionError<E>> for BlockExecutionError
+where
+    E: core::error::Error + Send + Sync + 'static,
+{
+    fn from(err: TransitionError<E>) -> Self {
+        match err {
+            TransitionError::Database(err) => Self::other(err),
+            TransitionError::Migration(err) => Self::other(err),
+        }
+    }
+}
+
+/// Code hashes a transition expects to find before and to produce after it is applied.
+///
+/// Unset hashes are not checked.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default, deny_unknown_fields))]
+pub struct CodeHashCheck {
+    /// Expected code hash before the transition is applied.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub pre: Option<B256>,
+    /// Expected code hash after the transition is applied.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub post: Option<B256>,
+}
+
+impl CodeHashCheck {
+    /// No expectations.
+    pub const NONE: Self = Self { pre: None, post: None };
+
+    /// Creates a check expecting the given code hashes before and after the transition.
+    pub const fn new(pre: B256, post: B256) -> Self {
+        Self { pre: Some(pre), post: Some(post) }
+    }
+
+    /// Returns `true` if no code hash is checked.
+    pub const fn is_empty(&self) -> bool {
+        self.pre.is_none() && self.post.is_none()
+    }
+
+    fn check_pre(&self, address: Address, got: B256) -> Result<(), OpBlockExecutionError> {
+        match self.pre {
+            Some(expected) if expected != got => {
+                Err(OpBlockExecutionError::MigrationPreStateMismatch { address, expected, got })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn check_post(&self, address: Address, got: B256) -> Result<(), OpBlockExecutionError> {
+        match self.post {
+            Some(expected) if expected != got => {
+                Err(OpBlockExecutionError::MigrationPostStateMismatch { address, expected, got })
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl From<ProxyUpgrade> for IrregularStateTransition {
+    fn from(upgrade: ProxyUpgrade) -> Self {
+        Self::ProxyUpgrade(upgrade)
+    }
+}
+
+impl From<BytecodePatch> for IrregularStateTransition {
+    fn from(patch: BytecodePatch) -> Self {
+        Self::BytecodePatch(patch)
+    }
+}
+
+impl From<StorageWrite> for IrregularStateTransition {
+    fn from(write: StorageWrite) -> Self {
+        Self::StorageWrite(write)
+    }
+}
+
+impl From<ForceDeploy> for IrregularStateTransition {
+    fn from(deploy: ForceDeploy) -> Self {
+        Self::ForceDeploy(deploy)
+    }
+}
+
+/// A write of `value` into the code at `offset`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct CodePatch {
+    /// Byte offset into the code.
+    pub offset: usize,
+    /// Bytes written at `offset`.
+    pub value: Bytes,
+}
+
+/// Turns the contract at `address` into an upgradable proxy.
+///
+/// The current code of `address` (optionally patched) is copied to `implementation`, the code of
+/// `address` is replaced with the code of `proxy_source`, and the EIP-1967 admin and
+/// implementation slots of `address` are set to `admin` and `implementation`.
+///
+/// The `pre` code hash of `expected` is checked against the code of `address` before the upgrade,
+/// the `post` code hash against the code stored at `implementation`.
+///
+/// When deserialized, `implementation`, `proxy_source` and `admin` default to the values used by
+/// [`ProxyUpgrade::fraxtal`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "serde_repr::ProxyUpgrade"))]
+pub struct ProxyUpgrade {
+    /// The contract being upgraded.
+    pub address: Address,
+    /// The account receiving the original code.
+    pub implementation: Address,
+    /// The account the proxy code is copied from.
+    pub proxy_source: Address,
+    /// The proxy admin.
+    pub admin: Address,
+    /// Patches applied to the code before it is stored at `implementation`.
+    pub implementation_patches: Vec<CodePatch>,
+    /// Expected code hashes of the upgraded contract and its implementation.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "CodeHashCheck::is_empty"))]
+    pub expected: CodeHashCheck,
+}
+
+impl ProxyUpgrade {
+    /// Creates the standard Fraxtal proxy upgrade for `address`, moving its code to the
+    /// `0xfcc0d3`-prefixed implementation address.
+    pub fn fraxtal(address: Address) -> Self {
+        Self {
+            address,
+            implementation: implementation_address(address),
+            proxy_source: PROXY_ADDR,
+            admin: PROXY_ADMIN_ADDR,
+            implementation_patches: Vec::new(),
+            expected: CodeHashCheck::NONE,
+        }
+    }
+
+    /// Writes `value` at each of the given offsets of the implementation code.
+    pub fn with_implementation_patches(
+        mut self,
+        offsets: &[usize],
+        value: impl Into<Bytes>,
+    ) -> Self {
+        let value = value.into();
+        self.implementation_patches
+            .extend(offsets.iter().map(|&offset| CodePatch { offset, value: value.clone() }));
+        self
+    }
+
+    /// Sets the code hashes expected before and after the upgrade.
+    pub const fn with_expected(mut self, expected: CodeHashCheck) -> Self {
+        self.expected = expected;
+        self
+    }
+
+    fn apply<DB>(&self, db: &mut DB) -> Result<(), TransitionError<DB::Error>>
+    where
+        DB: Database + DatabaseCommit,
+    {
+        info!(target: "evm", "Setting implementation from {} to {}", self.address, self.implementation);
+
+        let mut current_contract_acc = if self.implementation_patches.is_empty() {
+            load_contract_account(db, self.address).map_err(TransitionError::Database)?
+        } else {
+            load_patched_account(db, self.address)?
+        };
+        self.expected.check_pre(self.address, current_contract_acc.code_hash)?;
+        let mut new_implementation_code = get_contract_code(db, &current_contract_acc);
+        for patch in &self.implementation_patches {
+            write_code(self.address, &mut new_implementation_code, patch.offset, &patch.value)?;
+        }
+
+        let mut implementation_acc =
+            load_contract_account(db, self.implementation).map_err(TransitionError::Database)?;
+        implementation_acc.code = Some(Bytecode::new_raw(new_implementation_code.into()));
+        implementation_acc.code_hash =
+            implementation_acc.code.as_ref().map_or(KECCAK256_EMPTY, |c| c.hash_slow());
+        self.expected.check_post(self.implementation, implementation_acc.code_hash)?;
+        let mut implementation_revm_account: Account = implementation_acc.into();
+        implementation_revm_account.mark_touch();
+
+        info!(target: "evm", "Setting proxy from {} to {}", self.proxy_source, self.address);
+        let proxy_acc =
+            load_contract_account(db, self.proxy_source).map_err(TransitionError::Database)?;
+        current_contract_acc.code = proxy_acc.code.clone();
+        current_contract_acc.code_hash = proxy_acc.code_hash;
+
+        let mut current_contract_revm_account: Account = current_contract_acc.into();
+        current_contract_revm_account.mark_touch();
+        info!(target: "evm", "Setting proxy {} admin to {}", self.address, self.admin);
+        current_contract_revm_account.storage.insert(
+            U256::from_be_bytes(PROXY_ADMIN_SLOT.into()),
+            EvmStorageSlot::new_changed(
+                U256::default(),
+                U256::from_be_bytes(B256::left_padding_from(self.admin.as_slice()).into()),
+                0,
+            ),
+        );
+        info!(target: "evm", "Setting proxy {} implementation to {}", self.address, self.implementation);
+        current_contract_revm_account.storage.insert(
+            U256::from_be_bytes(PROXY_IMPLEMENTATION_SLOT.into()),
+            EvmStorageSlot::new_changed(
+                U256::default(),
+                U256::from_be_bytes(B256::left_padding_from(self.implementation.as_slice()).into()),
+                0,
+            ),
+        );
+
+        db.commit(HashMap::from_iter([
+            (self.implementation, implementation_revm_account),
+            (self.address, current_contract_revm_account),
+        ]));
+
+        Ok(())
+    }
+}
+
+/// Overwrites the code of `address` at `offset` with `value`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct BytecodePatch {
+    /// The patched contract.
+    pub address: Address,
+    /// Byte offset into the code.
+    pub offset: usize,
+    /// Bytes written at `offset`.
+    pub value: Bytes,
+    /// Expected code hashes of `address` before and after the patch.
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "CodeHashCheck::is_empty"))]
+    pub expected: CodeHashCheck,
+}
+
+impl BytecodePatch {
+    fn apply<DB>(&self, db: &mut DB) -> Result<(), TransitionError<DB::Error>>
+    where
+        DB: Database + DatabaseCommit,
+    {
+        info!(target: "evm", "Doing bytecode migration of account {}", self.address);
+
+        let mut acc = load_patched_account(db, self.address)?;
+        self.expected.check_pre(self.address, acc.code_hash)?;
+        let mut code = get_contract_code(db, &acc);
+
+        write_code(self.address, &mut code, self.offset, &self.value)?;
+
+        acc.code = Some(Bytecode::new_raw(code.into()));
+        acc.code_hash = acc.code.as_ref().map_or(KECCAK256_EMPTY, |c| c.hash_slow());
+        self.expected.check_post(self.address, acc.code_hash)?;
+        let mut revm_acc: Account = acc.into();
+        revm_acc.mark_touch();
+
+        db.commit(HashMap::from_iter([(self.address, revm_acc)]));
+
+        Ok(())
+    }
+}
+
+/// Writes `value` into `slot` of `address`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(deny_unknown_fields))]
+pub struct StorageWrite {
+    /// The account whose storage is written.
+    pub address: Address,
+    /// The storage slot.
+    pub slot: B256,
+    /// The new slot value.
+    pub value: B256,
+}
+
+impl StorageWrite {
+    fn apply<DB>(&self, db: &mut DB) -> Result<(), TransitionError<DB::Error>>
+    where
+        DB: Database + DatabaseCommit,
+    {
+        info!(target: "evm", "Doing storage migration of account {}", self.address);
+
+        let mut acc: Account =
+            load_contract_account(db, self.address).map_err(TransitionError::Database)?.into();
+
+        acc.storage.insert(
+            self.slot.into(),
+            EvmStorageSlot::new_changed(U256::default(), self.value.into(), 0),
+        );
+
+        acc.mark_touch();
+
+        db.commit(HashMap::from_iter([(self.address, acc)]));
+
+        Ok(())
+    }
+}
+
+/// Sets the code of `address` to `code`, keeping its balance, nonce and storage.
+///
+/// Fails if `code` does not hash to `code_hash`. The `post` code hash of `expected` is checked
+/// against the hash of `code` as well.
+///
+/// When deserialized, `code_hash` defaults to the hash of `code`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "serde_repr::ForceDeploy"))]
+pub struct ForceDeploy {
+    /// The account receiving the code.
+    pub address: Address,
+    /// The hash of `code`.
+    pub code_hash: B256,
+    /// The deployed bytecode.
+    pub code: Bytes,
+    /// Expected code hashes of `address` before and after the deployment.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "CodeHashCheck::is_empty"))]
+    pub expected: CodeHashCheck,
+}
+
+impl ForceDeploy {
+    fn apply<DB>(&self, db: &mut DB) -> Result<(), TransitionError<DB::Error>>
+    where
+        DB: Database + DatabaseCommit,
+    {
+        info!(target: "evm", "Force-deploying code to {}", self.address);
+
+        // Load the account from the cache.
+        let mut acc_info =
+            load_contract_account(db, self.address).map_err(TransitionError::Database)?;
+        self.expected.check_pre(self.address, acc_info.code_hash)?;
+        let code_hash = keccak256(&self.code);
+        if code_hash != self.code_hash {
+            return Err(OpBlockExecutionError::MigrationPostStateMismatch {
+                address: self.address,
+                expected: self.code_hash,
+                got: code_hash,
+            }
+            .into());
+        }
+        self.expected.check_post(self.address, code_hash)?;
+
+        // Update the account info with the new codehash and bytecode.
+        acc_info.code_hash = code_hash;
+        acc_info.code = Some(Bytecode::new_raw(self.code.clone()));
+
+        // Convert the cache account back into a revm account and mark it as touched.
+        let mut revm_acc: Account = acc_info.into();
+        revm_acc.mark_touch();
+
+        // Commit the account to the database.
+        db.commit(HashMap::from_iter([(self.address, revm_acc)]));
+
+        Ok(())
+    }
+}
+
+/// Loads the account whose code is about to be patched, which must exist.
+fn load_patched_account<DB>(
+    db: &mut DB,
+    address: Address,
+) -> Result<AccountInfo, TransitionError<DB::Error>>
+where
+    DB: Database,
+{
+    db.basic(address)
+        .map_err(TransitionError::Database)?
+        .ok_or_else(|| OpBlockExecutionError::MigrationAccountMissing { address }.into())
+}
+
+/// Writes `value` into `code` at `offset`, failing if it does not fit.
+fn write_code(
+    address: Address,
+    code: &mut [u8],
+    offset: usize,
+    value: &[u8],
+) -> Result<(), OpBlockExecutionError> {
+    let len = value.len();
+    offset
+        .checked_add(len)
+        .and_then(|end| code.get_mut(offset..end))
+        .ok_or(OpBlockExecutionError::MigrationCodeTooShort { address, offset, len })?
+        .copy_from_slice(value);
+    Ok(())
+}
+
+#[cfg(feature = "serde")]
+mod serde_repr {
+    //! Deserialization representations filling in defaults of optional fields.
+
+    use alloy_primitives::{Address, B256, Bytes, keccak256};
+
+    use super::{CodeHashCheck, CodePatch};
+
+    #[derive(serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub(super) struct ProxyUpgrade {
+        address: Address,
+        implementation: Option<Address>,
+        proxy_source: Option<Address>,
+        admin: Option<Address>,
+        #[serde(default)]
+        implementation_patches: Vec<CodePatch>,
+        #[serde(default)]
+        expected: CodeHashCheck,
+    }
+
+    impl From<ProxyUpgrade> for super::ProxyUpgrade {
+        fn from(repr: ProxyUpgrade) -> Self {
+            let ProxyUpgrade {
+                address,
+                implementation,
+                proxy_source,
+                admin,
+                implementation_patches,
+                expected,
+            } = repr;
+            let defaults = Self::fraxtal(address);
+            Self {
+                address,
+                implementation: implementation.unwrap_or(defaults.implementation),
+                proxy_source: proxy_source.unwrap_or(defaults.proxy_source),
+                admin: admin.unwrap_or(defaults.admin),
+                implementation_patches,
+                expected,
+            }
+        }
+    }
+
+    #[derive(serde::Deserialize)]
+    #[serde(deny_unknown_fields)]
+    pub(super) struct ForceDeploy {
+        address: Address,
+        code_hash: Option<B256>,
+        code: Bytes,
+        #[serde(default)]
+        expected: CodeHashCheck,
+    }
+
+    impl From<ForceDeploy> for super::ForceDeploy {
+        fn from(repr: ForceDeploy) -> Self {
+            let ForceDeploy { address, code_hash, code, expected } = repr;
+            Self {
+                address,
+                code_hash: code_hash.unwrap_or_else(|| keccak256(&code)),
+                code,
+                expected,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::bytes;
+    use revm::{
+        Database as _,
+        database::{CacheDB, EmptyDB},
+    };
+
+    const TOKEN: Address = address!("fc00000000000000000000000000000000000001");
+
+    fn db_with_code(address: Address, code: Bytes) -> CacheDB<EmptyDB> {
+        let mut db = CacheDB::new(EmptyDB::default());
+        db.insert_account_info(address, AccountInfo::from_bytecode(Bytecode::new_raw(code)));
+        db
+    }
+
+    fn code_of(db: &mut CacheDB<EmptyDB>, address: Address) -> Bytes {
+        let account = db.basic(address).unwrap().unwrap_or_default();
+        account.code.map(|code| code.original_bytes()).unwrap_or_default()
+    }
+
+    #[test]
+    fn bytecode_patch_writes_code() {
+        let mut db = db_with_code(TOKEN, bytes!("00000000"));
+        let patch = BytecodePatch {
+            address: TOKEN,
+            offset: 1,
+            value: bytes!("ffff"),
+            expected: CodeHashCheck::new(
+                keccak256(bytes!("00000000")),
+                keccak256(bytes!("00ffff00")),
+            ),
+        };
+
+        IrregularStateTransition::from(patch).apply(&mut db).unwrap();
+        assert_eq!(code_of(&mut db, TOKEN), bytes!("00ffff00"));
+    }
+
+    #[test]
+    fn bytecode_patch_rejects_truncated_code() {
+        let mut db = db_with_code(TOKEN, bytes!("0000"));
+        let patch = BytecodePatch {
+            address: TOKEN,
+            offset: 1,
+            value: bytes!("ffff"),
+            expected: CodeHashCheck::NONE,
+        };
+
+        let err = IrregularStateTransition::from(patch).apply(&mut db).unwrap_err();
+        assert!(matches!(
+            err,
+            TransitionError::Migration(OpBlockExecutionError::MigrationCodeTooShort {
+                address,
+                offset: 1,
+                len: 2,
+            }) if address == TOKEN
+        ));
+        assert_eq!(code_of(&mut db, TOKEN), bytes!("0000"));
+    }
+
+    #[test]
+    fn bytecode_patch_rejects_offset_overflow() {
+        let mut db = db_with_code(TOKEN, bytes!("0000"));
+        let patch = BytecodePatch {
+            address: TOKEN,
+            offset: usize::MAX,
+            value: bytes!("ff"),
+            expected: CodeHashCheck::NONE,
+        };
+
+        let err = IrregularStateTransition::from(patch).apply(&mut db).unwrap_err();
+        assert!(matches!(
+            err,
+            TransitionError::Migration(OpBlockExecutionError::MigrationCodeTooShort { .. })
+        ));
+    }
+
+    #[test]
+    fn bytecode_patch_rejects_missing_account() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let patch = BytecodePatch {
+            address: TOKEN,
+            offset: 0,
+            value: bytes!("ff"),
+            expected: CodeHashCheck::NONE,
+        };
+
+        let err = IrregularStateTransition::from(patch).apply(&mut db).unwrap_err();
+        assert!(matches!(
+            err,
+            TransitionError::Migration(OpBlockExecutionError::MigrationAccountMissing { address })
+                if address == TOKEN
+        ));
+    }
+
+    #[test]
+    fn bytecode_patch_rejects_unexpected_code() {
+        let mut db = db_with_code(TOKEN, bytes!("0000"));
+        let expected = B256::repeat_byte(0x11);
+        let patch = BytecodePatch {
+            address: TOKEN,
+            offset: 0,
+            value: bytes!("ff"),
+            expected: CodeHashCheck { pre: Some(expected), post: None },
+        };
+
+        let err = IrregularStateTransition::from(patch).apply(&mut db).unwrap_err();
+        assert!(matches!(
+            err,
+            TransitionError::Migration(OpBlockExecutionError::MigrationPreStateMismatch {
+                address,
+                expected: e,
+                ..
+            }) if address == TOKEN && e == expected
+        ));
+        assert_eq!(code_of(&mut db, TOKEN), bytes!("0000"));
+    }
+
+    #[test]
+    fn force_deploy_rejects_mismatching_code_hash() {
+        let mut db = CacheDB::new(EmptyDB::default());
+        let deploy = ForceDeploy {
+            address: TOKEN,
+            code_hash: keccak256(bytes!("6000")),
+            code: bytes!("6001"),
+            expected: CodeHashCheck::NONE,
+        };
+
+        let err = IrregularStateTransition::from(deploy).apply(&mut db).unwrap_err();
+        assert!(matches!(
+            err,
+            TransitionError::Migration(OpBlockExecutionError::MigrationPostStateMismatch {
+                address,
+                got,
+                ..
+            }) if address == TOKEN && got == keccak256(bytes!("6001"))
+        ));
+        assert!(code_of(&mut db, TOKEN).is_empty());
+
+        let deploy = ForceDeploy {
+            address: TOKEN,
+            code_hash: keccak256(bytes!("6001")),
+            code: bytes!("6001"),
+            expected: CodeHashCheck::NONE,
+        };
+        IrregularStateTransition::from(deploy).apply(&mut db).unwrap();
+        assert_eq!(code_of(&mut db, TOKEN), bytes!("6001"));
+    }
+
+    #[test]
+    fn proxy_upgrade_rejects_truncated_code() {
+        let mut db = db_with_code(TOKEN, bytes!("00000000"));
+        db.insert_account_info(
+            PROXY_ADDR,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("60006000"))),
+        );
+        let upgrade =
+            ProxyUpgrade::fraxtal(TOKEN).with_implementation_patches(&[0, 3], bytes!("ffff"));
+
+        let err = IrregularStateTransition::from(upgrade).apply(&mut db).unwrap_err();
+        assert!(matches!(
+            err,
+            TransitionError::Migration(OpBlockExecutionError::MigrationCodeTooShort {
+                address,
+                offset: 3,
+                len: 2,
+            }) if address == TOKEN
+        ));
+        assert_eq!(code_of(&mut db, TOKEN), bytes!("00000000"));
+        assert!(code_of(&mut db, implementation_address(TOKEN)).is_empty());
+    }
+
+    #[test]
+    fn proxy_upgrade_moves_code() {
+        let mut db = db_with_code(TOKEN, bytes!("00000000"));
+        db.insert_account_info(
+            PROXY_ADDR,
+            AccountInfo::from_bytecode(Bytecode::new_raw(bytes!("60006000"))),
+        );
+        let upgrade =
+            ProxyUpgrade::fraxtal(TOKEN).with_implementation_patches(&[2], bytes!("ffff"));
+
+        IrregularStateTransition::from(upgrade).apply(&mut db).unwrap();
+        assert_eq!(code_of(&mut db, TOKEN), bytes!("60006000"));
+        assert_eq!(code_of(&mut db, implementation_address(TOKEN)), bytes!("0000ffff"));
+        assert_eq!(
+            db.storage(TOKEN, PROXY_IMPLEMENTATION_SLOT.into()).unwrap(),
+            U256::from_be_slice(implementation_address(TOKEN).as_slice())
+        );
+    }
+}
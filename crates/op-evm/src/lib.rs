@@ -22,7 +22,7 @@ use revm::{
 };
 
 pub mod block;
-pub use block::{FraxtalBlockExecutor, FraxtalBlockExecutorFactory};
+pub use block::{FraxtalBlockExecutionCtx, FraxtalBlockExecutor, FraxtalBlockExecutorFactory};
 
 /// OP EVM implementation.
 ///